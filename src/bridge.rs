@@ -0,0 +1,192 @@
+/*
+ * File: src/bridge.rs
+ * Date: 17.10.2026
+ * Author: MarkAtk
+ *
+ * MIT License
+ *
+ * Copyright (c) 2026 MarkAtk
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies
+ * of the Software, and to permit persons to whom the Software is furnished to do
+ * so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+
+use std::io;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use clap::{ArgMatches, SubCommand, Arg, App};
+
+use serial_unit_testing::serial::Serial;
+use crate::commands;
+use crate::status::CommandError;
+
+// default timeout in ms for polling each port
+const POLL_TIMEOUT: u64 = 5;
+
+pub fn run(matches: &ArgMatches) -> Result<(), CommandError> {
+    let (mut settings, port_name) = commands::get_serial_settings(matches)?;
+    let target_name = matches.value_of("target").unwrap();
+
+    let text_format = commands::get_text_format(matches);
+
+    // the timeout is the poll interval of each port, so it is kept short unless given explicitly
+    if matches.occurrences_of("timeout") == 0 {
+        settings.timeout = POLL_TIMEOUT;
+    }
+
+    let mut serial = commands::open_serial(matches, port_name, settings)?;
+    let mut target = commands::open_port(target_name, settings)?;
+
+    let running = Arc::new(AtomicBool::new(true));
+    let stop = running.clone();
+
+    // stop on enter, but keep running if standard input is closed
+    thread::spawn(move || {
+        let mut line = String::new();
+
+        if let Ok(length) = io::stdin().read_line(&mut line) {
+            if length > 0 {
+                stop.store(false, Ordering::SeqCst);
+            }
+        }
+    });
+
+    // log direction is the same as in recordings, written to (>) and read from (<) the device
+    println!("Bridging device {} and host {}, press enter to stop", port_name, target_name);
+
+    let (to_device, to_host) = forward_ports(&mut serial, &mut target, &running, |time, direction, data| {
+        println!("{}", commands::format_traffic(time, direction, data, text_format));
+    })?;
+
+    println!("Forwarded {} bytes to the device and {} bytes to the host", to_device, to_host);
+
+    Ok(())
+}
+
+pub fn command<'a>() -> App<'a, 'a> {
+    SubCommand::with_name("bridge")
+        .about("Forward data between a device and host serial port and log the traffic")
+        .args(commands::serial_arguments(false, true).as_slice())
+        .arg(Arg::with_name("target")
+            .help("Serial port of the host side (e.g. pty://[link] for a vendor tool), accepts the same names as port")
+            .required(true)
+            .takes_value(true))
+}
+
+// forward data in both directions until stopped, returns the amount of bytes written to the device and the host
+fn forward_ports<F>(device: &mut Serial, host: &mut Serial, running: &AtomicBool, mut log: F) -> Result<(usize, usize), String>
+    where F: FnMut(Duration, &str, &[u8]) {
+    let start = Instant::now();
+    let mut to_device = 0;
+    let mut to_host = 0;
+
+    while running.load(Ordering::SeqCst) {
+        to_device += forward(host, device, start, ">", &mut log)?;
+        to_host += forward(device, host, start, "<", &mut log)?;
+    }
+
+    Ok((to_device, to_host))
+}
+
+fn forward<F>(from: &mut Serial, to: &mut Serial, start: Instant, direction: &str, log: &mut F) -> Result<usize, String>
+    where F: FnMut(Duration, &str, &[u8]) {
+    let data = match from.read() {
+        Ok(bytes) => bytes.to_vec(),
+        Err(e) if e.is_timeout() => return Ok(0),
+        Err(e) => return Err(format!("Error reading from port: {}", e))
+    };
+
+    log(start.elapsed(), direction, &data);
+
+    let mut written = 0;
+
+    while written < data.len() {
+        match to.write_bytes(&data[written..]) {
+            Ok(count) => written += count,
+            Err(e) if e.is_timeout() => return Err("Serial connection timed out".to_string()),
+            Err(e) => return Err(format!("Error writing to port: {}", e))
+        };
+    }
+
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use serial_unit_testing::serial::settings::Settings;
+    use super::*;
+
+    #[test]
+    fn forwards_between_ports_until_stopped() {
+        let device_listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let host_listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let device_name = format!("tcp://{}", device_listener.local_addr().unwrap());
+        let host_name = format!("tcp://{}", host_listener.local_addr().unwrap());
+
+        let running = Arc::new(AtomicBool::new(true));
+        let flag = running.clone();
+
+        let bridge = thread::spawn(move || {
+            let settings = Settings {
+                timeout: POLL_TIMEOUT,
+                ..Settings::default()
+            };
+
+            let mut device = Serial::open_with_settings(&device_name, settings).unwrap();
+            let mut host = Serial::open_with_settings(&host_name, settings).unwrap();
+            let mut traffic: Vec<(String, Vec<u8>)> = Vec::new();
+
+            let result = forward_ports(&mut device, &mut host, &flag, |_, direction, data| {
+                match traffic.last_mut() {
+                    Some((last, bytes)) if last == direction => bytes.extend_from_slice(data),
+                    _ => traffic.push((direction.to_string(), data.to_vec()))
+                }
+            });
+
+            (result, traffic)
+        });
+
+        let (mut device_end, _) = device_listener.accept().unwrap();
+        let (mut host_end, _) = host_listener.accept().unwrap();
+        device_end.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+        host_end.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+
+        let mut buffer = [0; 4];
+
+        host_end.write_all(b"ping").unwrap();
+        device_end.read_exact(&mut buffer).unwrap();
+        assert_eq!(&buffer, b"ping");
+
+        device_end.write_all(b"pong").unwrap();
+        host_end.read_exact(&mut buffer).unwrap();
+        assert_eq!(&buffer, b"pong");
+
+        running.store(false, Ordering::SeqCst);
+
+        let (result, traffic) = bridge.join().unwrap();
+
+        assert_eq!(result, Ok((4, 4)));
+        assert_eq!(traffic, vec![(">".to_string(), b"ping".to_vec()), ("<".to_string(), b"pong".to_vec())]);
+    }
+}
@@ -26,19 +26,18 @@
  * SOFTWARE.
  */
 
+use std::time::Duration;
 use clap::{ArgMatches, SubCommand, Arg, App};
 use serial_unit_testing::utils;
-use serial_unit_testing::serial::{Serial, CheckSettings};
+use serial_unit_testing::serial::{CheckSettings, Framing, LengthPrefix};
 use crate::commands;
+use crate::status::{CommandError, ExitCode};
 use serial_unit_testing::utils::TextFormat;
 
-pub fn run(matches: &ArgMatches) -> Result<(), String> {
-    let (settings, port_name) = commands::get_serial_settings(matches).unwrap();
+pub fn run(matches: &ArgMatches) -> Result<(), CommandError> {
+    let (settings, port_name) = commands::get_serial_settings(matches)?;
 
-    let mut serial = match Serial::open_with_settings(port_name, settings) {
-        Ok(serial) => serial,
-        Err(e) => return Err(format!("Error opening port {:?}", e))
-    };
+    let mut serial = commands::open_serial(matches, port_name, settings)?;
 
     let mut text = matches.value_of("text").unwrap().to_string();
     let response = matches.value_of("response").unwrap();
@@ -56,31 +55,65 @@ pub fn run(matches: &ArgMatches) -> Result<(), String> {
         text = utils::escape_text(text);
     }
 
+    let framing = get_framing(matches)?;
+
     let check_settings = CheckSettings {
         ignore_case,
         input_format,
-        output_format
+        output_format,
+        framing
     };
 
     let (result, actual_response) = match serial.check_with_settings(&text, response, &check_settings) {
         Ok((result, actual_response)) => (result, actual_response),
-        Err(e) if e.is_timeout() => return Err("Serial connection timed out".to_string()),
-        Err(e) => return Err(format!("Error running check {:?}", e))
+        Err(e) if e.is_timeout() => return Err(CommandError::new(ExitCode::Timeout, "Serial connection timed out".to_string())),
+        Err(e) => return Err(CommandError::from(format!("Error running check {}", e)))
     };
 
     if echo_text {
         println!("{}", text);
     }
 
-    if result {
-        println!("OK");
-    } else {
+    if !result {
         println!("Mismatch: '{}' does not match '{}'", response, actual_response);
+
+        return Err(CommandError::silent(ExitCode::TestFailure));
     }
 
+    println!("OK");
+
     Ok(())
 }
 
+fn get_framing(matches: &ArgMatches) -> Result<Option<Framing>, String> {
+    if let Some(until) = matches.value_of("until") {
+        return Ok(Some(Framing::Until(utils::escape_text(until.to_string()).into_bytes())));
+    }
+
+    if let Some(length) = matches.value_of("length") {
+        return match length.parse::<usize>() {
+            Ok(length) if length > 0 => Ok(Some(Framing::Length(length))),
+            _ => Err(format!("Invalid response length '{}'", length))
+        };
+    }
+
+    if let Some(prefix) = matches.value_of("lengthprefix") {
+        return match LengthPrefix::from_name(prefix) {
+            Some(prefix) => Ok(Some(Framing::LengthPrefix(prefix))),
+            None => Err(format!("Invalid length prefix '{}'", prefix))
+        };
+    }
+
+    if let Some(idle) = matches.value_of("idle") {
+        return match idle.parse::<u64>() {
+            Ok(duration) => Ok(Some(Framing::Idle(Duration::from_millis(duration)))),
+            Err(_) => Err(format!("Invalid idle time '{}'", idle))
+        };
+    }
+
+    Ok(None)
+}
+
 pub fn command<'a>() -> App<'a, 'a> {
     SubCommand::with_name("check")
         .about("Send data to serial port and check for correct response")
@@ -95,6 +128,29 @@ pub fn command<'a>() -> App<'a, 'a> {
             .long("ignore-case")
             .short("c")
             .help("Ignore response letter case while comparing"))
+        .arg(Arg::with_name("until")
+            .long("until")
+            .takes_value(true)
+            .value_name("TEXT")
+            .conflicts_with_all(&["length", "lengthprefix", "idle"])
+            .help("Read response until the given text was received, escape sequences are supported"))
+        .arg(Arg::with_name("length")
+            .long("length")
+            .takes_value(true)
+            .value_name("BYTES")
+            .conflicts_with_all(&["lengthprefix", "idle"])
+            .help("Read response with a fixed number of bytes"))
+        .arg(Arg::with_name("lengthprefix")
+            .long("length-prefix")
+            .takes_value(true)
+            .possible_values(&["u8", "u16be", "u16le", "u32be", "u32le"])
+            .conflicts_with("idle")
+            .help("Read response with a leading length field"))
+        .arg(Arg::with_name("idle")
+            .long("idle")
+            .takes_value(true)
+            .value_name("MS")
+            .help("Read response until the line was silent for the given time in milliseconds"))
         .arg(Arg::with_name("text")
             .help("Text send to the serial port")
             .takes_value(true)
@@ -28,8 +28,14 @@
 
 use clap::{Arg, ArgMatches};
 
+use std::fs;
+use std::time::Duration;
+
+use serial_unit_testing::serial::{Serial, Loopback, MockDevice, FaultInjector, FaultSettings, Recorder};
 use serial_unit_testing::serial::settings::{Settings, DataBits, FlowControl, Parity, StopBits};
+use serial_unit_testing::parser;
 use serial_unit_testing::utils;
+use crate::status::{CommandError, ExitCode};
 
 pub fn serial_arguments<'a>(multi_port: bool, modifier_arguments: bool) -> Vec<Arg<'a, 'a>> {
     let databits = [ "5", "6", "7", "8" ];
@@ -38,7 +44,7 @@ pub fn serial_arguments<'a>(multi_port: bool, modifier_arguments: bool) -> Vec<A
     let flowcontrols = [ "none", "software", "hardware" ];
 
     let mut args = vec![Arg::with_name("port")
-            .help("Serial port OS specific name")
+            .help("Serial port OS specific name, loopback, tcp://host:port, rfc2217://host:port, pty://[link] or replay://file")
             .required(true)
             .multiple(multi_port)
             .takes_value(true),
@@ -80,7 +86,47 @@ pub fn serial_arguments<'a>(multi_port: bool, modifier_arguments: bool) -> Vec<A
             .short("t")
             .help("Set serial port timeout duration")
             .takes_value(true)
-            .default_value("1000")
+            .default_value("1000"),
+        Arg::with_name("mock")
+            .long("mock")
+            .help("Answer on the loopback port with the mock device described in given rules file")
+            .takes_value(true),
+        Arg::with_name("record")
+            .long("record")
+            .help("Record the session into given file. Replay the recording as device with the port replay://<file>")
+            .takes_value(true),
+        Arg::with_name("faultseed")
+            .long("fault-seed")
+            .help("Seed for injected faults to reproduce a run")
+            .takes_value(true),
+        Arg::with_name("faultdrop")
+            .long("fault-drop")
+            .help("Rate (0 to 1) of received bytes being dropped")
+            .takes_value(true),
+        Arg::with_name("faultflip")
+            .long("fault-flip")
+            .help("Rate (0 to 1) of received bytes with a flipped bit")
+            .takes_value(true),
+        Arg::with_name("faultgarbage")
+            .long("fault-garbage")
+            .help("Rate (0 to 1) of random bytes inserted after received bytes")
+            .takes_value(true),
+        Arg::with_name("faultsplit")
+            .long("fault-split")
+            .help("Rate (0 to 1) of reads returning only part of the received data")
+            .takes_value(true),
+        Arg::with_name("faultcoalesce")
+            .long("fault-coalesce")
+            .help("Rate (0 to 1) of reads waiting for further data to return")
+            .takes_value(true),
+        Arg::with_name("faulttimeout")
+            .long("fault-timeout")
+            .help("Rate (0 to 1) of reads failing with a timeout")
+            .takes_value(true),
+        Arg::with_name("faultlatency")
+            .long("fault-latency")
+            .help("Latency in ms added to every read")
+            .takes_value(true)
     ];
 
     if modifier_arguments {
@@ -195,6 +241,151 @@ pub fn get_serial_settings<'a>(matches: &'a ArgMatches) -> Result<(Settings, &'a
     Ok((settings, port_name))
 }
 
+pub fn open_serial(matches: &ArgMatches, port_name: &str, settings: Settings) -> Result<Serial, CommandError> {
+    let mut ports = open_serial_ports(matches, &[port_name], settings)?;
+
+    Ok(ports.remove(0))
+}
+
+/// Open all given ports with the mock, record and fault options.
+///
+/// Further ports are recorded into the record file with the port index appended (e.g. `session.rec.1`) and faults are
+/// injected with the seed increased by the port index.
+pub fn open_serial_ports(matches: &ArgMatches, port_names: &[&str], settings: Settings) -> Result<Vec<Serial>, CommandError> {
+    let faults = get_fault_settings(matches)?;
+
+    // the seed is needed to reproduce the run
+    if let Some(faults) = faults {
+        println!("Injecting faults with seed {}", faults.seed);
+    }
+
+    let mut ports = Vec::new();
+
+    for (index, port_name) in port_names.iter().enumerate() {
+        let mut serial = open_device(matches, port_name, settings)?;
+
+        // record the actual device data without injected faults
+        if let Some(record_file) = matches.value_of("record") {
+            let record_file = if index == 0 {
+                record_file.to_string()
+            } else {
+                format!("{}.{}", record_file, index)
+            };
+
+            serial = match Recorder::create(serial.into_transport(), &record_file) {
+                Ok(recorder) => Serial::from_transport(Box::new(recorder)),
+                Err(e) => return Err(CommandError::from(format!("Unable to create recording: {}", e)))
+            };
+        }
+
+        if let Some(mut faults) = faults {
+            faults.seed = faults.seed.wrapping_add(index as u64);
+
+            serial = Serial::from_transport(Box::new(FaultInjector::new(serial.into_transport(), faults)));
+        }
+
+        ports.push(serial);
+    }
+
+    Ok(ports)
+}
+
+pub fn get_fault_settings(matches: &ArgMatches) -> Result<Option<FaultSettings>, String> {
+    let rates = [ "faultdrop", "faultflip", "faultgarbage", "faultsplit", "faultcoalesce", "faulttimeout", "faultlatency" ];
+
+    if !rates.iter().any(|name| matches.is_present(name)) {
+        return Ok(None);
+    }
+
+    let mut faults = match matches.value_of("faultseed") {
+        Some(seed) => match seed.parse::<u64>() {
+            Ok(seed) => FaultSettings::with_seed(seed),
+            Err(_) => return Err(format!("Invalid fault seed '{}'", seed))
+        },
+        None => FaultSettings::new()
+    };
+
+    faults.drop_rate = get_rate(matches, "faultdrop")?;
+    faults.flip_rate = get_rate(matches, "faultflip")?;
+    faults.garbage_rate = get_rate(matches, "faultgarbage")?;
+    faults.split_rate = get_rate(matches, "faultsplit")?;
+    faults.coalesce_rate = get_rate(matches, "faultcoalesce")?;
+    faults.timeout_rate = get_rate(matches, "faulttimeout")?;
+
+    if let Some(latency) = matches.value_of("faultlatency") {
+        if let Ok(duration) = latency.parse::<u64>() {
+            faults.latency = Duration::from_millis(duration);
+        } else {
+            return Err(format!("Invalid fault latency '{}'", latency));
+        }
+    }
+
+    Ok(Some(faults))
+}
+
+fn get_rate(matches: &ArgMatches, name: &str) -> Result<f64, String> {
+    let value = match matches.value_of(name) {
+        Some(value) => value,
+        None => return Ok(0.0)
+    };
+
+    match value.parse::<f64>() {
+        Ok(rate) if (0.0..=1.0).contains(&rate) => Ok(rate),
+        _ => Err(format!("Invalid fault rate '{}', value between 0 and 1 expected", value))
+    }
+}
+
+fn open_device(matches: &ArgMatches, port_name: &str, settings: Settings) -> Result<Serial, CommandError> {
+    if let Some(mock_file) = matches.value_of("mock") {
+        if port_name != "loopback" {
+            return Err(CommandError::from("Mock devices can only be used with the loopback port".to_string()));
+        }
+
+        let mut file = match fs::File::open(mock_file) {
+            Ok(file) => file,
+            Err(e) => return Err(CommandError::from(format!("Unable to open mock file: {}", e)))
+        };
+
+        let rules = match parser::parse_mock_file(&mut file) {
+            Ok(rules) => rules,
+            Err(e) => return Err(CommandError::new(ExitCode::ParseError, format!("Unable to parse mock file: {}", e)))
+        };
+
+        return Ok(Serial::from_transport(Box::new(Loopback::with_mock(settings, MockDevice::new(rules)))));
+    }
+
+    open_port(port_name, settings)
+}
+
+pub fn open_port(port_name: &str, settings: Settings) -> Result<Serial, CommandError> {
+    let serial = match Serial::open_with_settings(port_name, settings) {
+        Ok(serial) => serial,
+        Err(e) => return Err(CommandError::new(ExitCode::PortError, format!("Error opening port {}: {}", port_name, e)))
+    };
+
+    // other programs need to know where to connect to the pseudo terminal
+    if port_name.starts_with("pty://") {
+        if let Some(name) = serial.name() {
+            println!("Pseudo terminal opened at {}", name);
+        }
+    }
+
+    Ok(serial)
+}
+
+pub fn format_time(time: Duration) -> String {
+    format!("{:4}.{:06}", time.as_secs(), time.subsec_micros())
+}
+
+pub fn format_traffic(time: Duration, direction: &str, data: &[u8], text_format: utils::TextFormat) -> String {
+    let text = match text_format {
+        utils::TextFormat::Text => String::from_utf8_lossy(data).escape_debug().to_string(),
+        _ => utils::radix_string(data, &text_format).unwrap_or_default()
+    };
+
+    format!("{} {} {}", format_time(time), direction, text)
+}
+
 pub fn get_text_format(matches: &ArgMatches) -> utils::TextFormat {
     if matches.is_present("binary") {
         return utils::TextFormat::Binary;
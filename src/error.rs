@@ -42,6 +42,7 @@ pub enum Error {
     Serial(serialport::Error),
     Num(num::ParseIntError),
     Utf8(str::Utf8Error),
+    UnknownChecksum(String),
     Other
 }
 
@@ -58,6 +59,7 @@ impl fmt::Display for Error {
             Error::Serial(ref cause) => write!(f, "Serial Error: {}", cause),
             Error::Num(ref cause) => write!(f, "Byte Parse Error: {}", cause),
             Error::Utf8(ref cause) => write!(f, "String Parse Error: {}", cause),
+            Error::UnknownChecksum(ref name) => write!(f, "Unknown checksum '{}'", name),
             Error::Other => write!(f, "Unknown error")
         }
     }
@@ -70,6 +72,7 @@ impl error::Error for Error {
             Error::Serial(ref cause) => Some(cause),
             Error::Num(ref cause) => Some(cause),
             Error::Utf8(ref cause) => Some(cause),
+            Error::UnknownChecksum(_) => None,
             Error::Other => None
         }
     }
@@ -36,6 +36,6 @@ pub mod prelude {
     pub use crate::error::*;
     pub use crate::utils::{TextFormat, NewlineFormat};
     pub use crate::tests::*;
-    pub use crate::serial::{Serial, CheckSettings};
+    pub use crate::serial::{Serial, CheckSettings, Transport};
     pub use crate::serial::settings::Settings;
 }
@@ -28,12 +28,16 @@
 
 use clap::{ArgMatches, App, SubCommand, Arg};
 use serialport::{self, SerialPortType};
+use crate::status::CommandError;
 
-pub fn run(matches: &ArgMatches) -> Result<(), String> {
+pub fn run(matches: &ArgMatches) -> Result<(), CommandError> {
     let verbose = matches.is_present("verbose");
     let raw = matches.is_present("raw");
 
-    let ports = serialport::available_ports().unwrap();
+    let ports = match serialport::available_ports() {
+        Ok(ports) => ports,
+        Err(e) => return Err(CommandError::from(format!("Unable to list ports: {}", e)))
+    };
 
     for port in ports {
         if !verbose {
@@ -31,20 +31,27 @@ extern crate clap;
 
 extern crate serial_unit_testing;
 
+use std::process;
+
 use clap::{App, ArgMatches, AppSettings};
 
 mod commands;
+mod status;
 mod windows;
 
+use status::CommandError;
+
 mod list;
 mod send;
 mod monitor;
 mod check;
 mod run;
 mod verify;
+mod replay;
+mod bridge;
 mod version;
 
-fn run(matches: ArgMatches) -> Result<(), String> {
+fn run(matches: ArgMatches) -> Result<(), CommandError> {
     match matches.subcommand() {
         ("send", Some(m)) => send::run(m),
         ("list", Some(m)) => list::run(m),
@@ -52,6 +59,8 @@ fn run(matches: ArgMatches) -> Result<(), String> {
         ("check", Some(m)) => check::run(m),
         ("run", Some(m)) => run::run(m),
         ("verify", Some(m)) => verify::run(m),
+        ("replay", Some(m)) => replay::run(m),
+        ("bridge", Some(m)) => bridge::run(m),
         ("version", Some(m)) => version::run(m),
         _ => Ok(())
     }
@@ -70,10 +79,16 @@ fn main() {
         .subcommand(check::command())
         .subcommand(run::command())
         .subcommand(verify::command())
+        .subcommand(replay::command())
+        .subcommand(bridge::command())
         .subcommand(version::command())
         .get_matches();
 
     if let Err(e) = run(matches) {
-        println!("{}", e);
+        if let Some(message) = e.message {
+            eprintln!("{}", message);
+        }
+
+        process::exit(e.code as i32);
     }
 }
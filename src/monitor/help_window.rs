@@ -48,7 +48,7 @@ impl HelpEntry {
         }
     }
 
-    pub fn get_entry(&self, length: usize) -> [Text; 3] {
+    pub fn get_entry(&self, length: usize) -> [Text<'_>; 3] {
         [
             Text::raw(" ".repeat(length - self.key.len())),
             Text::styled(self.key.clone(), Style::default().bg(Color::Cyan)),
@@ -93,7 +93,7 @@ impl HelpWindow {
         })
     }
 
-    fn get_help_text_entries(help_entries: &[HelpEntry], skip: usize, count: usize) -> Vec<Text> {
+    fn get_help_text_entries(help_entries: &[HelpEntry], skip: usize, count: usize) -> Vec<Text<'_>> {
         let entries = help_entries
             .iter()
             .skip(skip)
@@ -184,15 +184,11 @@ impl Window for HelpWindow {
             KeyEvent { code: KeyCode::Esc, modifiers: _ } => {
                 self.should_close = true;
             },
-            KeyEvent { code: KeyCode::Left, modifiers: _ }  => {
-                if self.page > 0 {
-                    self.page -= 1;
-                }
+            KeyEvent { code: KeyCode::Left, modifiers: _ } if self.page > 0 => {
+                self.page -= 1;
             },
-            KeyEvent { code: KeyCode::Right, modifiers: _ } => {
-                if self.page < self.page_count - 1 {
-                    self.page += 1;
-                }
+            KeyEvent { code: KeyCode::Right, modifiers: _ } if self.page < self.page_count - 1 => {
+                self.page += 1;
             },
             _ => {}
         };
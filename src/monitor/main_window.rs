@@ -188,11 +188,11 @@ impl<'a> Window for MainWindow<'a> {
             let (output, line_counter) = text_storage.get_output_lines(chunks[0].height as usize - 1);
             let line_spaces = chunks[1].width as usize - line_counter.len() - 1;
 
-            let output_text = vec![
+            let output_text = [
                 Text::raw(output)
             ];
 
-            let line_text = vec![
+            let line_text = [
                 Text::raw(format!("{}{}", " ".repeat(line_spaces), line_counter))
             ];
 
@@ -32,8 +32,8 @@ use std::time::Duration;
 use clap::{ArgMatches, App, SubCommand};
 use crossterm::event::KeyEvent;
 use crate::commands;
+use crate::status::CommandError;
 use crate::windows::{WindowManager, Event, WindowError};
-use serial_unit_testing::serial::Serial;
 use serial_unit_testing::error::Error as SerialError;
 
 mod text_storage;
@@ -42,14 +42,14 @@ mod help_window;
 
 use main_window::MainWindow;
 
-pub fn run(matches: &ArgMatches) -> Result<(), String> {
+pub fn run(matches: &ArgMatches) -> Result<(), CommandError> {
     let (io_tx, io_rx) = mpsc::channel();
-    let (settings, port_name) = commands::get_serial_settings(matches).unwrap();
+    let (settings, port_name) = commands::get_serial_settings(matches)?;
 
     // create windows
     let mut window_manager = match WindowManager::new() {
         Ok(window_manager) => window_manager,
-        Err(e) => return Err(e.to_string())
+        Err(e) => return Err(CommandError::from(e.to_string()))
     };
 
     let ui_tx = window_manager.get_tx().clone();
@@ -61,13 +61,13 @@ pub fn run(matches: &ArgMatches) -> Result<(), String> {
     main_window.text_storage.output_format = commands::get_text_output_format(matches);
     main_window.text_storage.newline_format = commands::get_newline_format(matches);
     main_window.text_storage.escape_input = matches.is_present("escape");
-    main_window.title = format!("{}, {} ", port_name, settings.to_short_string());
 
     // open serial port
-    let mut serial = match Serial::open_with_settings(port_name, settings) {
-        Ok(serial) => serial,
-        Err(e) => return Err(format!("Unable to connect to port: {:?}", e.to_string()))
-    };
+    let mut serial = commands::open_serial(matches, port_name, settings)?;
+
+    // show actual device name as the port name may be virtual (e.g. pseudo terminals)
+    let name = serial.name().unwrap_or_else(|| port_name.to_string());
+    main_window.title = format!("{}, {} ", name, settings.to_short_string());
 
     // start thread for receiving from and sending to serial port
     {
@@ -102,7 +102,7 @@ pub fn run(matches: &ArgMatches) -> Result<(), String> {
                             };
                         }
                     },
-                    Err(e) if e == mpsc::TryRecvError::Empty => (),
+                    Err(mpsc::TryRecvError::Empty) => (),
                     Err(_) => {
                         show_error(&ui_tx, "I/O thread closed".to_string(), false);
 
@@ -115,7 +115,7 @@ pub fn run(matches: &ArgMatches) -> Result<(), String> {
 
     match window_manager.run(main_window) {
         Ok(_) => Ok(()),
-        Err(e) => Err(e.to_string())
+        Err(e) => Err(CommandError::from(e.to_string()))
     }
 }
 
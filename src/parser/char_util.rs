@@ -39,13 +39,25 @@ pub fn is_separator(ch: char) -> bool {
 }
 
 pub fn is_identifier(ch: char) -> bool {
-    ch.is_alphanumeric() || ch == '-' || ch == '_' || ch == ' ' || ch == '\t'
+    ch.is_alphanumeric() || ch == '-' || ch == '_' || ch == '.' || ch == ' ' || ch == '\t'
 }
 
 pub fn is_identifier_start(ch: char) -> bool {
     ch.is_alphanumeric()
 }
 
+pub fn is_port_start(ch: char) -> bool {
+    ch == '@'
+}
+
+pub fn is_port(ch: char) -> bool {
+    ch.is_alphanumeric() || ch == '-' || ch == '_'
+}
+
+pub fn is_control_start(ch: char) -> bool {
+    ch == '!'
+}
+
 pub fn is_content_start(ch: char) -> bool {
     ch == '"'
 }
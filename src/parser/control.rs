@@ -0,0 +1,100 @@
+/*
+ * File: src/parser/control.rs
+ * Date: 17.10.2026
+ * Author: MarkAtk
+ *
+ * MIT License
+ *
+ * Copyright (c) 2026 MarkAtk
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies
+ * of the Software, and to permit persons to whom the Software is furnished to do
+ * so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+
+use crate::tests::{ControlStep, ControlLine};
+use super::token::{Token, TokenType};
+use super::string_util;
+use super::error::Error;
+
+pub fn is_control_step(tokens: &[Token]) -> bool {
+    match tokens[0].token_type {
+        TokenType::Control => true,
+        // name and options of the step, e.g. (Reset) !pulse dtr 100ms
+        TokenType::LeftTestParenthesis => match tokens.iter().position(|token| token.token_type == TokenType::RightTestParenthesis) {
+            Some(index) => tokens.get(index + 1).is_some_and(|token| token.token_type == TokenType::Control),
+            None => false
+        },
+        _ => false
+    }
+}
+
+// dtr = low
+// cts = high
+// pulse rts 100ms
+// pulse dtr low 100ms
+// break 250ms
+pub fn analyse_control_step(control_token: &Token, tokens: &[Token]) -> Result<ControlStep, Error> {
+    let error = || Error::InvalidControlStep(control_token.line, control_token.column);
+
+    // identifiers contain whitespaces thus the step is parsed from its text
+    let mut text = String::new();
+
+    for token in tokens {
+        match token.token_type {
+            TokenType::Identifier | TokenType::OptionSeparator => {
+                text.push_str(&token.value);
+                text.push(' ');
+            },
+            _ => return Err(Error::InvalidControlStep(token.line, token.column))
+        }
+    }
+
+    if let Some((line, level)) = text.split_once('=') {
+        let line = ControlLine::from_name(line).ok_or_else(error)?;
+        let level = get_level(level).ok_or_else(error)?;
+
+        return if line.is_output() {
+            Ok(ControlStep::Set(line, level))
+        } else {
+            Ok(ControlStep::Check(line, level))
+        };
+    }
+
+    let words: Vec<&str> = text.split_whitespace().collect();
+
+    let (line, level, duration) = match words.as_slice() {
+        ["break", duration] => return string_util::get_time_value(duration).map(ControlStep::Break).ok_or_else(error),
+        ["pulse", line, duration] => (line, "high", duration),
+        ["pulse", line, level, duration] => (line, *level, duration),
+        _ => return Err(error())
+    };
+
+    match (ControlLine::from_name(line), get_level(level), string_util::get_time_value(duration)) {
+        (Some(line), Some(level), Some(duration)) if line.is_output() => Ok(ControlStep::Pulse(line, level, duration)),
+        _ => Err(error())
+    }
+}
+
+fn get_level(value: &str) -> Option<bool> {
+    match value.trim().to_lowercase().as_str() {
+        "high" | "on" | "1" | "true" => Some(true),
+        "low" | "off" | "0" | "false" => Some(false),
+        _ => None
+    }
+}
@@ -45,8 +45,25 @@ pub enum Error {
     InvalidLineStart(u32, u32),
     InvalidOptionValue(String, u32, u32),
     InvalidOutputContent(String, u32, u32),
+    InvalidContent(String, u32, u32),
     UnknownTestOption(String, u32, u32),
-    UnknownGroupOption(String, u32, u32)
+    UnknownGroupOption(String, u32, u32),
+    UnknownMockOption(String, u32, u32),
+    InvalidSectionOption(String, u32, u32),
+    InvalidSectionEnd(u32, u32),
+    UnexpectedSectionEnd(u32, u32),
+    MissingIncludeFile(u32, u32),
+    UnknownPort(String, u32, u32),
+    DuplicatePort(String, u32, u32),
+    IncludeFile(String, u32, u32),
+    IncludeCycle(String, u32, u32),
+    InvalidLoop(u32, u32),
+    InvalidControlStep(u32, u32),
+    TooManyLoopIterations(u64, u32, u32),
+    MissingLoopEnd(u32, u32),
+    UnexpectedLoopEnd(u32, u32),
+    InvalidCondition(String, u32, u32),
+    File(String, Box<Error>)
 }
 
 impl fmt::Display for Error {
@@ -66,8 +83,25 @@ impl fmt::Display for Error {
             Error::InvalidLineStart(line, column) => formatter.write_fmt(format_args!("Invalid line start at {}:{}", line, column)),
             Error::InvalidOptionValue(ref expected_type, line, column) => formatter.write_fmt(format_args!("Invalid option type at {}:{}. {} type expected", line, column, expected_type)),
             Error::InvalidOutputContent(ref content, line, column) => formatter.write_fmt(format_args!("Invalid output content '{}' at {}:{}", content, line, column)),
+            Error::InvalidContent(ref content, line, column) => formatter.write_fmt(format_args!("Invalid content '{}' at {}:{}", content, line, column)),
             Error::UnknownTestOption(ref name, line, column) => formatter.write_fmt(format_args!("Unknown test option '{}' at {}:{}", name, line, column)),
-            Error::UnknownGroupOption(ref name, line, column) => formatter.write_fmt(format_args!("Unknown group option '{}' at {}:{}", name, line, column))
+            Error::UnknownGroupOption(ref name, line, column) => formatter.write_fmt(format_args!("Unknown group option '{}' at {}:{}", name, line, column)),
+            Error::UnknownMockOption(ref name, line, column) => formatter.write_fmt(format_args!("Unknown mock option '{}' at {}:{}", name, line, column)),
+            Error::InvalidSectionOption(ref name, line, column) => formatter.write_fmt(format_args!("Group option '{}' is not allowed for setup and teardown at {}:{}", name, line, column)),
+            Error::InvalidSectionEnd(line, column) => formatter.write_fmt(format_args!("Invalid section end at {}:{}. Options are not allowed", line, column)),
+            Error::UnexpectedSectionEnd(line, column) => formatter.write_fmt(format_args!("End without section or group at {}:{}", line, column)),
+            Error::UnknownPort(ref name, line, column) => formatter.write_fmt(format_args!("Unknown port '@{}' at {}:{}", name, line, column)),
+            Error::DuplicatePort(ref name, line, column) => formatter.write_fmt(format_args!("Duplicate port '@{}' at {}:{}", name, line, column)),
+            Error::MissingIncludeFile(line, column) => formatter.write_fmt(format_args!("Missing include file at {}:{}", line, column)),
+            Error::IncludeFile(ref path, line, column) => formatter.write_fmt(format_args!("Unable to read included file '{}' at {}:{}", path, line, column)),
+            Error::IncludeCycle(ref path, line, column) => formatter.write_fmt(format_args!("Include cycle with file '{}' at {}:{}", path, line, column)),
+            Error::InvalidLoop(line, column) => formatter.write_fmt(format_args!("Invalid loop at {}:{}", line, column)),
+            Error::InvalidControlStep(line, column) => formatter.write_fmt(format_args!("Invalid control step at {}:{}", line, column)),
+            Error::TooManyLoopIterations(max, line, column) => formatter.write_fmt(format_args!("Loop with more than {} iterations at {}:{}", max, line, column)),
+            Error::MissingLoopEnd(line, column) => formatter.write_fmt(format_args!("Missing end of loop at {}:{}", line, column)),
+            Error::UnexpectedLoopEnd(line, column) => formatter.write_fmt(format_args!("End without loop at {}:{}", line, column)),
+            Error::InvalidCondition(ref message, line, column) => formatter.write_fmt(format_args!("Invalid condition at {}:{}. {}", line, column, message)),
+            Error::File(ref file, ref error) => formatter.write_fmt(format_args!("{} in file '{}'", error, file))
         }
     }
 }
@@ -89,8 +123,25 @@ impl StdError for Error {
             Error::InvalidLineStart(_, _) => "Invalid line start",
             Error::InvalidOptionValue(_, _, _) => "Invalid option value",
             Error::InvalidOutputContent(_, _, _) => "Invalid output content",
+            Error::InvalidContent(_, _, _) => "Invalid content",
             Error::UnknownTestOption(_, _, _) => "Unknown test option",
-            Error::UnknownGroupOption(_, _, _) => "Unknown group option"
+            Error::UnknownGroupOption(_, _, _) => "Unknown group option",
+            Error::UnknownMockOption(_, _, _) => "Unknown mock option",
+            Error::InvalidSectionOption(_, _, _) => "Invalid section option",
+            Error::InvalidSectionEnd(_, _) => "Invalid section end",
+            Error::UnexpectedSectionEnd(_, _) => "End without section or group",
+            Error::UnknownPort(_, _, _) => "Unknown port",
+            Error::DuplicatePort(_, _, _) => "Duplicate port",
+            Error::MissingIncludeFile(_, _) => "Missing include file",
+            Error::IncludeFile(_, _, _) => "Include file error",
+            Error::IncludeCycle(_, _, _) => "Include cycle",
+            Error::InvalidLoop(_, _) => "Invalid loop",
+            Error::InvalidControlStep(_, _) => "Invalid control step",
+            Error::TooManyLoopIterations(_, _, _) => "Too many loop iterations",
+            Error::MissingLoopEnd(_, _) => "Missing end of loop",
+            Error::UnexpectedLoopEnd(_, _) => "End without loop",
+            Error::InvalidCondition(_, _, _) => "Invalid condition",
+            Error::File(_, _) => "Error in file"
         }
     }
 }
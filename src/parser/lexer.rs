@@ -89,6 +89,14 @@ impl Lexer {
             return self.recognize_content();
         }
 
+        if char_util::is_port_start(ch) {
+            return self.recognize_port();
+        }
+
+        if char_util::is_control_start(ch) {
+            return self.recognize_control(ch);
+        }
+
         if char_util::is_parenthesis(ch) {
             return self.recognize_parenthesis(ch);
         }
@@ -157,6 +165,35 @@ impl Lexer {
         Token::new_with_value(TokenType::Identifier, identifier, self.line, column)
     }
 
+    fn recognize_port(&mut self) -> Token {
+        let mut port = String::new();
+
+        let column = self.column;
+
+        // skip port start
+        self.position += 1;
+        self.column += 1;
+
+        while self.position < self.input.len() {
+            let ch = self.input.chars().nth(self.position).unwrap();
+
+            if !char_util::is_port(ch) {
+                break;
+            }
+
+            port.push(ch);
+
+            self.position += 1;
+            self.column += 1;
+        }
+
+        if port.is_empty() {
+            return Token::new_with_value(TokenType::Illegal, "@".to_string(), self.line, column);
+        }
+
+        Token::new_with_value(TokenType::Port, port, self.line, column)
+    }
+
     fn recognize_content(&mut self) -> Token {
         let mut content = String::new();
         let mut escape_next_char = false;
@@ -224,6 +261,13 @@ impl Lexer {
         Token::new_with_value(TokenType::FormatSpecifier, ch.to_string(), self.line, self.column)
     }
 
+    fn recognize_control(&mut self, ch: char) -> Token {
+        self.column += 1;
+        self.position += 1;
+
+        Token::new_with_value(TokenType::Control, ch.to_string(), self.line, self.column)
+    }
+
     fn recognize_parenthesis(&mut self, ch: char) -> Token {
         self.column += 1;
         self.position += 1;
@@ -0,0 +1,188 @@
+/*
+ * File: src/parser/loops.rs
+ * Date: 17.10.2026
+ * Author: MarkAtk
+ *
+ * MIT License
+ *
+ * Copyright (c) 2026 MarkAtk
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies
+ * of the Software, and to permit persons to whom the Software is furnished to do
+ * so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+
+use crate::tests;
+use super::token::{Token, TokenType};
+use super::error::Error;
+
+// maximum number of iterations of a range, ranges are expanded while parsing
+const MAX_RANGE_ITERATIONS: u64 = 10000;
+
+/// Loop over ranges or tables of parameters.
+pub struct Loop {
+    /// Names of the parameters.
+    pub names: Vec<String>,
+    /// Values of the parameters for each iteration in order of the names.
+    pub iterations: Vec<Vec<String>>
+}
+
+pub fn is_loop_start(tokens: &[Token]) -> bool {
+    let first_token = &tokens[0];
+
+    first_token.token_type == TokenType::Identifier && first_token.value.split_whitespace().next() == Some("for")
+}
+
+pub fn is_loop_end(tokens: &[Token]) -> bool {
+    tokens.len() == 1 && tokens[0].token_type == TokenType::Identifier && tokens[0].value.trim() == "end"
+}
+
+// for ch in 0..16
+// for ch in 1, 5, "A"
+// for reg, value in (0x10, "01"), (0x11, "02")
+pub fn analyse_loop(tokens: &[Token]) -> Result<Loop, Error> {
+    let first_token = &tokens[0];
+    let error = || Error::InvalidLoop(first_token.line, first_token.column);
+
+    // identifiers contain whitespaces thus the loop is parsed from its text
+    let mut text = String::new();
+
+    for token in tokens {
+        match token.token_type {
+            TokenType::Content => {
+                text.push('"');
+                text.push_str(&token.value);
+                text.push('"');
+            },
+            TokenType::Identifier | TokenType::ContentSeparator | TokenType::LeftTestParenthesis | TokenType::RightTestParenthesis => text.push_str(&token.value),
+            _ => return Err(error())
+        };
+    }
+
+    let header = text.trim()[3..].trim_start();
+    let in_position = match header.find(" in ") {
+        Some(position) => position,
+        None => return Err(error())
+    };
+
+    let mut names: Vec<String> = Vec::new();
+
+    for name in header[..in_position].split(',').map(str::trim) {
+        if name.is_empty() || !name.chars().all(|ch| ch.is_ascii_alphanumeric() || ch == '_') || names.iter().any(|other| other == name) {
+            return Err(error());
+        }
+
+        names.push(name.to_string());
+    }
+
+    let values = header[in_position + 4..].trim();
+
+    let iterations = if let (1, Some((start, end))) = (names.len(), values.split_once("..")) {
+        match (tests::parse_number(start), tests::parse_number(end)) {
+            (Some(start), Some(end)) if start <= end && end - start > MAX_RANGE_ITERATIONS => {
+                return Err(Error::TooManyLoopIterations(MAX_RANGE_ITERATIONS, first_token.line, first_token.column));
+            },
+            (Some(start), Some(end)) if start <= end => (start..end).map(|value| vec!(value.to_string())).collect(),
+            _ => return Err(error())
+        }
+    } else {
+        let mut iterations = Vec::new();
+
+        for item in split_list(values).ok_or_else(error)? {
+            let row = if names.len() == 1 {
+                vec!(parse_value(item).ok_or_else(error)?)
+            } else {
+                let inner = item.strip_prefix('(').and_then(|item| item.strip_suffix(')')).ok_or_else(error)?;
+
+                split_list(inner).ok_or_else(error)?.into_iter().map(parse_value).collect::<Option<Vec<String>>>().ok_or_else(error)?
+            };
+
+            if row.len() != names.len() {
+                return Err(error());
+            }
+
+            iterations.push(row);
+        }
+
+        iterations
+    };
+
+    Ok(Loop {
+        names,
+        iterations
+    })
+}
+
+// split list at commas not being part of contents or parentheses
+fn split_list(text: &str) -> Option<Vec<&str>> {
+    let mut items = Vec::new();
+    let mut depth = 0;
+    let mut in_content = false;
+    let mut escaped = false;
+    let mut start = 0;
+
+    for (index, ch) in text.char_indices() {
+        if in_content {
+            if escaped {
+                escaped = false;
+            } else if ch == '\\' {
+                escaped = true;
+            } else if ch == '"' {
+                in_content = false;
+            }
+
+            continue;
+        }
+
+        match ch {
+            '"' => in_content = true,
+            '(' => depth += 1,
+            ')' if depth == 0 => return None,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                items.push(text[start..index].trim());
+
+                start = index + 1;
+            },
+            _ => ()
+        };
+    }
+
+    if depth != 0 || in_content {
+        return None;
+    }
+
+    items.push(text[start..].trim());
+
+    Some(items)
+}
+
+// values are either contents or identifiers
+fn parse_value(text: &str) -> Option<String> {
+    let text = text.trim();
+
+    if text.len() >= 2 && text.starts_with('"') && text.ends_with('"') {
+        return Some(text[1..text.len() - 1].to_string());
+    }
+
+    if text.is_empty() || text.contains('"') || text.contains('(') {
+        return None;
+    }
+
+    Some(text.to_string())
+}
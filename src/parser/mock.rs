@@ -0,0 +1,116 @@
+/*
+ * File: src/parser/mock.rs
+ * Date: 17.10.2026
+ * Author: MarkAtk
+ *
+ * MIT License
+ *
+ * Copyright (c) 2026 MarkAtk
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies
+ * of the Software, and to permit persons to whom the Software is furnished to do
+ * so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+
+use regex::bytes::Regex;
+use crate::serial::{MockRule, MockPattern};
+use crate::utils::{self, TextFormat};
+use super::token::{Token, TokenType};
+use super::error::Error;
+use super::finite_state_machine::FiniteStateMachine;
+use super::options::set_mock_option;
+use super::{check_test_line, analyse_options, get_text_format};
+
+pub fn analyse_mock_rule(tokens: &[Token], state_machine: &FiniteStateMachine) -> Result<MockRule, Error> {
+    check_test_line(tokens, state_machine)?;
+
+    // mock devices have a single port
+    if let Some(token) = tokens.iter().find(|token| token.token_type == TokenType::Port) {
+        return Err(Error::UnknownPort(token.value.clone(), token.line, token.column));
+    }
+
+    let mut rule = MockRule::new(MockPattern::Bytes(Vec::new()), Vec::new());
+    let mut regex = false;
+    let mut index = 0;
+
+    if tokens[index].token_type == TokenType::LeftTestParenthesis {
+        // rule names are only for documentation
+        if tokens[index + 1].token_type == TokenType::Identifier {
+            index += 1;
+        }
+
+        index += 1;
+
+        index += analyse_options(&tokens[index..], TokenType::RightTestParenthesis, |option| set_mock_option(option, &mut rule, &mut regex))?;
+    }
+
+    // mock rules always need a request and a response
+    if tokens[index].token_type == TokenType::DirectionSeparator {
+        return Err(Error::MissingContent("input".to_string(), tokens[index].line, tokens[index].column));
+    }
+
+    let mut input_format = TextFormat::Text;
+    if tokens[index].token_type == TokenType::FormatSpecifier {
+        input_format = get_text_format(&tokens[index])?;
+        index += 1;
+    }
+
+    let input = &tokens[index];
+
+    rule.pattern = if regex {
+        if input_format != TextFormat::Text {
+            return Err(Error::InvalidContent(input.value.clone(), input.line, input.column));
+        }
+
+        match Regex::new(&input.value) {
+            Ok(regex) => MockPattern::Regex(regex),
+            Err(_) => return Err(Error::InvalidContent(input.value.clone(), input.line, input.column))
+        }
+    } else {
+        MockPattern::Bytes(get_content_bytes(input, input_format)?)
+    };
+
+    if index + 1 >= tokens.len() {
+        return Err(Error::MissingDirectionSeparator(input.line, input.column));
+    }
+
+    // skip direction separator
+    index += 2;
+
+    let mut output_format = TextFormat::Text;
+    if tokens[index].token_type == TokenType::FormatSpecifier {
+        output_format = get_text_format(&tokens[index])?;
+        index += 1;
+    }
+
+    rule.response = get_content_bytes(&tokens[index], output_format)?;
+
+    Ok(rule)
+}
+
+fn get_content_bytes(token: &Token, format: TextFormat) -> Result<Vec<u8>, Error> {
+    let result = match format {
+        TextFormat::Text => Ok(utils::escape_text(token.value.clone()).into_bytes()),
+        _ => utils::bytes_from_format(&token.value, format)
+    };
+
+    match result {
+        Ok(bytes) => Ok(bytes),
+        Err(_) => Err(Error::InvalidContent(token.value.clone(), token.line, token.column))
+    }
+}
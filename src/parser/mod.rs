@@ -28,9 +28,11 @@
 
 use std::fs;
 use std::io::{BufReader, Read};
+use std::path::{Path, PathBuf};
 use regex::Regex;
-use crate::tests::{TestCase, TestSuite, TestCaseSettings, TestSuiteSettings};
-use crate::utils::TextFormat;
+use crate::tests::{self, TestCase, TestSuite, TestSuiteKind, TestCaseSettings, TestSuiteSettings};
+use crate::serial::MockRule;
+use crate::utils::{self, BytePattern, TextFormat};
 
 mod error;
 mod token;
@@ -39,24 +41,122 @@ mod char_util;
 mod lexer;
 mod finite_state_machine;
 mod options;
+mod mock;
+mod loops;
+mod control;
 
 use self::lexer::Lexer;
 use self::token::{Token, TokenType};
-use self::error::Error;
+pub use self::error::Error;
 use self::finite_state_machine::FiniteStateMachine;
-use self::options::{set_test_option, set_group_option};
+use self::options::{set_test_option, set_group_option, is_condition_option};
+use self::mock::analyse_mock_rule;
+use self::loops::{analyse_loop, is_loop_start, is_loop_end};
+use self::control::{analyse_control_step, is_control_step};
+
+/// Script with declared ports and test suites.
+#[derive(Debug)]
+pub struct Script {
+    /// Names of the declared ports in order of declaration.
+    pub ports: Vec<String>,
+    /// Test suites of the script.
+    pub test_suites: Vec<TestSuite>
+}
 
 /// Parse the given file for tests and test suites.
 ///
+/// Included files are resolved relative to the current working directory.
 /// A vector of test suites is returned on successful parsing, otherwise a parsing error is returned.
+#[deprecated(note = "Use parse_path to resolve includes relative to the script and get file names in errors")]
 pub fn parse_file(file: &mut fs::File) -> Result<Vec<TestSuite>, Error> {
+    #[allow(deprecated)]
     parse_file_with_default_settings(file, Default::default())
 }
 
 /// Parse the given file for tests and test suites with the given default settings.
 ///
+/// Included files are resolved relative to the current working directory.
 /// A vector of test suites is returned on successful parsing, otherwise a parsing error is returned.
+#[deprecated(note = "Use parse_path_with_default_settings to resolve includes relative to the script and get file names in errors")]
 pub fn parse_file_with_default_settings(file: &mut fs::File, default_test_settings: TestCaseSettings) -> Result<Vec<TestSuite>, Error> {
+    let tokens = read_tokens(file)?;
+
+    let mut lines = Vec::new();
+    expand_lines(tokens, None, &mut Vec::new(), &mut lines)?;
+
+    Ok(analyse_lines(lines, default_test_settings)?.test_suites)
+}
+
+/// Parse the script at the given path for tests and test suites.
+///
+/// Included files are resolved relative to the including file and errors carry the name of the file they occurred in.
+/// A vector of test suites is returned on successful parsing, otherwise a parsing error is returned.
+pub fn parse_path<P: AsRef<Path>>(path: P) -> Result<Vec<TestSuite>, Error> {
+    parse_path_with_default_settings(path, Default::default())
+}
+
+/// Parse the script at the given path for tests and test suites with the given default settings.
+///
+/// Included files are resolved relative to the including file and errors carry the name of the file they occurred in.
+/// A vector of test suites is returned on successful parsing, otherwise a parsing error is returned.
+pub fn parse_path_with_default_settings<P: AsRef<Path>>(path: P, default_test_settings: TestCaseSettings) -> Result<Vec<TestSuite>, Error> {
+    Ok(parse_script(path, default_test_settings)?.test_suites)
+}
+
+/// Parse the script at the given path for declared ports, tests and test suites with the given default settings.
+///
+/// Included files are resolved relative to the including file and errors carry the name of the file they occurred in.
+/// The script is returned on successful parsing, otherwise a parsing error is returned.
+pub fn parse_script<P: AsRef<Path>>(path: P, default_test_settings: TestCaseSettings) -> Result<Script, Error> {
+    let path = path.as_ref();
+
+    let mut file = match fs::File::open(path) {
+        Ok(file) => file,
+        Err(_) => return Err(Error::File(path.display().to_string(), Box::new(Error::ReadFile)))
+    };
+
+    let tokens = match read_tokens(&mut file) {
+        Ok(tokens) => tokens,
+        Err(err) => return Err(in_file(err, Some(path)))
+    };
+
+    let mut includes = Vec::new();
+
+    if let Ok(canonical_path) = path.canonicalize() {
+        includes.push(canonical_path);
+    }
+
+    let mut lines = Vec::new();
+    expand_lines(tokens, Some(path), &mut includes, &mut lines)?;
+
+    analyse_lines(lines, default_test_settings)
+}
+
+/// Parse the given file for mock device rules.
+///
+/// Rules use the same syntax as tests, the input is the request and the output the response of the mock device.
+/// A vector of rules is returned on successful parsing, otherwise a parsing error is returned.
+pub fn parse_mock_file(file: &mut fs::File) -> Result<Vec<MockRule>, Error> {
+    let tokens = read_tokens(file)?;
+    let lines = split_lines(tokens)?;
+
+    let state_machine = create_test_state_machine();
+    let mut rules = Vec::new();
+
+    for line in lines {
+        let first_token: &Token = line.first().unwrap();
+
+        if !is_test_line_start(first_token) {
+            return Err(Error::InvalidLineStart(first_token.line, first_token.column));
+        }
+
+        rules.push(analyse_mock_rule(&line, &state_machine)?);
+    }
+
+    Ok(rules)
+}
+
+fn read_tokens(file: &mut fs::File) -> Result<Vec<Token>, Error> {
     let mut reader = BufReader::new(file);
     let mut content = String::new();
 
@@ -65,12 +165,11 @@ pub fn parse_file_with_default_settings(file: &mut fs::File, default_test_settin
     }
 
     let mut lexer = Lexer::new(content);
-    let tokens = lexer.get_tokens();
 
-    analyse_tokens(tokens, default_test_settings)
+    Ok(lexer.get_tokens())
 }
 
-fn analyse_tokens(tokens: Vec<Token>, default_test_settings: TestCaseSettings) -> Result<Vec<TestSuite>, Error> {
+fn split_lines(tokens: Vec<Token>) -> Result<Vec<Vec<Token>>, Error> {
     let mut lines: Vec<Vec<Token>> = Vec::new();
     let mut line: Vec<Token> = Vec::new();
 
@@ -94,8 +193,157 @@ fn analyse_tokens(tokens: Vec<Token>, default_test_settings: TestCaseSettings) -
         line.push(token);
     }
 
+    Ok(lines)
+}
+
+#[derive(Clone)]
+struct ScriptLine {
+    file: Option<PathBuf>,
+    tokens: Vec<Token>,
+    // loop parameters the line was expanded with
+    parameters: Vec<(String, String)>
+}
+
+fn in_file(error: Error, file: Option<&Path>) -> Error {
+    match file {
+        Some(file) => Error::File(file.display().to_string(), Box::new(error)),
+        None => error
+    }
+}
+
+fn expand_lines(tokens: Vec<Token>, file: Option<&Path>, includes: &mut Vec<PathBuf>, lines: &mut Vec<ScriptLine>) -> Result<(), Error> {
+    let token_lines = match split_lines(tokens) {
+        Ok(token_lines) => token_lines,
+        Err(err) => return Err(in_file(err, file))
+    };
+
+    for line in token_lines {
+        let first_token: &Token = line.first().unwrap();
+
+        if first_token.token_type != TokenType::Identifier || first_token.value.trim() != "include" {
+            lines.push(ScriptLine {
+                file: file.map(|file| file.to_path_buf()),
+                tokens: line,
+                parameters: Vec::new()
+            });
+
+            continue;
+        }
+
+        // include "path"
+        if line.len() != 2 || line[1].token_type != TokenType::Content {
+            let token = line.get(1).unwrap_or(first_token);
+
+            return Err(in_file(Error::MissingIncludeFile(token.line, token.column), file));
+        }
+
+        let content_token = &line[1];
+        let path = match file.and_then(|file| file.parent()) {
+            Some(directory) => directory.join(&content_token.value),
+            None => PathBuf::from(&content_token.value)
+        };
+
+        let canonical_path = match path.canonicalize() {
+            Ok(canonical_path) => canonical_path,
+            Err(_) => return Err(in_file(Error::IncludeFile(path.display().to_string(), content_token.line, content_token.column), file))
+        };
+
+        if includes.contains(&canonical_path) {
+            return Err(in_file(Error::IncludeCycle(path.display().to_string(), content_token.line, content_token.column), file));
+        }
+
+        let tokens = match fs::File::open(&path).map_err(|_| Error::ReadFile).and_then(|mut include_file| read_tokens(&mut include_file)) {
+            Ok(tokens) => tokens,
+            Err(_) => return Err(in_file(Error::IncludeFile(path.display().to_string(), content_token.line, content_token.column), file))
+        };
+
+        includes.push(canonical_path);
+        expand_lines(tokens, Some(&path), includes, lines)?;
+        includes.pop();
+    }
+
+    Ok(())
+}
+
+// repeat the lines of loops for each iteration with the loop parameters substituted in contents
+fn expand_loops(lines: &[ScriptLine], parameters: &[(String, String)], expanded_lines: &mut Vec<ScriptLine>) -> Result<(), Error> {
+    let mut index = 0;
+
+    while index < lines.len() {
+        let script_line = &lines[index];
+        let file = script_line.file.as_deref();
+        let first_token = &script_line.tokens[0];
+
+        if is_loop_end(&script_line.tokens) {
+            return Err(in_file(Error::UnexpectedLoopEnd(first_token.line, first_token.column), file));
+        }
+
+        if !is_loop_start(&script_line.tokens) {
+            let mut expanded_line = script_line.clone();
+
+            for token in expanded_line.tokens.iter_mut().filter(|token| token.token_type == TokenType::Content) {
+                token.value = match tests::substitute_parameters(&token.value, parameters) {
+                    Ok(value) => value,
+                    Err(_) => return Err(in_file(Error::InvalidContent(token.value.clone(), token.line, token.column), file))
+                };
+            }
+
+            expanded_line.parameters.extend_from_slice(parameters);
+            expanded_lines.push(expanded_line);
+
+            index += 1;
+
+            continue;
+        }
+
+        let script_loop = match analyse_loop(&script_line.tokens) {
+            Ok(script_loop) => script_loop,
+            Err(err) => return Err(in_file(err, file))
+        };
+
+        // find end of loop skipping nested loops
+        let mut depth = 0;
+        let mut end = None;
+
+        for (offset, line) in lines[index + 1..].iter().enumerate() {
+            if is_loop_start(&line.tokens) {
+                depth += 1;
+            } else if is_loop_end(&line.tokens) {
+                if depth == 0 {
+                    end = Some(index + 1 + offset);
+
+                    break;
+                }
+
+                depth -= 1;
+            }
+        }
+
+        let end = match end {
+            Some(end) => end,
+            None => return Err(in_file(Error::MissingLoopEnd(first_token.line, first_token.column), file))
+        };
+
+        for values in script_loop.iterations {
+            let mut loop_parameters = parameters.to_vec();
+            loop_parameters.extend(script_loop.names.iter().cloned().zip(values));
+
+            expand_loops(&lines[index + 1..end], &loop_parameters, expanded_lines)?;
+        }
+
+        index = end + 1;
+    }
+
+    Ok(())
+}
+
+fn analyse_lines(lines: Vec<ScriptLine>, default_test_settings: TestCaseSettings) -> Result<Script, Error> {
+    let mut expanded_lines = Vec::new();
+    expand_loops(&lines, &[], &mut expanded_lines)?;
+
     // analyse each line
     let mut test_suites: Vec<TestSuite> = Vec::new();
+    let mut ports: Vec<String> = Vec::new();
 
     // <> mark optional tokens
     // / mark alternative tokens
@@ -110,72 +358,211 @@ fn analyse_tokens(tokens: Vec<Token>, default_test_settings: TestCaseSettings) -
             3 if token.token_type == TokenType::ContentSeparator => 5,
             5 if token.token_type == TokenType::Identifier => 6,
             6 if token.token_type == TokenType::OptionSeparator => 7,
-            7 if token.token_type == TokenType::Identifier => 3,
+            7 if token.token_type == TokenType::Identifier || token.token_type == TokenType::Content => 3,
             _ => 0
         }
     });
 
-    // <( Identifier <, Identifier < = Value> >* )> < <b/o/d/h>" Content ">* : < <b/o/d/h>" Content ">*
-    let test_state_machine = FiniteStateMachine::new(1, vec!(9), |state, token| -> u32 {
-        match state {
-            1 if token.token_type == TokenType::LeftTestParenthesis => 2,
-            1 if token.token_type == TokenType::FormatSpecifier => 5,
-            1 if token.token_type == TokenType::Content => 5,
-            2 if token.token_type == TokenType::Identifier => 3,
-            2 if token.token_type == TokenType::ContentSeparator => 10,
-            3 if token.token_type == TokenType::RightTestParenthesis => 4,
-            3 if token.token_type == TokenType::ContentSeparator => 10,
-            4 if token.token_type == TokenType::FormatSpecifier => 5,
-            4 if token.token_type == TokenType::Content => 6,
-            5 if token.token_type == TokenType::Content => 6,
-            6 if token.token_type == TokenType::DirectionSeparator => 7,
-            7 if token.token_type == TokenType::FormatSpecifier => 8,
-            7 if token.token_type == TokenType::Content => 9,
-            8 if token.token_type == TokenType::Content => 9,
-            10 if token.token_type == TokenType::Identifier => 11,
-            11 if token.token_type == TokenType::OptionSeparator => 12,
-            11 if token.token_type == TokenType::ContentSeparator => 10,
-            11 if token.token_type == TokenType::RightTestParenthesis => 4,
-            12 if token.token_type == TokenType::Identifier => 3,
-            _ => 0
-        }
-    });
+    let test_state_machine = create_test_state_machine();
 
-    for line in lines {
+    // script wide setup and teardown are kept apart to run them before and after all groups
+    let mut setup: Option<TestSuite> = None;
+    let mut teardown: Option<TestSuite> = None;
+    let mut section: Option<(TestSuiteKind, TestCaseSettings)> = None;
+    let mut in_group = false;
+    let mut ungrouped = false;
+
+    for script_line in expanded_lines {
+        let file = script_line.file.as_deref();
+        let line = script_line.tokens;
         let first_token: &Token = line.first().unwrap();
 
+        // port declaration
+        if first_token.token_type == TokenType::Port && line.len() == 1 {
+            if ports.contains(&first_token.value) {
+                return Err(in_file(Error::DuplicatePort(first_token.value.clone(), first_token.line, first_token.column), file));
+            }
+
+            ports.push(first_token.value.clone());
+
+            continue;
+        }
+
+        if first_token.token_type == TokenType::LeftGroupParenthesis && is_section_end(&line) {
+            // the end closes a setup or teardown section first and the group afterwards
+            if line.len() > 3 {
+                return Err(in_file(Error::InvalidSectionEnd(line[2].line, line[2].column), file));
+            } else if section.is_some() {
+                section = None;
+            } else if in_group {
+                in_group = false;
+            } else {
+                return Err(in_file(Error::UnexpectedSectionEnd(first_token.line, first_token.column), file));
+            }
+
+            continue;
+        }
+
         if first_token.token_type == TokenType::LeftGroupParenthesis {
-            match analyse_test_group(&line, &group_state_machine, default_test_settings.clone()) {
-                Ok(test_suite) => test_suites.push(test_suite),
-                Err(err) => return Err(err)
+            let kind = get_section_kind(&line);
+
+            // steps of group sections use the group settings
+            let group_test_settings = if kind != TestSuiteKind::Tests && in_group {
+                TestCaseSettings::default()
+            } else {
+                default_test_settings.clone()
+            };
+
+            let test_suite = match analyse_test_group(&line, &group_state_machine, kind, group_test_settings) {
+                Ok(test_suite) => test_suite,
+                Err(err) => return Err(in_file(err, file))
             };
 
+            if kind == TestSuiteKind::Tests {
+                test_suites.push(test_suite);
+
+                section = None;
+                in_group = true;
+                ungrouped = false;
+            } else {
+                section = Some((kind, test_suite.test_settings));
+            }
+
             continue;
         }
 
-        if first_token.token_type == TokenType::LeftTestParenthesis || first_token.token_type == TokenType::FormatSpecifier || first_token.token_type == TokenType::Content {
-            match analyse_test(&line, &test_state_machine) {
-                Ok(test) => {
-                    if test_suites.is_empty() {
+        if is_test_line_start(first_token) || first_token.token_type == TokenType::Control {
+            let result = if is_control_step(&line) {
+                analyse_control_test(&line, &test_state_machine, &ports, &script_line.parameters)
+            } else {
+                analyse_test(&line, &test_state_machine, &ports, &script_line.parameters)
+            };
+
+            let mut test = match result {
+                Ok(test) => test,
+                Err(err) => return Err(in_file(err, file))
+            };
+
+            match section {
+                Some((kind, ref section_test_settings)) => {
+                    test.settings.merge_weak(section_test_settings);
+                    test.settings.port.merge_weak(&section_test_settings.port);
+
+                    let test_suite: &mut TestSuite = if in_group {
+                        test_suites.last_mut().unwrap()
+                    } else {
+                        let script_section = if kind == TestSuiteKind::Setup { &mut setup } else { &mut teardown };
+
+                        script_section.get_or_insert_with(|| {
+                            let name = if kind == TestSuiteKind::Setup { "Setup" } else { "Teardown" };
+                            let mut test_suite = TestSuite::new(name.to_string());
+                            test_suite.kind = kind;
+
+                            test_suite
+                        })
+                    };
+
+                    if kind == TestSuiteKind::Setup {
+                        test_suite.push_setup(test);
+                    } else {
+                        test_suite.push_teardown(test);
+                    }
+                },
+                None => {
+                    // tests outside of groups are collected in an unnamed test suite
+                    if !in_group && !ungrouped {
                         test_suites.push(TestSuite::new(String::new()));
+                        ungrouped = true;
                     }
 
                     let test_suite: &mut TestSuite = test_suites.last_mut().unwrap();
                     test_suite.push(test);
                 }
-                Err(err) => return Err(err)
             };
 
             continue;
         }
 
-        return Err(Error::InvalidLineStart(first_token.line, first_token.column));
+        return Err(in_file(Error::InvalidLineStart(first_token.line, first_token.column), file));
+    }
+
+    if let Some(test_suite) = setup {
+        test_suites.insert(0, test_suite);
+    }
+
+    if let Some(test_suite) = teardown {
+        test_suites.push(test_suite);
+    }
+
+    Ok(Script {
+        ports,
+        test_suites
+    })
+}
+
+fn get_section_kind(tokens: &[Token]) -> TestSuiteKind {
+    match tokens.get(1) {
+        Some(token) if token.token_type == TokenType::Identifier => {
+            match token.value.trim().to_lowercase().as_str() {
+                "setup" => TestSuiteKind::Setup,
+                "teardown" => TestSuiteKind::Teardown,
+                _ => TestSuiteKind::Tests
+            }
+        },
+        _ => TestSuiteKind::Tests
     }
+}
 
-    Ok(test_suites)
+fn is_section_end(tokens: &[Token]) -> bool {
+    match tokens.get(1) {
+        Some(token) => token.token_type == TokenType::Identifier && token.value.trim().eq_ignore_ascii_case("end"),
+        None => false
+    }
 }
 
-fn analyse_test_group(tokens: &[Token], state_machine: &FiniteStateMachine, default_test_settings: TestCaseSettings) -> Result<TestSuite, Error> {
+fn is_test_line_start(token: &Token) -> bool {
+    matches!(token.token_type, TokenType::LeftTestParenthesis | TokenType::FormatSpecifier | TokenType::Content | TokenType::DirectionSeparator | TokenType::Port)
+}
+
+fn create_test_state_machine() -> FiniteStateMachine {
+    // <( Identifier <, Identifier < = Value> >* )> < <@Port> <b/o/d/h>" Content ">* < : < <@Port> <b/o/d/h>" Content ">* >
+    // either input or output can be omitted for send only and expect only tests
+    FiniteStateMachine::new(1, vec!(6, 9), |state, token| -> u32 {
+        match state {
+            1 if token.token_type == TokenType::LeftTestParenthesis => 2,
+            1 if token.token_type == TokenType::FormatSpecifier => 5,
+            1 if token.token_type == TokenType::Content => 6,
+            1 if token.token_type == TokenType::DirectionSeparator => 7,
+            1 if token.token_type == TokenType::Port => 13,
+            2 if token.token_type == TokenType::Identifier => 3,
+            2 if token.token_type == TokenType::ContentSeparator => 10,
+            3 if token.token_type == TokenType::RightTestParenthesis => 4,
+            3 if token.token_type == TokenType::ContentSeparator => 10,
+            4 if token.token_type == TokenType::FormatSpecifier => 5,
+            4 if token.token_type == TokenType::Content => 6,
+            4 if token.token_type == TokenType::DirectionSeparator => 7,
+            4 if token.token_type == TokenType::Port => 13,
+            5 if token.token_type == TokenType::Content => 6,
+            6 if token.token_type == TokenType::DirectionSeparator => 7,
+            7 if token.token_type == TokenType::FormatSpecifier => 8,
+            7 if token.token_type == TokenType::Content => 9,
+            7 if token.token_type == TokenType::Port => 14,
+            8 if token.token_type == TokenType::Content => 9,
+            10 if token.token_type == TokenType::Identifier => 11,
+            11 if token.token_type == TokenType::OptionSeparator => 12,
+            11 if token.token_type == TokenType::ContentSeparator => 10,
+            11 if token.token_type == TokenType::RightTestParenthesis => 4,
+            12 if token.token_type == TokenType::Identifier || token.token_type == TokenType::Content => 3,
+            13 if token.token_type == TokenType::FormatSpecifier => 5,
+            13 if token.token_type == TokenType::Content => 6,
+            14 if token.token_type == TokenType::FormatSpecifier => 8,
+            14 if token.token_type == TokenType::Content => 9,
+            _ => 0
+        }
+    })
+}
+
+fn analyse_test_group(tokens: &[Token], state_machine: &FiniteStateMachine, kind: TestSuiteKind, default_test_settings: TestCaseSettings) -> Result<TestSuite, Error> {
     let result = state_machine.run(tokens);
 
     if let Err((state, token)) = result {
@@ -202,71 +589,94 @@ fn analyse_test_group(tokens: &[Token], state_machine: &FiniteStateMachine, defa
     let mut settings = TestSuiteSettings::default();
     let mut test_settings = default_test_settings;
 
-    analyse_group_options(&tokens[index..], &mut settings, &mut test_settings)?;
+    analyse_group_options(&tokens[index..], kind, &mut settings, &mut test_settings)?;
 
     let test_suite = TestSuite::new_with_settings(name, settings, test_settings);
 
     Ok(test_suite)
 }
 
-fn analyse_test(tokens: &[Token], state_machine: &FiniteStateMachine) -> Result<TestCase, Error> {
-    let result = state_machine.run(tokens);
-
-    if let Err((state, token)) = result {
-        return match state {
-            2 => Err(Error::MissingTestIdentifier(token.line, token.column)),
-            3 => Err(Error::MissingClosingParenthesis(")".to_string(), token.line, token.column)),
-            4 | 5 => Err(Error::MissingContent("input".to_string(), token.line, token.column)),
-            6 => Err(Error::MissingDirectionSeparator(token.line, token.column)),
-            7 | 8 => Err(Error::MissingContent("output".to_string(), token.line, token.column)),
-            10 => Err(Error::MissingOptionIdentifier(token.line, token.column)),
-            11 => Err(Error::MissingOptionSeparator(token.line, token.column)),
-            12 => Err(Error::MissingOptionValue(token.line, token.column)),
-            _ => Err(Error::Unknown(token.line, token.column))
-        };
-    }
+fn analyse_test(tokens: &[Token], state_machine: &FiniteStateMachine, ports: &[String], parameters: &[(String, String)]) -> Result<TestCase, Error> {
+    check_test_line(tokens, state_machine)?;
 
     // create test case
-    let mut name = String::new();
     let mut settings = TestCaseSettings::default();
     let mut input_format: Option<TextFormat> = None;
     let mut output_format: Option<TextFormat> = None;
+    let mut input_port: Option<String> = None;
+    let mut output_port: Option<String> = None;
 
-    let mut index = 0;
+    let (name, mut index) = analyse_test_name(tokens, &mut settings)?;
 
-    if tokens[index].token_type == TokenType::LeftTestParenthesis {
-        if tokens[index + 1].token_type == TokenType::Identifier {
-            name = tokens[1].value.clone();
+    // expect only tests start with the direction separator
+    let mut input = None;
+
+    if tokens[index].token_type != TokenType::DirectionSeparator {
+        if tokens[index].token_type == TokenType::Port {
+            input_port = Some(get_port(&tokens[index], ports)?);
             index += 1;
         }
 
-        index += 1;
+        if tokens[index].token_type == TokenType::FormatSpecifier {
+            input_format = Some(get_text_format(&tokens[index])?);
+            index += 1;
+        }
 
-        index += analyse_test_options(&tokens[index..], &mut settings)?;
-    }
+        let content = tokens[index].value.clone();
+        if input_format.is_some() && utils::split_checksums(&content).is_err() {
+            return Err(Error::InvalidContent(content, tokens[index].line, tokens[index].column));
+        }
 
-    if tokens[index].token_type == TokenType::FormatSpecifier {
-        input_format = Some(get_text_format(&tokens[index])?);
+        input = Some(content);
         index += 1;
     }
 
-    let input = tokens[index].value.clone();
-
-    // skip direction separator
-    index += 2;
+    // send only tests end after the input
+    let mut output = None;
 
-    if tokens[index].token_type == TokenType::FormatSpecifier {
-        output_format = Some(get_text_format(&tokens[index])?);
+    if index < tokens.len() {
+        // skip direction separator
         index += 1;
-    }
 
-    let output = tokens[index].value.clone();
-    if Regex::new(&output).is_err() {
-        return Err(Error::InvalidOutputContent(output, tokens[index].line, tokens[index].column));
+        if tokens[index].token_type == TokenType::Port {
+            output_port = Some(get_port(&tokens[index], ports)?);
+            index += 1;
+        }
+
+        if tokens[index].token_type == TokenType::FormatSpecifier {
+            output_format = Some(get_text_format(&tokens[index])?);
+            index += 1;
+        }
+
+        let content = tokens[index].value.clone();
+        // variables are only known when running the test
+        let content_without_variables = tests::remove_variables(&content);
+        let format = output_format.unwrap_or(TextFormat::Text);
+
+        let valid = BytePattern::parse(&content_without_variables, format).is_some() || match tests::checksum_pattern(&content_without_variables, format) {
+            Ok((pattern, _)) => Regex::new(&pattern).is_ok(),
+            Err(_) => false
+        };
+
+        if !valid {
+            return Err(Error::InvalidOutputContent(content, tokens[index].line, tokens[index].column));
+        }
+
+        output = Some(content);
     }
 
-    let mut test = TestCase::new(name, input, output);
+    let name = parameter_name(name, parameters);
+
+    let mut test = match (input, output) {
+        (Some(input), Some(output)) => TestCase::new(name, input, output),
+        (Some(input), None) => TestCase::new_send(name, input),
+        (None, Some(output)) => TestCase::new_expect(name, output),
+        (None, None) => return Err(Error::MissingContent("input".to_string(), tokens[0].line, tokens[0].column))
+    };
+
     test.settings = settings;
+    test.input_port = input_port;
+    test.output_port = output_port;
 
     if let Some(format) = input_format {
         test.input_format = format;
@@ -279,44 +689,149 @@ fn analyse_test(tokens: &[Token], state_machine: &FiniteStateMachine) -> Result<
     Ok(test)
 }
 
-fn analyse_test_options(tokens: &[Token], settings: &mut TestCaseSettings) -> Result<usize, Error> {
+// (Name, option = value) !@port step
+fn analyse_control_test(tokens: &[Token], state_machine: &FiniteStateMachine, ports: &[String], parameters: &[(String, String)]) -> Result<TestCase, Error> {
+    let mut settings = TestCaseSettings::default();
+
+    if tokens[0].token_type == TokenType::LeftTestParenthesis {
+        let control_index = tokens.iter().position(|token| token.token_type == TokenType::Control).unwrap();
+
+        // name and options end after the closing parenthesis where tests continue with their input
+        match state_machine.run(&tokens[..control_index]) {
+            Err((4, _)) | Ok(()) => (),
+            Err((state, token)) => return Err(test_line_error(state, token))
+        };
+    }
+
+    let (name, mut index) = analyse_test_name(tokens, &mut settings)?;
+
+    let control_token = &tokens[index];
+    index += 1;
+
+    let mut port = None;
+
+    if index < tokens.len() && tokens[index].token_type == TokenType::Port {
+        port = Some(get_port(&tokens[index], ports)?);
+        index += 1;
+    }
+
+    let step = analyse_control_step(control_token, &tokens[index..])?;
+
+    let mut test = TestCase::new_control(parameter_name(name, parameters), step);
+    test.settings = settings;
+    test.input_port = port;
+
+    Ok(test)
+}
+
+// (Name, option = value)
+fn analyse_test_name(tokens: &[Token], settings: &mut TestCaseSettings) -> Result<(String, usize), Error> {
+    let mut name = String::new();
     let mut index = 0;
 
-    while tokens[index].token_type == TokenType::ContentSeparator {
-        // get length of option
-        let mut option_length = 1;
-        while tokens[index + option_length].token_type != TokenType::ContentSeparator && tokens[index + option_length].token_type != TokenType::RightTestParenthesis {
-            option_length += 1;
+    if tokens[index].token_type == TokenType::LeftTestParenthesis {
+        if tokens[index + 1].token_type == TokenType::Identifier {
+            name = tokens[1].value.clone();
+            index += 1;
         }
 
-        let offset = set_test_option(&tokens[index + 1 .. index + option_length], settings)?;
+        index += 1;
 
-        index += 2 + offset;
+        index += analyse_test_options(&tokens[index..], settings)?;
     }
 
-    Ok(index + 1)
+    Ok((name, index))
+}
+
+// tests of loops are named with their parameter values
+fn parameter_name(name: String, parameters: &[(String, String)]) -> String {
+    if parameters.is_empty() {
+        return name;
+    }
+
+    let values: Vec<String> = parameters.iter().map(|(name, value)| format!("{} = {}", name, value)).collect();
+
+    if name.trim().is_empty() {
+        format!("[{}]", values.join(", "))
+    } else {
+        format!("{} [{}]", name.trim_end(), values.join(", "))
+    }
+}
+
+fn get_port(token: &Token, ports: &[String]) -> Result<String, Error> {
+    if !ports.contains(&token.value) {
+        return Err(Error::UnknownPort(token.value.clone(), token.line, token.column));
+    }
+
+    Ok(token.value.clone())
+}
+
+fn check_test_line(tokens: &[Token], state_machine: &FiniteStateMachine) -> Result<(), Error> {
+    let result = state_machine.run(tokens);
+
+    if let Err((state, token)) = result {
+        return Err(test_line_error(state, token));
+    }
+
+    Ok(())
+}
+
+fn test_line_error(state: u32, token: &Token) -> Error {
+    match state {
+        2 => Error::MissingTestIdentifier(token.line, token.column),
+        3 => Error::MissingClosingParenthesis(")".to_string(), token.line, token.column),
+        4 | 5 | 13 => Error::MissingContent("input".to_string(), token.line, token.column),
+        6 => Error::MissingDirectionSeparator(token.line, token.column),
+        7 | 8 | 14 => Error::MissingContent("output".to_string(), token.line, token.column),
+        10 => Error::MissingOptionIdentifier(token.line, token.column),
+        11 => Error::MissingOptionSeparator(token.line, token.column),
+        12 => Error::MissingOptionValue(token.line, token.column),
+        _ => Error::Unknown(token.line, token.column)
+    }
 }
 
-fn analyse_group_options(tokens: &[Token], settings: &mut TestSuiteSettings, test_settings: &mut TestCaseSettings) -> Result<usize, Error> {
+fn analyse_test_options(tokens: &[Token], settings: &mut TestCaseSettings) -> Result<usize, Error> {
+    analyse_options(tokens, TokenType::RightTestParenthesis, |option| set_test_option(option, settings))
+}
+
+fn analyse_group_options(tokens: &[Token], kind: TestSuiteKind, settings: &mut TestSuiteSettings, test_settings: &mut TestCaseSettings) -> Result<usize, Error> {
+    analyse_options(tokens, TokenType::RightGroupParenthesis, |option| {
+        if is_condition_option(option) {
+            return set_suite_option(option, kind, settings);
+        }
+
+        // test for both group and test option
+        match set_test_option(option, test_settings) {
+            Ok(offset) => Ok(offset),
+            Err(Error::UnknownTestOption(_, _, _)) => set_suite_option(option, kind, settings),
+            Err(err) => Err(err)
+        }
+    })
+}
+
+fn set_suite_option(tokens: &[Token], kind: TestSuiteKind, settings: &mut TestSuiteSettings) -> Result<usize, Error> {
+    if kind == TestSuiteKind::Tests {
+        return set_group_option(tokens, settings);
+    }
+
+    // group options decide how the whole group is run, so they are not allowed for setup and teardown sections
+    set_group_option(tokens, &mut TestSuiteSettings::default())?;
+
+    Err(Error::InvalidSectionOption(tokens[0].value.trim().to_string(), tokens[0].line, tokens[0].column))
+}
+
+fn analyse_options<F>(tokens: &[Token], closing_parenthesis: TokenType, mut set_option: F) -> Result<usize, Error>
+    where F: FnMut(&[Token]) -> Result<usize, Error> {
     let mut index = 0;
 
     while tokens[index].token_type == TokenType::ContentSeparator {
         // get length of option
         let mut option_length = 1;
-        while tokens[index + option_length].token_type != TokenType::ContentSeparator && tokens[index + option_length].token_type != TokenType::RightGroupParenthesis {
+        while tokens[index + option_length].token_type != TokenType::ContentSeparator && tokens[index + option_length].token_type != closing_parenthesis {
             option_length += 1;
         }
 
-        // test for both group and test option
-        let offset = match set_test_option(&tokens[index + 1 .. index + option_length], test_settings) {
-            Ok(offset) => offset,
-            Err(err) => {
-                match err {
-                    Error::UnknownTestOption(_, _, _) => set_group_option(&tokens[index + 1 .. index + option_length], settings)?,
-                    _ => return Err(err)
-                }
-            },
-        };
+        let offset = set_option(&tokens[index + 1 .. index + option_length])?;
 
         index += 2 + offset;
     }
@@ -26,8 +26,11 @@
  * SOFTWARE.
  */
 
-use crate::tests::{TestCaseSettings, TestSuiteSettings};
-use super::token::{Token};
+use crate::tests::{TestCaseSettings, TestSuiteSettings, Condition};
+use crate::serial::settings::{DataBits, Parity, StopBits, FlowControl};
+use crate::serial::{MockRule, Framing, LengthPrefix};
+use crate::utils;
+use super::token::{Token, TokenType};
 use super::string_util;
 use super::error::Error;
 
@@ -38,6 +41,7 @@ pub fn set_test_option(tokens: &[Token], settings: &mut TestCaseSettings) -> Res
     match name {
         "ignore-case" => return parse_optional_boolean_option(tokens, &mut settings.ignore_case),
         "allow-failure" => return parse_optional_boolean_option(tokens, &mut settings.allow_failure),
+        "flush" => return parse_optional_boolean_option(tokens, &mut settings.flush),
         _ => ()
     };
 
@@ -67,6 +71,43 @@ pub fn set_test_option(tokens: &[Token], settings: &mut TestCaseSettings) -> Res
                 Err(Error::InvalidOptionValue("time".to_string(), tokens[2].line, tokens[2].column))
             }
         },
+        "retry" => {
+            if let Ok(count) = value.trim().parse::<u32>() {
+                settings.retry = Some(count);
+
+                Ok(2)
+            } else {
+                Err(Error::InvalidOptionValue("number".to_string(), tokens[2].line, tokens[2].column))
+            }
+        },
+        "retry-interval" | "retry-for" => {
+            let time = match string_util::get_time_value(&value) {
+                Some(time) => time,
+                None => return Err(Error::InvalidOptionValue("time".to_string(), tokens[2].line, tokens[2].column))
+            };
+
+            if name == "retry-interval" {
+                settings.retry_interval = Some(time);
+            } else {
+                settings.retry_for = Some(time);
+            }
+
+            Ok(2)
+        },
+        "max-latency" | "min-latency" | "inter-byte-gap" => {
+            let time = match string_util::get_time_value(&value) {
+                Some(time) => time,
+                None => return Err(Error::InvalidOptionValue("time".to_string(), tokens[2].line, tokens[2].column))
+            };
+
+            match name {
+                "max-latency" => settings.max_latency = Some(time),
+                "min-latency" => settings.min_latency = Some(time),
+                _ => settings.max_inter_byte_gap = Some(time)
+            };
+
+            Ok(2)
+        },
         "repeat" => {
             if let Ok(count) = value.parse::<u32>() {
                 settings.repeat = Some(count);
@@ -76,6 +117,104 @@ pub fn set_test_option(tokens: &[Token], settings: &mut TestCaseSettings) -> Res
                 Err(Error::InvalidOptionValue("number".to_string(), tokens[2].line, tokens[2].column))
             }
         },
+        "until" => {
+            // quoted delimiters may contain whitespace
+            let delimiter = match tokens[2].token_type {
+                TokenType::Content => value,
+                _ => value.trim().to_string()
+            };
+
+            if delimiter.is_empty() {
+                return Err(Error::InvalidOptionValue("text".to_string(), tokens[2].line, tokens[2].column));
+            }
+
+            settings.framing = Some(Framing::Until(utils::escape_text(delimiter).into_bytes()));
+
+            Ok(2)
+        },
+        "length" => {
+            match value.trim().parse::<usize>() {
+                Ok(length) if length > 0 => {
+                    settings.framing = Some(Framing::Length(length));
+
+                    Ok(2)
+                },
+                _ => Err(Error::InvalidOptionValue("number".to_string(), tokens[2].line, tokens[2].column))
+            }
+        },
+        "length-prefix" => {
+            if let Some(prefix) = LengthPrefix::from_name(&value) {
+                settings.framing = Some(Framing::LengthPrefix(prefix));
+
+                Ok(2)
+            } else {
+                Err(Error::InvalidOptionValue("length prefix".to_string(), tokens[2].line, tokens[2].column))
+            }
+        },
+        "idle" => {
+            if let Some(time) = string_util::get_time_value(&value) {
+                settings.framing = Some(Framing::Idle(time));
+
+                Ok(2)
+            } else {
+                Err(Error::InvalidOptionValue("time".to_string(), tokens[2].line, tokens[2].column))
+            }
+        },
+        "if" | "skip-if" => {
+            settings.condition = Some(get_condition(name, &tokens[2])?);
+
+            Ok(2)
+        },
+        "baud" => {
+            match value.trim().parse::<u32>() {
+                Ok(baud_rate) if baud_rate > 0 => {
+                    settings.port.baud_rate = Some(baud_rate);
+
+                    Ok(2)
+                },
+                _ => Err(Error::InvalidOptionValue("number".to_string(), tokens[2].line, tokens[2].column))
+            }
+        },
+        "databits" => {
+            settings.port.data_bits = match value.trim() {
+                "5" => Some(DataBits::Five),
+                "6" => Some(DataBits::Six),
+                "7" => Some(DataBits::Seven),
+                "8" => Some(DataBits::Eight),
+                _ => return Err(Error::InvalidOptionValue("data bits".to_string(), tokens[2].line, tokens[2].column))
+            };
+
+            Ok(2)
+        },
+        "parity" => {
+            settings.port.parity = match value.trim() {
+                "none" => Some(Parity::None),
+                "even" => Some(Parity::Even),
+                "odd" => Some(Parity::Odd),
+                _ => return Err(Error::InvalidOptionValue("parity".to_string(), tokens[2].line, tokens[2].column))
+            };
+
+            Ok(2)
+        },
+        "stopbits" => {
+            settings.port.stop_bits = match value.trim() {
+                "1" => Some(StopBits::One),
+                "2" => Some(StopBits::Two),
+                _ => return Err(Error::InvalidOptionValue("stop bits".to_string(), tokens[2].line, tokens[2].column))
+            };
+
+            Ok(2)
+        },
+        "flowcontrol" => {
+            settings.port.flow_control = match value.trim() {
+                "none" => Some(FlowControl::None),
+                "software" => Some(FlowControl::Software),
+                "hardware" => Some(FlowControl::Hardware),
+                _ => return Err(Error::InvalidOptionValue("flow control".to_string(), tokens[2].line, tokens[2].column))
+            };
+
+            Ok(2)
+        },
         _ => Err(Error::UnknownTestOption(name.to_string(), tokens[0].line, tokens[0].column))
     }
 }
@@ -95,7 +234,77 @@ pub fn set_group_option(tokens: &[Token], settings: &mut TestSuiteSettings) -> R
         return Err(Error::MissingOptionValue(tokens[0].line, tokens[0].column));
     }
 
-    Err(Error::UnknownGroupOption(name.to_string(), tokens[0].line, tokens[0].column))
+    match name {
+        "if" | "skip-if" => {
+            settings.condition = Some(get_condition(name, &tokens[2])?);
+
+            Ok(2)
+        },
+        _ => Err(Error::UnknownGroupOption(name.to_string(), tokens[0].line, tokens[0].column))
+    }
+}
+
+/// Check if the option is a condition, conditions of groups decide if the whole group is run.
+pub fn is_condition_option(tokens: &[Token]) -> bool {
+    matches!(tokens[0].value.trim(), "if" | "skip-if")
+}
+
+fn get_condition(name: &str, token: &Token) -> Result<Condition, Error> {
+    let condition = if name == "skip-if" {
+        Condition::new_skip_if(&token.value)
+    } else {
+        Condition::new_if(&token.value)
+    };
+
+    condition.map_err(|err| Error::InvalidCondition(err, token.line, token.column))
+}
+
+pub fn set_mock_option(tokens: &[Token], rule: &mut MockRule, regex: &mut bool) -> Result<usize, Error> {
+    let name = tokens[0].value.trim();
+
+    // options with implicit value
+    if name == "regex" {
+        return parse_boolean_option(tokens, regex);
+    }
+
+    // options with explicit value
+    if tokens.len() < 3 {
+        return Err(Error::MissingOptionValue(tokens[0].line, tokens[0].column));
+    }
+
+    let value = tokens[2].value.clone();
+
+    match name {
+        "delay" => {
+            if let Some(time) = string_util::get_time_value(&value) {
+                rule.delay = time;
+
+                Ok(2)
+            } else {
+                Err(Error::InvalidOptionValue("time".to_string(), tokens[2].line, tokens[2].column))
+            }
+        },
+        "chunk-size" => {
+            match value.parse::<usize>() {
+                Ok(size) if size > 0 => {
+                    rule.chunk_size = Some(size);
+
+                    Ok(2)
+                },
+                _ => Err(Error::InvalidOptionValue("number".to_string(), tokens[2].line, tokens[2].column))
+            }
+        },
+        "chunk-delay" => {
+            if let Some(time) = string_util::get_time_value(&value) {
+                rule.chunk_delay = time;
+
+                Ok(2)
+            } else {
+                Err(Error::InvalidOptionValue("time".to_string(), tokens[2].line, tokens[2].column))
+            }
+        },
+        _ => Err(Error::UnknownMockOption(name.to_string(), tokens[0].line, tokens[0].column))
+    }
 }
 
 fn parse_boolean_option(tokens: &[Token], option: &mut bool) -> Result<usize, Error> {
@@ -42,9 +42,9 @@ pub fn get_time_value(value: &str) -> Option<Duration> {
 
     // Get <time><unit> strings
     for ch in value.chars() {
-        if ch.is_digit(10) && unit_string.is_empty() {
+        if ch.is_ascii_digit() && unit_string.is_empty() {
             time_string.push(ch);
-        } else if !ch.is_digit(10) && !time_string.is_empty() {
+        } else if !ch.is_ascii_digit() && !time_string.is_empty() {
             unit_string.push(ch);
         } else {
             // Wrong format
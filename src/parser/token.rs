@@ -26,7 +26,7 @@
  * SOFTWARE.
  */
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum TokenType {
     Illegal,
     EndOfFile,
@@ -35,6 +35,8 @@ pub enum TokenType {
     FormatSpecifier,
     Identifier,
     Content,
+    Port,
+    Control,
 
     ContentSeparator,
     DirectionSeparator,
@@ -46,7 +48,7 @@ pub enum TokenType {
     RightTestParenthesis
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Token {
     pub token_type: TokenType,
     pub value: String,
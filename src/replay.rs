@@ -0,0 +1,74 @@
+/*
+ * File: src/replay.rs
+ * Date: 17.10.2026
+ * Author: MarkAtk
+ *
+ * MIT License
+ *
+ * Copyright (c) 2026 MarkAtk
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies
+ * of the Software, and to permit persons to whom the Software is furnished to do
+ * so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+
+use clap::{ArgMatches, SubCommand, Arg, App};
+
+use serial_unit_testing::serial::{Recording, RecordEvent};
+use crate::commands;
+use crate::status::CommandError;
+
+pub fn run(matches: &ArgMatches) -> Result<(), CommandError> {
+    let filename = matches.value_of("file").unwrap();
+
+    let recording = match Recording::load(filename) {
+        Ok(recording) => recording,
+        Err(e) => return Err(CommandError::from(format!("Unable to load recording: {}", e)))
+    };
+
+    let text_format = commands::get_text_format(matches);
+
+    for entry in recording.entries {
+        match entry.event {
+            RecordEvent::Settings(settings) => println!("{} settings {}", commands::format_time(entry.time), settings),
+            RecordEvent::Write(data) => println!("{}", commands::format_traffic(entry.time, ">", &data, text_format)),
+            RecordEvent::Read(data) => println!("{}", commands::format_traffic(entry.time, "<", &data, text_format))
+        };
+    }
+
+    Ok(())
+}
+
+pub fn command<'a>() -> App<'a, 'a> {
+    SubCommand::with_name("replay")
+        .about("Print a recorded session. Run scripts against a recording with the port replay://<file>")
+        .after_help("To replay a recording as device, open the port replay://<file> with any command, e.g. sut run script.sut replay://session.rec")
+        .arg(Arg::with_name("file")
+            .help("Recording to print")
+            .required(true)
+            .takes_value(true))
+        .arg(Arg::with_name("hex")
+            .long("hex")
+            .short("H")
+            .help("Set hexadecimal mode"))
+        .arg(Arg::with_name("binary")
+            .long("binary")
+            .short("B")
+            .help("Set binary mode")
+            .conflicts_with("hex"))
+}
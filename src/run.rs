@@ -30,53 +30,95 @@ use std::io;
 use std::fs::File;
 use clap::{ArgMatches, SubCommand, Arg, App};
 use colored::*;
-use serial_unit_testing::serial::Serial;
 use serial_unit_testing::parser;
-use serial_unit_testing::tests::TestCaseSettings;
+use serial_unit_testing::tests::{TestCaseSettings, TestSuiteKind, TestContext, ReportFormat, write_report};
 use crate::commands;
+use crate::status::{CommandError, ExitCode};
 
-pub fn run(matches: &ArgMatches) -> Result<(), String> {
+pub fn run(matches: &ArgMatches) -> Result<(), CommandError> {
     let filename = matches.value_of("file").unwrap();
 
-    let mut file = match File::open(filename) {
-        Ok(file) => file,
-        Err(ref e) if e.kind() == io::ErrorKind::NotFound => return Err("File not found".to_string()),
-        Err(e) => return Err(format!("{}", e))
+    match File::open(filename) {
+        Ok(_) => (),
+        Err(ref e) if e.kind() == io::ErrorKind::NotFound => return Err(CommandError::from("File not found".to_string())),
+        Err(e) => return Err(CommandError::from(format!("{}", e)))
     };
 
-    // open serial
-    let (settings, port_name) = commands::get_serial_settings(matches).unwrap();
-
-    let mut serial = match Serial::open_with_settings(port_name, settings) {
-        Ok(serial) => serial,
-        Err(e) => return Err(format!("Error opening port {:?}", e))
-    };
-
-    // parse and run tests
+    // parse tests
     let default_test_settings = TestCaseSettings {
         verbose: Some(matches.is_present("verbose")),
+        flush: if matches.is_present("flush") { Some(true) } else { None },
         ..Default::default()
     };
 
-    let test_suites = match parser::parse_file_with_default_settings(&mut file, default_test_settings) {
-        Ok(test_suites) => test_suites,
-        Err(e) => return Err(format!("Unable to parse file: {}", e))
+    let script = match parser::parse_script(filename, default_test_settings) {
+        Ok(script) => script,
+        Err(e) => return Err(CommandError::new(ExitCode::ParseError, format!("Unable to parse file: {}", e)))
     };
 
+    let mut test_suites = script.test_suites;
+
+    // declared ports are mapped to the given ports in order
+    let port_names: Vec<&str> = matches.values_of("port").unwrap().collect();
+
+    if script.ports.is_empty() && port_names.len() > 1 {
+        return Err(CommandError::new(ExitCode::PortError, format!("Script declares no ports but {} ports are given", port_names.len())));
+    }
+
+    if !script.ports.is_empty() && script.ports.len() != port_names.len() {
+        return Err(CommandError::new(ExitCode::PortError, format!("Script declares {} ports but {} ports are given", script.ports.len(), port_names.len())));
+    }
+
+    // open serial
+    let (settings, _) = commands::get_serial_settings(matches)?;
+
+    let mut ports = commands::open_serial_ports(matches, &port_names, settings)?;
+    let mut serial = ports.remove(0);
+
+    // run tests
+    let mut context = TestContext::new();
+
+    if let Some(definitions) = matches.values_of("define") {
+        for definition in definitions {
+            match definition.split_once('=') {
+                Some((name, value)) if !name.trim().is_empty() => context.set_variable(name.trim(), value.trim()),
+                _ => return Err(CommandError::from(format!("Invalid definition '{}', expected name=value", definition)))
+            };
+        }
+    }
+
+    if let Some(name) = script.ports.first() {
+        context.set_default_port(name);
+    }
+
+    for (name, port) in script.ports.iter().skip(1).zip(ports) {
+        context.add_port(name, port);
+    }
+
     let mut total_tests = 0;
     let mut successful_tests = 0;
     let mut failed_tests = 0;
+    let mut skipped_tests = 0;
+    let mut failed_steps = 0;
+    let mut timed_out = 0;
 
     let stop_on_failure = matches.is_present("stop");
     let quiet = matches.is_present("quiet");
 
-    for mut test_suite in test_suites {
+    // script wide teardown is run even after stopping
+    let mut stopped = false;
+
+    for test_suite in test_suites.iter_mut() {
+        if stopped && test_suite.kind != TestSuiteKind::Teardown {
+            continue;
+        }
+
         // only set stop_on_failure and never reset
         if stop_on_failure {
             test_suite.settings.stop_on_failure = stop_on_failure;
         }
 
-        let result = test_suite.run_and_print(&mut serial, quiet);
+        let result = test_suite.run_and_print_with_context(&mut serial, &mut context, quiet);
 
         let successful = test_suite.successful();
         let failed = test_suite.failed();
@@ -84,27 +126,70 @@ pub fn run(matches: &ArgMatches) -> Result<(), String> {
         total_tests += successful + failed;
         successful_tests += successful;
         failed_tests += failed;
+        skipped_tests += test_suite.skipped();
+        failed_steps += test_suite.failed_steps();
+        timed_out += test_suite.timed_out();
 
         if !quiet {
             println!();
         }
 
+        if test_suite.kind == TestSuiteKind::Setup && test_suite.is_setup_failed() {
+            println!("Stopping because setup failed");
+
+            stopped = true;
+
+            continue;
+        }
+
         if !result && test_suite.settings.stop_on_failure {
             // global stop on failure?
             if stop_on_failure {
                 println!("Stopping because 'stop-on-failure' is set");
 
-                break;
-            } else {
+                stopped = true;
+            } else if !test_suite.is_setup_failed() {
                 println!("Stopping group because 'stop-on-failure' is set\n");
-
-                continue;
             }
         }
     }
 
     if !quiet {
         println!("\nRan {} tests, {} successful, {} failed", total_tests.to_string().yellow(), successful_tests.to_string().green(), failed_tests.to_string().red());
+
+        if skipped_tests > 0 {
+            println!("{} tests skipped", skipped_tests.to_string().yellow());
+        }
+
+        if failed_steps > 0 {
+            println!("{} setup or teardown steps failed", failed_steps.to_string().red());
+        }
+    }
+
+    if let Some(report_file) = matches.value_of("reportfile") {
+        let format = match matches.value_of("report") {
+            Some("tap") => ReportFormat::Tap,
+            Some("json") => ReportFormat::Json,
+            _ => ReportFormat::JUnit
+        };
+
+        let mut file = match File::create(report_file) {
+            Ok(file) => file,
+            Err(e) => return Err(CommandError::from(format!("Unable to create report file: {}", e)))
+        };
+
+        if let Err(e) = write_report(&mut file, format, &test_suites) {
+            return Err(CommandError::from(format!("Unable to write report: {}", e)));
+        }
+    }
+
+    // only report a timeout if no test failed because of its response
+    if failed_tests + failed_steps > 0 && failed_tests + failed_steps == timed_out {
+        return Err(CommandError::silent(ExitCode::Timeout));
+    }
+
+    if failed_tests > 0 || failed_steps > 0 {
+        return Err(CommandError::silent(ExitCode::TestFailure));
     }
 
     Ok(())
@@ -122,6 +207,9 @@ pub fn command<'a>() -> App<'a, 'a> {
             .long("stop-on-failure")
             .short("S")
             .help("Stop on first test failing"))
+        .arg(Arg::with_name("flush")
+            .long("flush")
+            .help("Discard received data before writing the input of every test"))
         .arg(Arg::with_name("verbose")
             .long("verbose")
             .short("v")
@@ -130,4 +218,21 @@ pub fn command<'a>() -> App<'a, 'a> {
             .long("quiet")
             .short("q")
             .help("Only show failed tests"))
+        .arg(Arg::with_name("define")
+            .long("define")
+            .short("D")
+            .help("Define a variable used by the script, e.g. fw=2.1")
+            .takes_value(true)
+            .multiple(true)
+            .number_of_values(1))
+        .arg(Arg::with_name("report")
+            .long("report")
+            .help("Format of the test report written with --report-file, defaults to junit")
+            .takes_value(true)
+            .possible_values(&["junit", "tap", "json"])
+            .requires("reportfile"))
+        .arg(Arg::with_name("reportfile")
+            .long("report-file")
+            .help("Write a test report to given file")
+            .takes_value(true))
 }
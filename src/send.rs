@@ -31,17 +31,32 @@ use clap::{ArgMatches, SubCommand, Arg, App};
 use serial_unit_testing::utils;
 use serial_unit_testing::serial::Serial;
 use crate::commands;
+use crate::status::{CommandError, ExitCode};
 use serial_unit_testing::utils::TextFormat;
 
-pub fn run(matches: &ArgMatches) -> Result<(), String> {
-    let (settings, port_name) = commands::get_serial_settings(matches).unwrap();
+pub fn run(matches: &ArgMatches) -> Result<(), CommandError> {
+    let (settings, port_name) = commands::get_serial_settings(matches)?;
 
-    let mut serial = match Serial::open_with_settings(port_name, settings) {
-        Ok(serial) => serial,
-        Err(e) => return Err(format!("Error opening port {:?}", e))
+    let mut serial = commands::open_serial(matches, port_name, settings)?;
+
+    // control lines are set before sending, e.g. to reset the device
+    if let Some(level) = matches.value_of("rts") {
+        if let Err(e) = serial.write_request_to_send(level == "high") {
+            return Err(CommandError::from(format!("Error setting RTS {}", e)));
+        }
+    }
+
+    if let Some(level) = matches.value_of("dtr") {
+        if let Err(e) = serial.write_data_terminal_ready(level == "high") {
+            return Err(CommandError::from(format!("Error setting DTR {}", e)));
+        }
+    }
+
+    let mut text = match matches.value_of("text") {
+        Some(text) => text.to_string(),
+        None => return Ok(())
     };
 
-    let mut text = matches.value_of("text").unwrap().to_string();
     let echo_text = matches.is_present("echo");
 
     let input_text_format = commands::get_text_input_format(matches);
@@ -56,8 +71,8 @@ pub fn run(matches: &ArgMatches) -> Result<(), String> {
 
     match serial.write_format(&text, input_text_format) {
         Ok(_) => (),
-        Err(e) if e.is_timeout() => return Err("Serial connection timed out".to_string()),
-        Err(e) => return Err(format!("Error sending text {:?}", e))
+        Err(e) if e.is_timeout() => return Err(CommandError::new(ExitCode::Timeout, "Serial connection timed out".to_string())),
+        Err(e) => return Err(CommandError::from(format!("Error sending text {}", e)))
     };
 
     if echo_text {
@@ -65,7 +80,7 @@ pub fn run(matches: &ArgMatches) -> Result<(), String> {
     }
 
     if matches.is_present("response") {
-        read_response(&mut serial, &output_text_format).unwrap();
+        read_response(&mut serial, &output_text_format)?;
     }
 
     Ok(())
@@ -85,9 +100,19 @@ pub fn command<'a>() -> App<'a, 'a> {
             .long("show-response")
             .short("r")
             .help("Show response from device"))
+        .arg(Arg::with_name("rts")
+            .long("rts")
+            .takes_value(true)
+            .possible_values(&["high", "low"])
+            .help("Set the RTS control line before sending"))
+        .arg(Arg::with_name("dtr")
+            .long("dtr")
+            .takes_value(true)
+            .possible_values(&["high", "low"])
+            .help("Set the DTR control line before sending"))
         .arg(Arg::with_name("text")
-            .help("Text send to the serial port")
-            .required(true)
+            .help("Text send to the serial port, may be omitted if only control lines are set")
+            .required_unless_one(&["rts", "dtr"])
             .takes_value(true))
 }
 
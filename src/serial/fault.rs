@@ -0,0 +1,596 @@
+/*
+ * File: src/serial/fault.rs
+ * Date: 17.10.2026
+ * Author: MarkAtk
+ *
+ * MIT License
+ *
+ * Copyright (c) 2026 MarkAtk
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies
+ * of the Software, and to permit persons to whom the Software is furnished to do
+ * so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+
+use std::io;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use crate::error::Result;
+use super::settings::{Settings, DataBits, Parity, StopBits, FlowControl};
+use super::transport::Transport;
+
+// duration waited for further data when coalescing reads
+const COALESCE_TIMEOUT: Duration = Duration::from_millis(10);
+
+/// Faults injected by a `FaultInjector`.
+///
+/// Rates are probabilities between 0 and 1. Byte faults are applied per received byte, read faults per read.
+#[derive(Debug, Clone, Copy)]
+pub struct FaultSettings {
+    /// Seed of the random number generator. Equal seeds produce equal faults for equal data.
+    pub seed: u64,
+    /// Rate of received bytes being dropped.
+    pub drop_rate: f64,
+    /// Rate of received bytes with a single flipped bit.
+    pub flip_rate: f64,
+    /// Rate of random bytes inserted after a received byte.
+    pub garbage_rate: f64,
+    /// Rate of reads only returning part of the received data.
+    pub split_rate: f64,
+    /// Rate of reads waiting for further data and returning it together.
+    pub coalesce_rate: f64,
+    /// Rate of reads failing with a timeout without reading data.
+    pub timeout_rate: f64,
+    /// Latency added to every read receiving data.
+    pub latency: Duration
+}
+
+impl FaultSettings {
+    /// Create fault settings without any faults, seeded by the current time.
+    pub fn new() -> FaultSettings {
+        let seed = match SystemTime::now().duration_since(UNIX_EPOCH) {
+            Ok(duration) => duration.as_nanos() as u64,
+            Err(_) => 0
+        };
+
+        FaultSettings::with_seed(seed)
+    }
+
+    /// Create fault settings without any faults with the given seed.
+    pub fn with_seed(seed: u64) -> FaultSettings {
+        FaultSettings {
+            seed,
+            drop_rate: 0.0,
+            flip_rate: 0.0,
+            garbage_rate: 0.0,
+            split_rate: 0.0,
+            coalesce_rate: 0.0,
+            timeout_rate: 0.0,
+            latency: Duration::from_millis(0)
+        }
+    }
+}
+
+impl Default for FaultSettings {
+    fn default() -> FaultSettings {
+        FaultSettings::new()
+    }
+}
+
+// xorshift64* generator, good enough for faults and reproducible on every platform
+struct Random {
+    state: u64
+}
+
+impl Random {
+    fn new(seed: u64) -> Random {
+        // spread the seed with a splitmix64 step, the state must never be zero
+        let mut state = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        state = (state ^ (state >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        state = (state ^ (state >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        state ^= state >> 31;
+
+        if state == 0 {
+            state = 0x9E37_79B9_7F4A_7C15;
+        }
+
+        Random {
+            state
+        }
+    }
+
+    fn next(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+
+        self.state.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    fn below(&mut self, max: usize) -> usize {
+        (self.next() % max as u64) as usize
+    }
+
+    fn chance(&mut self, rate: f64) -> bool {
+        if rate <= 0.0 {
+            return false;
+        }
+
+        // use the upper 53 bits for a uniform value in [0, 1)
+        ((self.next() >> 11) as f64 / (1u64 << 53) as f64) < rate
+    }
+}
+
+/// Transport wrapper injecting faults into the received data of another transport.
+///
+/// The injector simulates flaky links to check host code against dropped bytes, flipped bits, garbage, split or coalesced reads,
+/// latency and timeouts. Written data is passed through unchanged. All faults are driven by a seedable random number generator,
+/// so runs can be reproduced.
+///
+/// # Example
+///
+/// ```
+/// use serial_unit_testing::serial::{Serial, Loopback, FaultInjector, FaultSettings};
+/// use serial_unit_testing::serial::settings::Settings;
+/// use serial_unit_testing::error::Result;
+///
+/// fn main() -> Result<()> {
+///     let mut faults = FaultSettings::with_seed(42);
+///     faults.split_rate = 1.0;
+///
+///     let loopback = Loopback::new(Settings::default());
+///     let mut serial = Serial::from_transport(Box::new(FaultInjector::new(Box::new(loopback), faults)));
+///
+///     // the response is received in multiple reads but still matches
+///     let (result, _) = serial.check("Hello World!", "Hello World!")?;
+///
+///     assert!(result);
+///
+///     Ok(())
+/// }
+/// ```
+pub struct FaultInjector {
+    transport: Box<dyn Transport>,
+    settings: FaultSettings,
+    random: Random,
+    buffer: Vec<u8>
+}
+
+impl FaultInjector {
+    /// Create a new fault injector wrapping the given transport.
+    pub fn new(transport: Box<dyn Transport>, settings: FaultSettings) -> FaultInjector {
+        FaultInjector {
+            transport,
+            settings,
+            random: Random::new(settings.seed),
+            buffer: Vec::new()
+        }
+    }
+
+    /// Get the fault settings.
+    pub fn fault_settings(&self) -> FaultSettings {
+        self.settings
+    }
+
+    /// Unwrap the injector and return the wrapped transport.
+    pub fn into_inner(self) -> Box<dyn Transport> {
+        self.transport
+    }
+
+    // read from the wrapped transport in the timeout duration and apply byte faults, returns the number of bytes read
+    fn receive(&mut self, size: usize, timeout: Duration) -> io::Result<usize> {
+        let old_timeout = self.transport.timeout();
+
+        if let Err(e) = self.transport.set_timeout(timeout) {
+            return Err(io::Error::other(e.to_string()));
+        }
+
+        let mut data = vec![0; size.max(1)];
+        let result = self.transport.read(&mut data);
+
+        if let Err(e) = self.transport.set_timeout(old_timeout) {
+            return Err(io::Error::other(e.to_string()));
+        }
+
+        let length = result?;
+
+        for &byte in &data[..length] {
+            if self.random.chance(self.settings.drop_rate) {
+                continue;
+            }
+
+            if self.random.chance(self.settings.flip_rate) {
+                self.buffer.push(byte ^ (1 << self.random.below(8)));
+            } else {
+                self.buffer.push(byte);
+            }
+
+            if self.random.chance(self.settings.garbage_rate) {
+                self.buffer.push(self.random.next() as u8);
+            }
+        }
+
+        Ok(length)
+    }
+
+    // wait shortly for further data to be received together with the current data
+    fn coalesce(&mut self, size: usize) -> io::Result<()> {
+        match self.receive(size, COALESCE_TIMEOUT) {
+            Err(ref e) if e.kind() == io::ErrorKind::TimedOut => Ok(()),
+            Err(e) => Err(e),
+            Ok(_) => Ok(())
+        }
+    }
+}
+
+impl Transport for FaultInjector {
+    fn name(&self) -> Option<String> {
+        self.transport.name()
+    }
+
+    fn settings(&self) -> Settings {
+        self.transport.settings()
+    }
+
+    fn set_baud_rate(&mut self, baud_rate: u32) -> Result<()> {
+        self.transport.set_baud_rate(baud_rate)
+    }
+
+    fn set_data_bits(&mut self, data_bits: DataBits) -> Result<()> {
+        self.transport.set_data_bits(data_bits)
+    }
+
+    fn set_parity(&mut self, parity: Parity) -> Result<()> {
+        self.transport.set_parity(parity)
+    }
+
+    fn set_stop_bits(&mut self, stop_bits: StopBits) -> Result<()> {
+        self.transport.set_stop_bits(stop_bits)
+    }
+
+    fn set_flow_control(&mut self, flow_control: FlowControl) -> Result<()> {
+        self.transport.set_flow_control(flow_control)
+    }
+
+    fn set_timeout(&mut self, timeout: Duration) -> Result<()> {
+        self.transport.set_timeout(timeout)
+    }
+
+    fn timeout(&self) -> Duration {
+        self.transport.timeout()
+    }
+
+    fn baud_rate(&self) -> Result<u32> {
+        self.transport.baud_rate()
+    }
+
+    fn data_bits(&self) -> Result<DataBits> {
+        self.transport.data_bits()
+    }
+
+    fn parity(&self) -> Result<Parity> {
+        self.transport.parity()
+    }
+
+    fn stop_bits(&self) -> Result<StopBits> {
+        self.transport.stop_bits()
+    }
+
+    fn flow_control(&self) -> Result<FlowControl> {
+        self.transport.flow_control()
+    }
+
+    fn clear_input(&mut self) -> Result<()> {
+        self.buffer.clear();
+
+        self.transport.clear_input()
+    }
+
+    fn clear_output(&mut self) -> Result<()> {
+        self.transport.clear_output()
+    }
+
+    fn write_request_to_send(&mut self, level: bool) -> Result<()> {
+        self.transport.write_request_to_send(level)
+    }
+
+    fn write_data_terminal_ready(&mut self, level: bool) -> Result<()> {
+        self.transport.write_data_terminal_ready(level)
+    }
+
+    fn set_break(&mut self, level: bool) -> Result<()> {
+        self.transport.set_break(level)
+    }
+
+    fn read_clear_to_send(&mut self) -> Result<bool> {
+        self.transport.read_clear_to_send()
+    }
+
+    fn read_data_set_ready(&mut self) -> Result<bool> {
+        self.transport.read_data_set_ready()
+    }
+
+    fn read_ring_indicator(&mut self) -> Result<bool> {
+        self.transport.read_ring_indicator()
+    }
+
+    fn read_carrier_detect(&mut self) -> Result<bool> {
+        self.transport.read_carrier_detect()
+    }
+}
+
+impl io::Write for FaultInjector {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.transport.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.transport.flush()
+    }
+}
+
+impl io::Read for FaultInjector {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.random.chance(self.settings.timeout_rate) {
+            thread::sleep(self.transport.timeout());
+
+            return Err(io::Error::new(io::ErrorKind::TimedOut, "Timed out"));
+        }
+
+        // receive until data is left after dropping bytes, all reads together take at most the timeout duration
+        let deadline = Instant::now() + self.transport.timeout();
+
+        while self.buffer.is_empty() {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+
+            if remaining == Duration::from_millis(0) {
+                return Err(io::Error::new(io::ErrorKind::TimedOut, "Timed out"));
+            }
+
+            if self.receive(buf.len(), remaining)? == 0 {
+                return Err(io::Error::new(io::ErrorKind::TimedOut, "Timed out"));
+            }
+
+            if !self.buffer.is_empty() && self.random.chance(self.settings.coalesce_rate) {
+                self.coalesce(buf.len())?;
+            }
+
+            if !self.buffer.is_empty() && self.settings.latency > Duration::from_millis(0) {
+                thread::sleep(self.settings.latency);
+            }
+        }
+
+        let mut len = self.buffer.len().min(buf.len());
+
+        if len > 1 && self.random.chance(self.settings.split_rate) {
+            len = 1 + self.random.below(len - 1);
+        }
+
+        buf[..len].clone_from_slice(&self.buffer[..len]);
+        self.buffer.drain(..len);
+
+        Ok(len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+    use std::time::Instant;
+    use crate::serial::{Serial, Loopback, CheckSettings};
+    use crate::tests::TestCase;
+    use crate::utils;
+    use super::*;
+
+    fn loopback(faults: FaultSettings) -> Serial {
+        let loopback = Loopback::new(Settings {
+            timeout: 100,
+            ..Settings::default()
+        });
+
+        Serial::from_transport(Box::new(FaultInjector::new(Box::new(loopback), faults)))
+    }
+
+    fn faults(configure: fn(&mut FaultSettings)) -> FaultSettings {
+        let mut faults = FaultSettings::with_seed(7);
+        configure(&mut faults);
+
+        faults
+    }
+
+    // run the faults through a test case and a check of the same response
+    fn run(faults: FaultSettings, response: &str) -> (TestCase, std::result::Result<bool, String>, Result<(bool, String)>) {
+        let mut test = TestCase::new("Fault".to_string(), response.to_string(), response.to_string());
+        let result = test.run(&mut loopback(faults));
+
+        let mut serial = loopback(faults);
+        serial.write(response).unwrap();
+
+        (test, result, serial.check_read_with_settings(response, &CheckSettings::default()))
+    }
+
+    // transport ending every read without data
+    struct Closed;
+
+    impl Transport for Closed {
+        fn name(&self) -> Option<String> {
+            None
+        }
+
+        fn settings(&self) -> Settings {
+            Settings::default()
+        }
+
+        fn set_baud_rate(&mut self, _baud_rate: u32) -> Result<()> {
+            Ok(())
+        }
+
+        fn set_data_bits(&mut self, _data_bits: DataBits) -> Result<()> {
+            Ok(())
+        }
+
+        fn set_parity(&mut self, _parity: Parity) -> Result<()> {
+            Ok(())
+        }
+
+        fn set_stop_bits(&mut self, _stop_bits: StopBits) -> Result<()> {
+            Ok(())
+        }
+
+        fn set_flow_control(&mut self, _flow_control: FlowControl) -> Result<()> {
+            Ok(())
+        }
+
+        fn set_timeout(&mut self, _timeout: Duration) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    impl io::Read for Closed {
+        fn read(&mut self, _buf: &mut [u8]) -> io::Result<usize> {
+            Ok(0)
+        }
+    }
+
+    impl io::Write for Closed {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn seeds_never_produce_zero_state() {
+        // the seed cancelled out the state of the former initialisation
+        let mut random = Random::new(0x9E37_79B9_7F4A_7C15);
+
+        assert_ne!(random.next(), random.next());
+        assert_ne!(Random::new(0).next(), Random::new(1).next());
+    }
+
+    #[test]
+    fn dropped_bytes() {
+        let (test, result, check) = run(faults(|faults| faults.drop_rate = 1.0), "Hello World!");
+
+        assert!(result.is_err());
+        assert!(test.is_timed_out());
+        assert!(check.unwrap_err().is_timeout());
+    }
+
+    #[test]
+    fn dropped_bytes_time_out_once() {
+        let mut serial = loopback(faults(|faults| faults.drop_rate = 1.0));
+        serial.write(&"x".repeat(5000)).unwrap();
+
+        let start = Instant::now();
+
+        assert!(serial.read().unwrap_err().is_timeout());
+        assert!(start.elapsed() < Duration::from_millis(200));
+    }
+
+    #[test]
+    fn empty_reads_time_out() {
+        let mut injector = FaultInjector::new(Box::new(Closed), FaultSettings::with_seed(7));
+
+        assert_eq!(injector.read(&mut [0; 10]).unwrap_err().kind(), io::ErrorKind::TimedOut);
+    }
+
+    #[test]
+    fn flipped_bits() {
+        let (_, result, check) = run(faults(|faults| faults.flip_rate = 1.0), "Hello World!");
+        let (matched, response) = check.unwrap();
+
+        assert_eq!(result, Ok(false));
+        assert!(!matched);
+        assert_eq!((response.as_bytes()[0] ^ b'H').count_ones(), 1);
+    }
+
+    #[test]
+    fn inserted_garbage() {
+        let (_, result, check) = run(faults(|faults| faults.garbage_rate = 1.0), "Hello World!");
+
+        assert_eq!(result, Ok(false));
+        assert!(!check.unwrap().0);
+
+        let mut serial = loopback(faults(|faults| faults.garbage_rate = 1.0));
+        serial.write("Hello").unwrap();
+
+        assert_eq!(serial.read_min_str_with_format_and_timeout(20, utils::TextFormat::Hex, Duration::from_millis(100)).unwrap().len(), 20);
+    }
+
+    #[test]
+    fn split_and_coalesced_reads() {
+        for faults in [faults(|faults| faults.split_rate = 1.0), faults(|faults| faults.coalesce_rate = 1.0)].iter() {
+            let (_, result, check) = run(*faults, "Hello World!");
+
+            assert_eq!(result, Ok(true));
+            assert_eq!(check.unwrap(), (true, "Hello World!".to_string()));
+        }
+
+        let mut serial = loopback(faults(|faults| faults.split_rate = 1.0));
+        serial.write("Hello World!").unwrap();
+
+        assert!(serial.read().unwrap().len() < 12);
+    }
+
+    #[test]
+    fn added_latency() {
+        let (test, result, check) = run(faults(|faults| faults.latency = Duration::from_millis(30)), "Hello World!");
+
+        assert_eq!(result, Ok(true));
+        assert!(test.duration().unwrap() >= Duration::from_millis(30));
+        assert!(check.unwrap().0);
+    }
+
+    #[test]
+    fn forced_timeouts() {
+        let (test, result, check) = run(faults(|faults| faults.timeout_rate = 1.0), "Hello World!");
+
+        assert!(result.is_err());
+        assert!(test.is_timed_out());
+        assert!(check.unwrap_err().is_timeout());
+    }
+
+    #[test]
+    fn equal_seeds_reproduce_faults() {
+        let receive = |seed: u64| {
+            let mut faults = FaultSettings::with_seed(seed);
+            faults.drop_rate = 0.2;
+            faults.flip_rate = 0.2;
+            faults.garbage_rate = 0.2;
+            faults.split_rate = 0.5;
+
+            let mut serial = loopback(faults);
+            serial.write(&"abcdefgh".repeat(20)).unwrap();
+
+            let mut reads = Vec::new();
+
+            while let Ok(bytes) = serial.read() {
+                reads.push(bytes.to_vec());
+            }
+
+            reads
+        };
+
+        assert_eq!(receive(42), receive(42));
+        assert_ne!(receive(42), receive(43));
+    }
+}
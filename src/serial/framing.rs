@@ -0,0 +1,162 @@
+/*
+ * File: src/serial/framing.rs
+ * Date: 17.10.2026
+ * Author: MarkAtk
+ *
+ * MIT License
+ *
+ * Copyright (c) 2026 MarkAtk
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies
+ * of the Software, and to permit persons to whom the Software is furnished to do
+ * so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+
+use std::time::Duration;
+
+/// Type of the length prefix of a frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LengthPrefix {
+    /// One byte.
+    U8,
+    /// Two bytes, most significant byte first.
+    U16Be,
+    /// Two bytes, least significant byte first.
+    U16Le,
+    /// Four bytes, most significant byte first.
+    U32Be,
+    /// Four bytes, least significant byte first.
+    U32Le
+}
+
+impl LengthPrefix {
+    /// Get the length prefix type by name, e.g. `u16be`.
+    pub fn from_name(name: &str) -> Option<LengthPrefix> {
+        match name.trim().to_lowercase().as_str() {
+            "u8" => Some(LengthPrefix::U8),
+            "u16be" => Some(LengthPrefix::U16Be),
+            "u16le" => Some(LengthPrefix::U16Le),
+            "u32be" => Some(LengthPrefix::U32Be),
+            "u32le" => Some(LengthPrefix::U32Le),
+            _ => None
+        }
+    }
+
+    /// Get the number of bytes of the prefix.
+    pub fn size(self) -> usize {
+        match self {
+            LengthPrefix::U8 => 1,
+            LengthPrefix::U16Be | LengthPrefix::U16Le => 2,
+            LengthPrefix::U32Be | LengthPrefix::U32Le => 4
+        }
+    }
+
+    /// Get the length stored in the prefix at the start of the data.
+    ///
+    /// None is returned if the data is shorter than the prefix.
+    pub fn length(self, data: &[u8]) -> Option<usize> {
+        let prefix = data.get(..self.size())?;
+
+        let bytes = match self {
+            LengthPrefix::U16Le | LengthPrefix::U32Le => prefix.iter().rev().cloned().collect(),
+            _ => prefix.to_vec()
+        };
+
+        Some(bytes.iter().fold(0usize, |length, byte| (length << 8) | *byte as usize))
+    }
+}
+
+/// Framing deciding when a response is complete.
+///
+/// # Example
+///
+/// ```
+/// use serial_unit_testing::serial::{Framing, LengthPrefix};
+///
+/// let framing = Framing::LengthPrefix(LengthPrefix::U16Be);
+///
+/// assert_eq!(framing.frame_length(&[0x00, 0x02, 0xAA]), None);
+/// assert_eq!(framing.frame_length(&[0x00, 0x02, 0xAA, 0xBB, 0xCC]), Some(4));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Framing {
+    /// Response ends with the given bytes, e.g. `\r\n`.
+    Until(Vec<u8>),
+    /// Response has the given number of bytes.
+    Length(usize),
+    /// Response starts with its length, the prefix is part of the response but not counted in the length.
+    LengthPrefix(LengthPrefix),
+    /// Response ends when no data is received for the given duration.
+    Idle(Duration)
+}
+
+impl Framing {
+    /// Get the length of the complete frame at the start of the data.
+    ///
+    /// None is returned if the frame is not complete yet. Idle frames are never complete by their data.
+    pub fn frame_length(&self, data: &[u8]) -> Option<usize> {
+        match self {
+            Framing::Until(delimiter) if delimiter.is_empty() => None,
+            Framing::Until(delimiter) => data.windows(delimiter.len()).position(|window| window == delimiter.as_slice()).map(|position| position + delimiter.len()),
+            Framing::Length(length) if data.len() >= *length => Some(*length),
+            Framing::LengthPrefix(prefix) => {
+                let length = prefix.size() + prefix.length(data)?;
+
+                if data.len() >= length {
+                    Some(length)
+                } else {
+                    None
+                }
+            },
+            _ => None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_delimiter() {
+        let framing = Framing::Until(b"\r\n".to_vec());
+
+        assert_eq!(framing.frame_length(b"OK\r"), None);
+        assert_eq!(framing.frame_length(b"OK\r\n"), Some(4));
+        assert_eq!(framing.frame_length(b"OK\r\nREADY"), Some(4));
+        assert_eq!(Framing::Until(Vec::new()).frame_length(b"OK"), None);
+    }
+
+    #[test]
+    fn short_length_prefix() {
+        assert_eq!(Framing::LengthPrefix(LengthPrefix::U16Be).frame_length(&[]), None);
+        assert_eq!(Framing::LengthPrefix(LengthPrefix::U16Be).frame_length(&[0x00]), None);
+        assert_eq!(Framing::LengthPrefix(LengthPrefix::U32Be).frame_length(&[0x00, 0x00, 0x01]), None);
+        assert_eq!(Framing::LengthPrefix(LengthPrefix::U8).frame_length(&[0x00]), Some(1));
+    }
+
+    #[test]
+    fn u32le_prefix() {
+        let framing = Framing::LengthPrefix(LengthPrefix::U32Le);
+
+        assert_eq!(LengthPrefix::U32Le.length(&[0x02, 0x01, 0x00, 0x00]), Some(0x0102));
+        assert_eq!(framing.frame_length(&[0x02, 0x00, 0x00, 0x00, 0xAA]), None);
+        assert_eq!(framing.frame_length(&[0x02, 0x00, 0x00, 0x00, 0xAA, 0xBB, 0xCC]), Some(6));
+        assert_eq!(Framing::LengthPrefix(LengthPrefix::U32Be).frame_length(&[0x02, 0x00, 0x00, 0x00, 0xAA, 0xBB]), None);
+    }
+}
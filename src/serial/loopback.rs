@@ -26,160 +26,224 @@
  * SOFTWARE.
  */
 
-use std::time::Duration;
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
 use std::io;
 use std::thread;
-use serialport::{self, SerialPort, StopBits, ClearBuffer, FlowControl, DataBits, Error, SerialPortSettings, Parity};
+use crate::error::Result;
+use super::settings::{Settings, DataBits, Parity, StopBits, FlowControl};
+use super::transport::Transport;
+use super::mock::MockDevice;
+
+/// Device simulated by a loopback instead of returning the written data.
+pub trait VirtualDevice: Send {
+    /// Handle data written to the device.
+    ///
+    /// Returns the response chunks with the delay to wait before each chunk is sent.
+    fn write(&mut self, data: &[u8]) -> Vec<(Duration, Vec<u8>)>;
+
+    /// Get the data sent by the device on its own after the loopback is created.
+    fn open(&mut self) -> Vec<(Duration, Vec<u8>)> {
+        Vec::new()
+    }
+
+    /// Discard all written data not handled yet.
+    fn clear(&mut self) {
+    }
+}
 
+/// Virtual serial port returning everything written to it.
+///
+/// The loopback can be used in every place a serial port is required, e.g. by opening the port named `loopback`.
+///
+/// Instead of returning the written data the loopback can act as a virtual device, e.g. a mock device answering with responses
+/// defined by rules.
+///
+/// Control lines are connected like by a loopback plug: CTS follows RTS, DSR and CD follow DTR. RTS and DTR are active after
+/// creating the loopback. Without a virtual device a break is received as a single zero byte like by most UARTs.
 pub struct Loopback {
-    settings: SerialPortSettings,
-    buffer: Vec<u8>
+    settings: Settings,
+    buffer: Vec<u8>,
+    device: Option<Box<dyn VirtualDevice>>,
+    pending: VecDeque<(Instant, Vec<u8>)>,
+    request_to_send: bool,
+    data_terminal_ready: bool,
+    break_condition: bool
 }
 
 impl Loopback {
-    pub fn open(settings: SerialPortSettings) -> Box<Loopback> {
-        Box::new(Loopback {
+    /// Create a new loopback with given settings.
+    pub fn new(settings: Settings) -> Loopback {
+        Loopback {
             settings,
-            buffer: vec!()
-        })
+            buffer: vec!(),
+            device: None,
+            pending: VecDeque::new(),
+            request_to_send: true,
+            data_terminal_ready: true,
+            break_condition: false
+        }
     }
-}
 
-impl SerialPort for Loopback {
-    fn name(&self) -> Option<String> {
-        Some("loopback".to_string())
+    /// Create a new loopback acting as the given mock device.
+    pub fn with_mock(settings: Settings, device: MockDevice) -> Loopback {
+        Loopback::with_device(settings, Box::new(device))
     }
 
-    fn settings(&self) -> SerialPortSettings {
-        self.settings
-    }
+    /// Create a new loopback acting as the given virtual device.
+    pub fn with_device(settings: Settings, mut device: Box<dyn VirtualDevice>) -> Loopback {
+        let mut loopback = Loopback::new(settings);
+        loopback.schedule(device.open());
+        loopback.device = Some(device);
 
-    fn baud_rate(&self) -> Result<u32, Error> {
-        Ok(self.settings.baud_rate)
+        loopback
     }
 
-    fn data_bits(&self) -> Result<DataBits, Error> {
-        Ok(self.settings.data_bits)
-    }
+    // add responses to the pending responses
+    fn schedule(&mut self, responses: Vec<(Duration, Vec<u8>)>) {
+        // responses are sent in order, so never schedule before the last pending response
+        let mut time = Instant::now();
 
-    fn flow_control(&self) -> Result<FlowControl, Error> {
-        Ok(self.settings.flow_control)
-    }
+        for (delay, data) in responses {
+            time += delay;
 
-    fn parity(&self) -> Result<Parity, Error> {
-        Ok(self.settings.parity)
-    }
+            if let Some((last_time, _)) = self.pending.back() {
+                time = time.max(*last_time);
+            }
 
-    fn stop_bits(&self) -> Result<StopBits, Error> {
-        Ok(self.settings.stop_bits)
+            self.pending.push_back((time, data));
+        }
     }
 
-    fn timeout(&self) -> Duration {
-        self.settings.timeout
+    // move all pending responses which are due into the read buffer
+    fn receive_pending(&mut self) {
+        let now = Instant::now();
+
+        while let Some((time, _)) = self.pending.front() {
+            if *time > now {
+                break;
+            }
+
+            if let Some((_, data)) = self.pending.pop_front() {
+                self.buffer.extend(data);
+            }
+        }
     }
+}
 
-    fn set_all(&mut self, settings: &SerialPortSettings) -> Result<(), Error> {
-        self.settings = *settings;
+impl Transport for Loopback {
+    fn name(&self) -> Option<String> {
+        Some("loopback".to_string())
+    }
 
-        Ok(())
+    fn settings(&self) -> Settings {
+        self.settings
     }
 
-    fn set_baud_rate(&mut self, baud_rate: u32) -> Result<(), Error> {
+    fn set_baud_rate(&mut self, baud_rate: u32) -> Result<()> {
         self.settings.baud_rate = baud_rate;
 
         Ok(())
     }
 
-    fn set_data_bits(&mut self, data_bits: DataBits) -> Result<(), Error> {
+    fn set_data_bits(&mut self, data_bits: DataBits) -> Result<()> {
         self.settings.data_bits = data_bits;
 
         Ok(())
     }
 
-    fn set_flow_control(&mut self, flow_control: FlowControl) -> Result<(), Error> {
-        self.settings.flow_control = flow_control;
+    fn set_parity(&mut self, parity: Parity) -> Result<()> {
+        self.settings.parity = parity;
 
         Ok(())
     }
 
-    fn set_parity(&mut self, parity: Parity) -> Result<(), Error> {
-        self.settings.parity = parity;
+    fn set_stop_bits(&mut self, stop_bits: StopBits) -> Result<()> {
+        self.settings.stop_bits = stop_bits;
 
         Ok(())
     }
 
-    fn set_stop_bits(&mut self, stop_bits: StopBits) -> Result<(), Error> {
-        self.settings.stop_bits = stop_bits;
+    fn set_flow_control(&mut self, flow_control: FlowControl) -> Result<()> {
+        self.settings.flow_control = flow_control;
 
         Ok(())
     }
 
-    fn set_timeout(&mut self, timeout: Duration) -> Result<(), Error> {
-        self.settings.timeout = timeout;
+    fn set_timeout(&mut self, timeout: Duration) -> Result<()> {
+        self.settings.timeout = timeout.as_millis() as u64;
 
         Ok(())
     }
 
-    fn write_request_to_send(&mut self, _level: bool) -> Result<(), Error> {
-        // do nothing
+    fn clear_input(&mut self) -> Result<()> {
+        // responses scheduled later are still received
+        self.receive_pending();
+        self.buffer.clear();
 
         Ok(())
     }
 
-    fn write_data_terminal_ready(&mut self, _level: bool) -> Result<(), Error> {
-        // do nothing
+    fn clear_output(&mut self) -> Result<()> {
+        if let Some(ref mut device) = self.device {
+            device.clear();
+        }
 
         Ok(())
     }
 
-    fn read_clear_to_send(&mut self) -> Result<bool, Error> {
-        Ok(true)
-    }
+    fn write_request_to_send(&mut self, level: bool) -> Result<()> {
+        self.request_to_send = level;
 
-    fn read_data_set_ready(&mut self) -> Result<bool, Error> {
-        Ok(true)
+        Ok(())
     }
 
-    fn read_ring_indicator(&mut self) -> Result<bool, Error> {
-        Ok(false)
-    }
+    fn write_data_terminal_ready(&mut self, level: bool) -> Result<()> {
+        self.data_terminal_ready = level;
 
-    fn read_carrier_detect(&mut self) -> Result<bool, Error> {
-        Ok(false)
+        Ok(())
     }
 
-    fn bytes_to_read(&self) -> Result<u32, Error> {
-        Ok(self.buffer.len() as u32)
-    }
+    fn set_break(&mut self, level: bool) -> Result<()> {
+        if level && !self.break_condition && self.device.is_none() {
+            self.buffer.push(0);
+        }
 
-    fn bytes_to_write(&self) -> Result<u32, Error> {
-        // always return 0 because of loopback
+        self.break_condition = level;
 
-        Ok(0)
+        Ok(())
     }
 
-    fn clear(&self, _buffer_to_clear: ClearBuffer) -> Result<(), Error> {
-        // do nothing (as buffer cannot be cleared without self being mutable)
+    fn read_clear_to_send(&mut self) -> Result<bool> {
+        Ok(self.request_to_send)
+    }
 
-        Ok(())
+    fn read_data_set_ready(&mut self) -> Result<bool> {
+        Ok(self.data_terminal_ready)
     }
 
-    fn try_clone(&self) -> Result<Box<dyn SerialPort>, Error> {
-        Ok(Box::new(Loopback {
-            settings: self.settings,
-            buffer: self.buffer.clone()
-        }))
+    fn read_carrier_detect(&mut self) -> Result<bool> {
+        Ok(self.data_terminal_ready)
     }
 }
 
 impl io::Write for Loopback {
-    fn write(&mut self, buf: &[u8]) -> Result<usize, io::Error> {
-        self.buffer.extend_from_slice(buf);
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let responses = match self.device {
+            Some(ref mut device) => device.write(buf),
+            None => {
+                self.buffer.extend_from_slice(buf);
+
+                return Ok(buf.len());
+            }
+        };
+
+        self.schedule(responses);
 
         Ok(buf.len())
     }
 
-    fn flush(&mut self) -> Result<(), io::Error> {
+    fn flush(&mut self) -> io::Result<()> {
         // do nothing
 
         Ok(())
@@ -187,16 +251,30 @@ impl io::Write for Loopback {
 }
 
 impl io::Read for Loopback {
-    fn read(&mut self, buf: &mut [u8]) -> Result<usize, io::Error> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.receive_pending();
+
         if self.buffer.is_empty() {
-            thread::sleep(self.settings.timeout);
+            let timeout = self.timeout();
 
-            return Err(io::Error::new(io::ErrorKind::TimedOut, "Timed out"));
+            // wait for the next pending response if it is due in the timeout duration
+            match self.pending.front() {
+                Some((time, _)) if time.saturating_duration_since(Instant::now()) <= timeout => {
+                    thread::sleep(time.saturating_duration_since(Instant::now()));
+
+                    self.receive_pending();
+                },
+                _ => {
+                    thread::sleep(timeout);
+
+                    return Err(io::Error::new(io::ErrorKind::TimedOut, "Timed out"));
+                }
+            }
         }
 
-        let len = self.buffer.len();
+        let len = self.buffer.len().min(buf.len());
         buf[..len].clone_from_slice(&self.buffer[..len]);
-        self.buffer.clear();
+        self.buffer.drain(..len);
 
         Ok(len)
     }
@@ -0,0 +1,263 @@
+/*
+ * File: src/serial/mock.rs
+ * Date: 17.10.2026
+ * Author: MarkAtk
+ *
+ * MIT License
+ *
+ * Copyright (c) 2026 MarkAtk
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies
+ * of the Software, and to permit persons to whom the Software is furnished to do
+ * so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+use std::time::Duration;
+use regex::bytes::Regex;
+use super::loopback::VirtualDevice;
+
+/// Pattern the data written to a mock device is matched against.
+#[derive(Debug, Clone)]
+pub enum MockPattern {
+    /// Literal byte sequence.
+    Bytes(Vec<u8>),
+    /// Regular expression. Capture groups can be referenced in the response with `$1` or `$name`.
+    Regex(Regex)
+}
+
+/// Request and response rule of a mock device.
+#[derive(Debug, Clone)]
+pub struct MockRule {
+    /// Pattern the written data must match.
+    pub pattern: MockPattern,
+    /// Response sent when the pattern matches.
+    pub response: Vec<u8>,
+    /// Wait given duration before responding.
+    pub delay: Duration,
+    /// Split the response into chunks of given size.
+    pub chunk_size: Option<usize>,
+    /// Wait given duration between response chunks.
+    pub chunk_delay: Duration
+}
+
+impl MockRule {
+    /// Create a new rule responding to the given request immediately.
+    pub fn new(pattern: MockPattern, response: Vec<u8>) -> MockRule {
+        MockRule {
+            pattern,
+            response,
+            delay: Duration::from_millis(0),
+            chunk_size: None,
+            chunk_delay: Duration::from_millis(0)
+        }
+    }
+
+    // find the first match in data and return its range and the response
+    fn find(&self, data: &[u8]) -> Option<(usize, usize, Vec<u8>)> {
+        match self.pattern {
+            MockPattern::Bytes(ref bytes) if bytes.is_empty() => None,
+            MockPattern::Bytes(ref bytes) => {
+                data.windows(bytes.len())
+                    .position(|window| window == bytes.as_slice())
+                    .map(|start| (start, start + bytes.len(), self.response.clone()))
+            },
+            MockPattern::Regex(ref regex) => {
+                let captures = regex.captures(data)?;
+                let mat = captures.get(0)?;
+
+                // empty matches would respond to any write
+                if mat.start() == mat.end() {
+                    return None;
+                }
+
+                let mut response = Vec::new();
+                captures.expand(&self.response, &mut response);
+
+                Some((mat.start(), mat.end(), response))
+            }
+        }
+    }
+}
+
+/// Mock device answering written data with responses given by rules.
+///
+/// Written data is buffered until a rule matches. If multiple rules match the one with the earliest match is used, on equal
+/// position the first rule wins. Data before and including the match is discarded afterwards.
+///
+/// A mock device can be used with the loopback port, see `Loopback::with_mock`.
+///
+/// # Example
+///
+/// ```
+/// use serial_unit_testing::serial::{Serial, Loopback, MockDevice, MockRule, MockPattern};
+/// use serial_unit_testing::serial::settings::Settings;
+/// use serial_unit_testing::error::Result;
+///
+/// fn main() -> Result<()> {
+///     let rules = vec![MockRule::new(MockPattern::Bytes(b"PING\r\n".to_vec()), b"PONG\r\n".to_vec())];
+///     let loopback = Loopback::with_mock(Settings::default(), MockDevice::new(rules));
+///
+///     let mut serial = Serial::from_transport(Box::new(loopback));
+///     let (result, _) = serial.check("PING\r\n", "PONG\r\n")?;
+///
+///     assert!(result);
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct MockDevice {
+    rules: Vec<MockRule>,
+    input: Vec<u8>
+}
+
+impl MockDevice {
+    /// Create a new mock device with given rules.
+    pub fn new(rules: Vec<MockRule>) -> MockDevice {
+        MockDevice {
+            rules,
+            input: Vec::new()
+        }
+    }
+}
+
+impl VirtualDevice for MockDevice {
+    fn write(&mut self, data: &[u8]) -> Vec<(Duration, Vec<u8>)> {
+        let mut responses = Vec::new();
+
+        self.input.extend_from_slice(data);
+
+        loop {
+            let mut next: Option<(usize, usize, Vec<u8>, &MockRule)> = None;
+
+            for rule in &self.rules {
+                if let Some((start, end, response)) = rule.find(&self.input) {
+                    if next.as_ref().is_none_or(|(next_start, _, _, _)| start < *next_start) {
+                        next = Some((start, end, response, rule));
+                    }
+                }
+            }
+
+            let (end, response, rule) = match next {
+                Some((_, end, response, rule)) => (end, response, rule),
+                None => break
+            };
+
+            let chunk_size = rule.chunk_size.unwrap_or(response.len()).max(1);
+
+            for (index, chunk) in response.chunks(chunk_size).enumerate() {
+                let delay = if index == 0 {
+                    rule.delay
+                } else {
+                    rule.chunk_delay
+                };
+
+                responses.push((delay, chunk.to_vec()));
+            }
+
+            self.input.drain(..end);
+        }
+
+        responses
+    }
+
+    fn clear(&mut self) {
+        self.input.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+    use crate::serial::{Serial, Loopback};
+    use crate::serial::settings::Settings;
+    use super::*;
+
+    fn bytes_rule(request: &str, response: &str) -> MockRule {
+        MockRule::new(MockPattern::Bytes(request.as_bytes().to_vec()), response.as_bytes().to_vec())
+    }
+
+    fn regex_rule(request: &str, response: &str) -> MockRule {
+        MockRule::new(MockPattern::Regex(Regex::new(request).unwrap()), response.as_bytes().to_vec())
+    }
+
+    #[test]
+    fn earliest_match_wins() {
+        let mut device = MockDevice::new(vec![bytes_rule("b", "2"), bytes_rule("a", "1"), bytes_rule("ab", "3")]);
+
+        // "a" and "ab" match at the same position, the rule listed first is used
+        assert_eq!(device.write(b"xab"), vec![(Duration::from_millis(0), b"1".to_vec()), (Duration::from_millis(0), b"2".to_vec())]);
+        assert!(device.write(b"c").is_empty());
+        assert_eq!(device.write(b"b"), vec![(Duration::from_millis(0), b"2".to_vec())]);
+    }
+
+    #[test]
+    fn requests_are_buffered_until_matching() {
+        let mut device = MockDevice::new(vec![bytes_rule("PING\r\n", "PONG\r\n")]);
+
+        assert!(device.write(b"PI").is_empty());
+        assert_eq!(device.write(b"NG\r\n"), vec![(Duration::from_millis(0), b"PONG\r\n".to_vec())]);
+
+        device.write(b"PI");
+        device.clear();
+
+        assert!(device.write(b"NG\r\n").is_empty());
+    }
+
+    #[test]
+    fn regex_captures_expand_into_responses() {
+        let mut device = MockDevice::new(vec![
+            regex_rule("set (?P<reg>[0-9]+)=(?P<value>[A-F0-9]+)\n", "ok $reg $value\n"),
+            regex_rule("get ([a-z]+)\n", "${1}!\n"),
+            regex_rule("x*", "never")
+        ]);
+
+        assert_eq!(device.write(b"set 12=FF\n"), vec![(Duration::from_millis(0), b"ok 12 FF\n".to_vec())]);
+        assert_eq!(device.write(b"get mode\n"), vec![(Duration::from_millis(0), b"mode!\n".to_vec())]);
+        assert!(device.write(b"y").is_empty());
+    }
+
+    #[test]
+    fn delays_and_chunks() {
+        let mut rule = bytes_rule("y\n", "abcde");
+        rule.delay = Duration::from_millis(30);
+        rule.chunk_size = Some(2);
+        rule.chunk_delay = Duration::from_millis(10);
+
+        let mut device = MockDevice::new(vec![rule.clone()]);
+
+        assert_eq!(device.write(b"y\n"), vec![
+            (Duration::from_millis(30), b"ab".to_vec()),
+            (Duration::from_millis(10), b"cd".to_vec()),
+            (Duration::from_millis(10), b"e".to_vec())
+        ]);
+
+        let loopback = Loopback::with_mock(Settings {
+            timeout: 200,
+            ..Settings::default()
+        }, MockDevice::new(vec![rule]));
+        let mut serial = Serial::from_transport(Box::new(loopback));
+
+        let start = Instant::now();
+        serial.write("y\n").unwrap();
+
+        assert_eq!(serial.read().unwrap(), b"ab");
+        assert!(start.elapsed() >= Duration::from_millis(30));
+        assert_eq!(serial.read_min_str_with_timeout(3, Duration::from_millis(200)).unwrap(), "cde");
+        assert!(start.elapsed() >= Duration::from_millis(50));
+    }
+}
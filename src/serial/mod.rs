@@ -28,22 +28,42 @@
 
 use std::boxed::Box;
 use std::str;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use serialport;
 use crate::utils;
 use crate::error::{Result, Error};
 
 pub mod settings;
+mod transport;
 mod loopback;
-
-use loopback::Loopback;
+mod mock;
+mod fault;
+mod recording;
+mod tcp;
+mod rfc2217;
+mod framing;
+#[cfg(unix)]
+mod pty;
+
+pub use self::transport::{Transport, SerialPortBreak};
+pub use self::loopback::{Loopback, VirtualDevice};
+pub use self::mock::{MockDevice, MockRule, MockPattern};
+pub use self::fault::{FaultInjector, FaultSettings};
+pub use self::recording::{Recording, RecordEntry, RecordEvent, Recorder, ReplayDevice};
+pub use self::tcp::TcpPort;
+pub use self::rfc2217::Rfc2217Port;
+pub use self::framing::{Framing, LengthPrefix};
+#[cfg(unix)]
+pub use self::pty::PtyPort;
 
 /// A serial port connection.
 ///
-/// This struct handles the complete communication with a serial device regardless of the platform.
+/// This struct handles the complete communication with a serial device regardless of the platform. The device itself is accessed
+/// through a `Transport`, which is a system serial port, a network connection or the loopback when opened by name.
 pub struct Serial {
-    port: Box<dyn serialport::SerialPort>,
-    read_buffer: Vec<u8>
+    port: Box<dyn Transport>,
+    read_buffer: Vec<u8>,
+    pending: Vec<u8>
 }
 
 impl Serial {
@@ -61,7 +81,7 @@ impl Serial {
     ///
     /// # Example
     ///
-    /// ```
+    /// ```no_run
     /// use serial_unit_testing::serial::Serial;
     /// use serial_unit_testing::error::Result;
     ///
@@ -82,10 +102,14 @@ impl Serial {
     /// Open a new connection with given settings.
     ///
     /// The port name is platform specific, e.g. starts with `COM` on Windows and `/dev/tty` or similar on UNIX systems.
+    /// The name `loopback` opens a virtual port returning everything written to it, `tcp://host:port` opens a raw TCP connection
+    /// (e.g. to a ser2net bridge) and `rfc2217://host:port` opens a connection to a RFC 2217 server with remote port settings.
+    /// On UNIX systems `pty://` creates a pseudo terminal pair, see `PtyPort` for details. `replay://<file>` opens a virtual device
+    /// replaying a recorded session, see `ReplayDevice`.
     ///
     /// # Example
     ///
-    /// ```
+    /// ```no_run
     /// use serial_unit_testing::serial::Serial;
     /// use serial_unit_testing::serial::settings::Settings;
     /// use serial_unit_testing::error::Result;
@@ -94,7 +118,7 @@ impl Serial {
     ///     let mut settings = Settings::default();
     ///     settings.baud_rate = 115200;
     ///
-    ///     let mut serial = Serial::open_with_settings("/dev/ttyACM0", &settings)?;
+    ///     let mut serial = Serial::open_with_settings("/dev/ttyACM0", settings)?;
     ///     serial.write("Hello World!")?;
     ///
     ///     Ok(())
@@ -102,28 +126,94 @@ impl Serial {
     ///
     /// ```
     pub fn open_with_settings(port_name: &str, settings: settings::Settings) -> Result<Serial> {
-        let serial_port_settings = settings.into();
-
         if port_name == "loopback" {
-            let port = Loopback::open(serial_port_settings);
+            return Ok(Serial::from_transport(Box::new(Loopback::new(settings))));
+        }
+
+        if let Some(path) = port_name.strip_prefix("replay://") {
+            let device = ReplayDevice::new(Recording::load(path)?);
+
+            return Ok(Serial::from_transport(Box::new(Loopback::with_device(settings, Box::new(device)))));
+        }
 
-            return Ok(Serial {
-                port,
-                read_buffer: vec![0; 1000]
-            });
+        if let Some(address) = port_name.strip_prefix("tcp://") {
+            return Ok(Serial::from_transport(Box::new(TcpPort::connect(address, settings)?)));
         }
 
-        match serialport::open_with_settings(&port_name, &serial_port_settings) {
-            Ok(port) => {
-                Ok(Serial { port, read_buffer: vec![0; 1000] })
-            },
+        if let Some(address) = port_name.strip_prefix("rfc2217://") {
+            return Ok(Serial::from_transport(Box::new(Rfc2217Port::connect(address, settings)?)));
+        }
+
+        if let Some(link) = port_name.strip_prefix("pty://") {
+            return Serial::open_pty(link, settings);
+        }
+
+        // terminals are opened directly as breaks are set on their file descriptor
+        #[cfg(unix)]
+        let port = serialport::posix::TTYPort::open(std::path::Path::new(&port_name), &settings.into()).map(Box::new);
+        #[cfg(not(unix))]
+        let port = serialport::open_with_settings(&port_name, &settings.into());
+
+        match port {
+            Ok(port) => Ok(Serial::from_transport(Box::new(port))),
             Err(e) => Err(Error::from(e))
         }
     }
 
+    /// Create a new connection using the given transport.
+    ///
+    /// This can be used to communicate over custom transports, e.g. network bridges or device simulators.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use serial_unit_testing::serial::{Serial, Loopback};
+    /// use serial_unit_testing::serial::settings::Settings;
+    /// use serial_unit_testing::error::Result;
+    ///
+    /// fn main() -> Result<()> {
+    ///     let mut serial = Serial::from_transport(Box::new(Loopback::new(Settings::default())));
+    ///     serial.write("Hello World!")?;
+    ///
+    ///     assert_eq!(serial.read_str()?, "Hello World!");
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn from_transport(transport: Box<dyn Transport>) -> Serial {
+        Serial {
+            port: transport,
+            read_buffer: vec![0; 1000],
+            pending: Vec::new()
+        }
+    }
+
+    /// Consume the connection and return the underlying transport.
+    ///
+    /// This can be used to wrap the transport of an opened port, e.g. with a `FaultInjector`.
+    pub fn into_transport(self) -> Box<dyn Transport> {
+        self.port
+    }
+
+    #[cfg(unix)]
+    fn open_pty(link: &str, settings: settings::Settings) -> Result<Serial> {
+        let link = if link.is_empty() {
+            None
+        } else {
+            Some(link.into())
+        };
+
+        Ok(Serial::from_transport(Box::new(PtyPort::open(settings, link)?)))
+    }
+
+    #[cfg(not(unix))]
+    fn open_pty(_link: &str, _settings: settings::Settings) -> Result<Serial> {
+        Err(Error::from(std::io::Error::other("Pseudo terminals are not supported on this platform")))
+    }
+
     /// Get the current serial settings.
     pub fn settings(&self) -> settings::Settings {
-        self.port.settings().into()
+        self.port.settings()
     }
 
     /// Get the port name if any exists.
@@ -135,92 +225,59 @@ impl Serial {
 
     /// Set the baud rate.
     pub fn set_baud_rate(&mut self, baud_rate: u32) -> Result<()> {
-        match self.port.set_baud_rate(baud_rate) {
-            Ok(_) => Ok(()),
-            Err(e) => Err(Error::from(e))
-        }
+        self.port.set_baud_rate(baud_rate)
     }
 
     /// Get the baud rate.
     ///
     /// This will return the actual device baud rate, which may differ from the last specified value.
     pub fn baud_rate(&self) -> Result<u32> {
-        match self.port.baud_rate() {
-            Ok(value) => Ok(value),
-            Err(e) => Err(Error::from(e))
-        }
+        self.port.baud_rate()
     }
 
     /// Set the number of data bits.
     pub fn set_data_bits(&mut self, data_bits: settings::DataBits) -> Result<()> {
-        match self.port.set_data_bits(data_bits.into()) {
-            Ok(_) => Ok(()),
-            Err(e) => Err(Error::from(e))
-        }
+        self.port.set_data_bits(data_bits)
     }
 
     /// Get the number of data bits.
     pub fn data_bits(&self) -> Result<settings::DataBits> {
-        match self.port.data_bits() {
-            Ok(value) => Ok(settings::DataBits::from(value)),
-            Err(e) => Err(Error::from(e))
-        }
+        self.port.data_bits()
     }
 
     /// Set the parity checking mode
     pub fn set_parity(&mut self, parity: settings::Parity) -> Result<()> {
-        match self.port.set_parity(parity.into()) {
-            Ok(_) => Ok(()),
-            Err(e) => Err(Error::from(e))
-        }
+        self.port.set_parity(parity)
     }
 
     /// Get the parity checking mode
     pub fn parity(&self) -> Result<settings::Parity> {
-        match self.port.parity() {
-            Ok(value) => Ok(settings::Parity::from(value)),
-            Err(e) => Err(Error::from(e))
-        }
+        self.port.parity()
     }
 
     /// Set the number of stop bits.
     pub fn set_stop_bits(&mut self, stop_bits: settings::StopBits) -> Result<()> {
-        match self.port.set_stop_bits(stop_bits.into()) {
-            Ok(_) => Ok(()),
-            Err(e) => Err(Error::from(e))
-        }
+        self.port.set_stop_bits(stop_bits)
     }
 
     /// Get the number of stop bits.
     pub fn stop_bits(&self) -> Result<settings::StopBits> {
-        match self.port.stop_bits() {
-            Ok(value) => Ok(settings::StopBits::from(value)),
-            Err(e) => Err(Error::from(e))
-        }
+        self.port.stop_bits()
     }
 
     /// Set the flow control.
     pub fn set_flow_control(&mut self, flow_control: settings::FlowControl) -> Result<()> {
-        match self.port.set_flow_control(flow_control.into()) {
-            Ok(_) => Ok(()),
-            Err(e) => Err(Error::from(e))
-        }
+        self.port.set_flow_control(flow_control)
     }
 
     /// Get the flow control.
     pub fn flow_control(&self) -> Result<settings::FlowControl> {
-        match self.port.flow_control() {
-            Ok(value) => Ok(settings::FlowControl::from(value)),
-            Err(e) => Err(Error::from(e))
-        }
+        self.port.flow_control()
     }
 
     /// Set the I/O timeout.
     pub fn set_timeout(&mut self, timeout: u64) -> Result<()> {
-        match self.port.set_timeout(Duration::from_millis(timeout)) {
-            Ok(_) => Ok(()),
-            Err(e) => Err(Error::from(e))
-        }
+        self.port.set_timeout(Duration::from_millis(timeout))
     }
 
     /// Get the I/O timeout.
@@ -228,6 +285,94 @@ impl Serial {
         self.port.timeout().as_millis() as u64
     }
 
+    /// Set the RTS (request to send) control line.
+    ///
+    /// Ports without control lines, e.g. TCP connections, ignore the level.
+    pub fn write_request_to_send(&mut self, level: bool) -> Result<()> {
+        self.port.write_request_to_send(level)
+    }
+
+    /// Set the DTR (data terminal ready) control line.
+    ///
+    /// Ports without control lines, e.g. TCP connections, ignore the level.
+    pub fn write_data_terminal_ready(&mut self, level: bool) -> Result<()> {
+        self.port.write_data_terminal_ready(level)
+    }
+
+    /// Start or stop a break condition.
+    ///
+    /// Breaks are supported by serial ports, pseudo terminals, loopback and RFC 2217 ports. Other ports, e.g. TCP connections, and
+    /// serial ports on platforms other than UNIX fail.
+    pub fn set_break(&mut self, level: bool) -> Result<()> {
+        self.port.set_break(level)
+    }
+
+    /// Send a break condition for the given duration.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use serial_unit_testing::serial::{Serial, Loopback};
+    /// use serial_unit_testing::serial::settings::Settings;
+    /// use serial_unit_testing::error::Result;
+    ///
+    /// fn main() -> Result<()> {
+    ///     let mut serial = Serial::from_transport(Box::new(Loopback::new(Settings::default())));
+    ///     serial.send_break(Duration::from_millis(10))?;
+    ///
+    ///     // the loopback receives a break as zero byte
+    ///     assert_eq!(serial.read()?, &[0]);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn send_break(&mut self, duration: Duration) -> Result<()> {
+        self.port.set_break(true)?;
+
+        std::thread::sleep(duration);
+
+        self.port.set_break(false)
+    }
+
+    /// Read the CTS (clear to send) control line.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use serial_unit_testing::serial::{Serial, Loopback};
+    /// use serial_unit_testing::serial::settings::Settings;
+    /// use serial_unit_testing::error::Result;
+    ///
+    /// fn main() -> Result<()> {
+    ///     let mut serial = Serial::from_transport(Box::new(Loopback::new(Settings::default())));
+    ///     serial.write_request_to_send(false)?;
+    ///
+    ///     // the loopback connects RTS to CTS
+    ///     assert_eq!(serial.read_clear_to_send()?, false);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn read_clear_to_send(&mut self) -> Result<bool> {
+        self.port.read_clear_to_send()
+    }
+
+    /// Read the DSR (data set ready) control line.
+    pub fn read_data_set_ready(&mut self) -> Result<bool> {
+        self.port.read_data_set_ready()
+    }
+
+    /// Read the RI (ring indicator) control line.
+    pub fn read_ring_indicator(&mut self) -> Result<bool> {
+        self.port.read_ring_indicator()
+    }
+
+    /// Read the CD (carrier detect) control line.
+    pub fn read_carrier_detect(&mut self) -> Result<bool> {
+        self.port.read_carrier_detect()
+    }
+
     /// Write text to the serial port.
     ///
     /// This is the same as using `Serial::write_format` with `TextFormat::Text` as format specifier.
@@ -238,9 +383,17 @@ impl Serial {
         }
     }
 
+    /// Write raw bytes to the serial port.
+    ///
+    /// Returns the number of bytes written, which may be less than the given data.
+    pub fn write_bytes(&mut self, data: &[u8]) -> Result<usize> {
+        Ok(self.port.write(data)?)
+    }
+
     /// Write data in the given format.
     ///
     /// For a list of supported formats see `TextFormat`. `TextFormat::Text` is the same as using `Serial::write`.
+    /// Checksum placeholders like `{crc16-modbus}` are replaced as described in `utils::bytes_from_format`.
     ///
     /// # Example
     ///
@@ -250,7 +403,7 @@ impl Serial {
     /// use serial_unit_testing::error::Result;
     ///
     /// fn main() -> Result<()> {
-    ///     let mut serial = Serial::open("/dev/ttyACM0")?;
+    ///     let mut serial = Serial::open("loopback")?;
     ///     serial.write_format("0a5f", TextFormat::Hex)?;
     ///
     ///     Ok(())
@@ -258,18 +411,7 @@ impl Serial {
     ///
     /// ```
     pub fn write_format(&mut self, text: &str, text_format: utils::TextFormat) -> Result<usize> {
-        let bytes = match text_format {
-            utils::TextFormat::Binary => utils::bytes_from_binary_string(text)?,
-            utils::TextFormat::Octal => utils::bytes_from_octal_string(text)?,
-            utils::TextFormat::Decimal => utils::bytes_from_decimal_string(text)?,
-            utils::TextFormat::Hex => utils::bytes_from_hex_string(text)?,
-            _ => {
-                let mut bytes = Vec::new();
-                bytes.extend_from_slice(text.as_bytes());
-
-                bytes
-            }
-        };
+        let bytes = utils::bytes_from_format(text, text_format)?;
 
         match self.port.write(bytes.as_slice()) {
             Ok(count) => Ok(count),
@@ -288,13 +430,19 @@ impl Serial {
     /// use serial_unit_testing::error::Result;
     ///
     /// fn main() -> Result<()> {
-    ///     let mut serial = Serial::open("/dev/ttyACM0")?;
+    ///     let mut serial = Serial::open("loopback")?;
+    ///     serial.write("Hello World!")?;
+    ///
     ///     let data = serial.read().unwrap();
     ///
     ///     Ok(())
     /// }
     /// ```
     pub fn read(&mut self) -> Result<&[u8]> {
+        if let Some(length) = self.read_pending() {
+            return Ok(&self.read_buffer[..length]);
+        }
+
         let length = match self.port.read(&mut self.read_buffer) {
             Ok(length) => length,
             Err(e) => return Err(Error::from(e))
@@ -418,40 +566,173 @@ impl Serial {
     ///
     /// At least one byte of data must be read to return data. The method fails when no data could be read in the timeout duration.
     pub fn read_with_timeout(&mut self, timeout: Duration) -> Result<&[u8]> {
+        if let Some(length) = self.read_pending() {
+            return Ok(&self.read_buffer[..length]);
+        }
+
         // remember old timeout
         let old_timeout = self.port.timeout();
-        if let Err(e) = self.port.set_timeout(timeout) {
-            return Err(Error::from(e));
-        }
+        self.port.set_timeout(timeout)?;
 
-        let length = self.port.read(&mut self.read_buffer)?;
+        // restore the timeout even if the read timed out
+        let result = self.port.read(&mut self.read_buffer);
 
-        if let Err(e) = self.port.set_timeout(old_timeout) {
-            return Err(Error::from(e));
-        }
+        self.port.set_timeout(old_timeout)?;
+
+        let length = result?;
 
         Ok(&self.read_buffer[..length])
     }
 
-    /// Read a string in given timeout duration.
+    /// Read a complete response with the given framing.
     ///
-    /// This function can be used to use a different timeout for a single read. Otherwise see the timeout property of serial.
+    /// Data received after the end of the frame is returned by the next read. If the frame is not complete when the read times
+    /// out, the data received so far is returned. The method fails when no data could be read in the timeout duration.
     ///
-    /// At least one character must be read to return successfully. The method fails when no data could be read in the timeout duration.
-    pub fn read_str_with_timeout(&mut self, timeout: Duration) -> Result<String> {
-        // remember old timeout
-        let old_timeout = self.port.timeout();
-        if let Err(e) = self.port.set_timeout(timeout) {
-            return Err(Error::from(e));
+    /// # Example
+    ///
+    /// ```
+    /// use serial_unit_testing::serial::{Serial, Loopback, Framing};
+    /// use serial_unit_testing::serial::settings::Settings;
+    /// use serial_unit_testing::error::Result;
+    ///
+    /// fn main() -> Result<()> {
+    ///     let mut serial = Serial::from_transport(Box::new(Loopback::new(Settings::default())));
+    ///     serial.write("OK\r\nREADY\r\n")?;
+    ///
+    ///     assert_eq!(serial.read_frame(&Framing::Until(b"\r\n".to_vec()))?, b"OK\r\n");
+    ///     assert_eq!(serial.read_frame(&Framing::Until(b"\r\n".to_vec()))?, b"READY\r\n");
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn read_frame(&mut self, framing: &Framing) -> Result<Vec<u8>> {
+        self.read_frame_with(framing, None, |_| ())
+    }
+
+    /// Read a complete response with the given framing in given timeout duration.
+    ///
+    /// The timeout applies to the whole frame rather than to every single read, see `read_frame` for details.
+    pub fn read_frame_with_timeout(&mut self, framing: &Framing, timeout: Duration) -> Result<Vec<u8>> {
+        self.read_frame_with(framing, Some(timeout), |_| ())
+    }
+
+    /// Read a complete response with the given framing and pass every received part to the given function, e.g. to measure timings.
+    pub(crate) fn read_frame_with<F: FnMut(&[u8])>(&mut self, framing: &Framing, timeout: Option<Duration>, mut on_read: F) -> Result<Vec<u8>> {
+        let mut data: Vec<u8> = Vec::new();
+        let deadline = Instant::now() + timeout.unwrap_or_else(|| self.port.timeout());
+
+        loop {
+            // every read only waits for the time left until the deadline
+            let remaining = deadline.saturating_duration_since(Instant::now());
+
+            if remaining == Duration::from_millis(0) && !data.is_empty() {
+                return Ok(data);
+            }
+
+            // idle frames end with the first read timing out after data was received
+            let read_timeout = match framing {
+                Framing::Idle(idle) if !data.is_empty() => (*idle).min(remaining),
+                _ => remaining
+            };
+
+            // some ports do not support a zero timeout
+            let result = self.read_with_timeout(read_timeout.max(Duration::from_millis(1)));
+
+            match result {
+                Ok(bytes) => {
+                    on_read(bytes);
+                    data.extend_from_slice(bytes);
+
+                    if let Some(length) = framing.frame_length(&data) {
+                        self.pending = data.split_off(length);
+
+                        return Ok(data);
+                    }
+                },
+                Err(e) if e.is_timeout() && !data.is_empty() => return Ok(data),
+                Err(e) => return Err(e)
+            };
         }
+    }
+
+    /// Discard all received data not read yet.
+    ///
+    /// Only buffers of the port are cleared, use `drain_for` to discard data of ports without buffers of their own, e.g. TCP connections.
+    pub fn clear_input(&mut self) -> Result<()> {
+        self.pending.clear();
+
+        self.port.clear_input()
+    }
+
+    /// Discard all written data not transmitted yet.
+    pub fn clear_output(&mut self) -> Result<()> {
+        self.port.clear_output()
+    }
 
-        let length = self.port.read(&mut self.read_buffer)?;
+    /// Read and discard all data received in the given duration.
+    ///
+    /// Reading continues after the duration until a read times out without data, so all data already received is read even if
+    /// the duration is zero. Returns the discarded data.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use serial_unit_testing::serial::{Serial, Loopback};
+    /// use serial_unit_testing::serial::settings::Settings;
+    /// use serial_unit_testing::error::Result;
+    ///
+    /// fn main() -> Result<()> {
+    ///     let mut serial = Serial::from_transport(Box::new(Loopback::new(Settings::default())));
+    ///     serial.write("stale")?;
+    ///
+    ///     assert_eq!(serial.drain_for(Duration::from_millis(0))?, b"stale");
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn drain_for(&mut self, duration: Duration) -> Result<Vec<u8>> {
+        let mut data = std::mem::take(&mut self.pending);
+        let end = Instant::now() + duration;
+
+        loop {
+            // some ports do not support a zero timeout
+            let timeout = end.saturating_duration_since(Instant::now()).max(Duration::from_millis(1));
+
+            match self.read_with_timeout(timeout) {
+                Ok(bytes) if !bytes.is_empty() => data.extend_from_slice(bytes),
+                Ok(_) => break,
+                Err(e) if e.is_timeout() && Instant::now() >= end => break,
+                Err(e) if e.is_timeout() => (),
+                Err(e) => return Err(e)
+            };
+        }
+
+        Ok(data)
+    }
 
-        if let Err(e) = self.port.set_timeout(old_timeout) {
-            return Err(Error::from(e));
+    // data left from reading a frame is returned before reading from the port
+    fn read_pending(&mut self) -> Option<usize> {
+        if self.pending.is_empty() {
+            return None;
         }
 
-        match str::from_utf8(&self.read_buffer[..length]) {
+        let length = self.pending.len().min(self.read_buffer.len());
+
+        self.read_buffer[..length].copy_from_slice(&self.pending[..length]);
+        self.pending.drain(..length);
+
+        Some(length)
+    }
+
+    /// Read a string in given timeout duration.
+    ///
+    /// This function can be used to use a different timeout for a single read. Otherwise see the timeout property of serial.
+    ///
+    /// At least one character must be read to return successfully. The method fails when no data could be read in the timeout duration.
+    pub fn read_str_with_timeout(&mut self, timeout: Duration) -> Result<String> {
+        match str::from_utf8(self.read_with_timeout(timeout)?) {
             Ok(text) => Ok(text.to_string()),
             Err(e) => Err(Error::from(e))
         }
@@ -497,18 +778,7 @@ impl Serial {
     ///
     /// At least one character must be read to return successfully. The method fails when no characters could be read in the timeout duration.
     pub fn read_str_with_format_and_timeout(&mut self, format: utils::TextFormat, timeout: Duration) -> Result<String> {
-        // remember old timeout
-        let old_timeout = self.port.timeout();
-        if let Err(e) = self.port.set_timeout(timeout) {
-            return Err(Error::from(e));
-        }
-
-        let length = self.port.read(&mut self.read_buffer)?;
-        let data = &self.read_buffer[..length];
-
-        if let Err(e) = self.port.set_timeout(old_timeout) {
-            return Err(Error::from(e));
-        }
+        let data = self.read_with_timeout(timeout)?;
 
         utils::radix_string(data, &format)
     }
@@ -523,9 +793,7 @@ impl Serial {
     pub fn read_min_str_with_format_and_timeout(&mut self, min_length: usize, format: utils::TextFormat, timeout: Duration) -> Result<String> {
         // remember old timeout
         let old_timeout = self.port.timeout();
-        if let Err(e) = self.port.set_timeout(timeout) {
-            return Err(Error::from(e));
-        }
+        self.port.set_timeout(timeout)?;
 
         let mut response = String::new();
 
@@ -542,9 +810,7 @@ impl Serial {
                 },
                 Err(e) if e.is_timeout() => {
                     if response.len() < min_length {
-                        if let Err(e) = self.port.set_timeout(old_timeout) {
-                            return Err(Error::from(e));
-                        }
+                        self.port.set_timeout(old_timeout)?;
 
                         return Err(e);
                     }
@@ -552,18 +818,14 @@ impl Serial {
                     break;
                 },
                 Err(e) => {
-                    if let Err(e) = self.port.set_timeout(old_timeout) {
-                        return Err(Error::from(e));
-                    }
+                    self.port.set_timeout(old_timeout)?;
 
                     return Err(e);
                 }
             }
         }
 
-        if let Err(e) = self.port.set_timeout(old_timeout) {
-            return Err(Error::from(e));
-        }
+        self.port.set_timeout(old_timeout)?;
 
         Ok(response)
     }
@@ -581,8 +843,8 @@ impl Serial {
     /// use serial_unit_testing::error::Result;
     ///
     /// fn main() -> Result<()> {
-    ///     let mut serial = Serial::open("/dev/ttyACM0")?;
-    ///     let (result, actual_response) = serial.check("hello", "world")?;
+    ///     let mut serial = Serial::open("loopback")?;
+    ///     let (result, actual_response) = serial.check("hello", "hello")?;
     ///
     ///     Ok(())
     /// }
@@ -621,6 +883,7 @@ impl Serial {
     ///
     /// Returns whether the actual response matches the desired response and the actual response. Fails with an timeout error or internal serial error.
     pub fn check_read_with_settings(&mut self, desired_response: &str, settings: &CheckSettings) -> Result<(bool, String)> {
+        let mut data: Vec<u8> = Vec::new();
         let mut response = String::new();
 
         // convert hex to upper case because actual hex output is returned in upper case letters
@@ -630,17 +893,37 @@ impl Serial {
             desired_response.to_string()
         };
 
+        // framed responses are read completely before comparing
+        if let Some(ref framing) = settings.framing {
+            let data = self.read_frame(framing)?;
+
+            response = match settings.output_format {
+                utils::TextFormat::Text => String::from_utf8_lossy(&data).to_string(),
+                _ => utils::radix_string(&data, &settings.output_format)?
+            };
+
+            if settings.ignore_case {
+                response = response.to_lowercase();
+            }
+
+            return Ok((compare == response, response));
+        }
+
         loop {
             match self.read() {
                 Ok(bytes) => {
-                    let mut new_text = utils::radix_string(bytes, &settings.output_format)?;
+                    // convert all data at once as characters may be split between reads, invalid text will not match
+                    data.extend_from_slice(bytes);
+
+                    response = match settings.output_format {
+                        utils::TextFormat::Text => String::from_utf8_lossy(&data).to_string(),
+                        _ => utils::radix_string(&data, &settings.output_format)?
+                    };
 
                     if settings.ignore_case {
-                        new_text = new_text.to_lowercase();
+                        response = response.to_lowercase();
                     }
 
-                    response.push_str(new_text.as_str());
-
                     if compare == response {
                         break;
                     }
@@ -650,7 +933,7 @@ impl Serial {
                     }
                 },
                 Err(e) if e.is_timeout() => {
-                    if response.is_empty() {
+                    if data.is_empty() {
                         return Err(e);
                     }
 
@@ -671,7 +954,9 @@ pub struct CheckSettings {
     /// Format of the data written to the serial port.
     pub input_format: utils::TextFormat,
     /// Format of the data received by the serial port.
-    pub output_format: utils::TextFormat
+    pub output_format: utils::TextFormat,
+    /// Framing of the response, otherwise reading stops as soon as the response does not match anymore.
+    pub framing: Option<Framing>
 }
 
 impl Default for CheckSettings {
@@ -679,7 +964,98 @@ impl Default for CheckSettings {
         CheckSettings {
             ignore_case: false,
             input_format: utils::TextFormat::Text,
-            output_format: utils::TextFormat::Text
+            output_format: utils::TextFormat::Text,
+            framing: None
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn loopback() -> Serial {
+        Serial::from_transport(Box::new(Loopback::new(settings::Settings::default())))
+    }
+
+    #[test]
+    fn pending_data_is_returned_by_next_read() {
+        let mut serial = loopback();
+        serial.write("OK\r\nREADY").unwrap();
+
+        assert_eq!(serial.read_frame(&Framing::Until(b"\r\n".to_vec())).unwrap(), b"OK\r\n");
+        assert_eq!(serial.read().unwrap(), b"READY");
+    }
+
+    #[test]
+    fn pending_data_starts_next_frame() {
+        let mut serial = loopback();
+        serial.write_bytes(&[0x01, 0x00, 0x00, 0x00, 0xAA, 0x02, 0x00]).unwrap();
+
+        let framing = Framing::LengthPrefix(LengthPrefix::U32Le);
+
+        assert_eq!(serial.read_frame(&framing).unwrap(), [0x01, 0x00, 0x00, 0x00, 0xAA]);
+
+        serial.write_bytes(&[0x00, 0x00, 0xBB, 0xCC]).unwrap();
+
+        assert_eq!(serial.read_frame(&framing).unwrap(), [0x02, 0x00, 0x00, 0x00, 0xBB, 0xCC]);
+    }
+
+    #[test]
+    fn pending_data_is_returned_by_string_reads() {
+        let mut serial = loopback();
+        let framing = Framing::Until(b"\r\n".to_vec());
+        serial.write("OK\r\nREADY").unwrap();
+
+        assert_eq!(serial.read_frame(&framing).unwrap(), b"OK\r\n");
+
+        serial.write("!").unwrap();
+
+        assert_eq!(serial.read_str_with_timeout(Duration::from_millis(50)).unwrap(), "READY");
+        assert_eq!(serial.read_str_with_timeout(Duration::from_millis(50)).unwrap(), "!");
+
+        serial.write("1\r\n2").unwrap();
+
+        assert_eq!(serial.read_frame(&framing).unwrap(), b"1\r\n");
+        assert_eq!(serial.read_str_with_format_and_timeout(utils::TextFormat::Hex, Duration::from_millis(50)).unwrap(), "32");
+
+        serial.write("a\r\nb").unwrap();
+
+        assert_eq!(serial.read_frame(&framing).unwrap(), b"a\r\n");
+
+        serial.write("c").unwrap();
+
+        assert_eq!(serial.read_str_until_with_timeout("c", Duration::from_millis(50)).unwrap(), "bc");
+    }
+
+    #[test]
+    fn incomplete_frame_is_returned_at_deadline() {
+        let mut serial = loopback();
+        serial.write("OK\r").unwrap();
+
+        let start = Instant::now();
+
+        assert_eq!(serial.read_frame_with_timeout(&Framing::Until(b"\r\n".to_vec()), Duration::from_millis(50)).unwrap(), b"OK\r");
+        assert!(start.elapsed() < Duration::from_millis(500));
+    }
+
+    #[test]
+    fn drain_reads_all_data_with_zero_duration() {
+        let mut serial = loopback();
+        let stale = vec![0x55; 2500];
+        serial.write_bytes(&stale).unwrap();
+
+        assert_eq!(serial.drain_for(Duration::from_millis(0)).unwrap(), stale);
+        assert!(serial.drain_for(Duration::from_millis(0)).unwrap().is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn breaks_are_set_on_terminals() {
+        let mut pty = Serial::open("pty://").unwrap();
+        let mut serial = Serial::open(&pty.name().unwrap()).unwrap();
+
+        pty.send_break(Duration::from_millis(1)).unwrap();
+        serial.send_break(Duration::from_millis(1)).unwrap();
+    }
+}
@@ -0,0 +1,210 @@
+/*
+ * File: src/serial/pty.rs
+ * Date: 17.10.2026
+ * Author: MarkAtk
+ *
+ * MIT License
+ *
+ * Copyright (c) 2026 MarkAtk
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies
+ * of the Software, and to permit persons to whom the Software is furnished to do
+ * so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+use std::fs;
+use std::io;
+use std::os::unix::io::AsRawFd;
+use std::path::PathBuf;
+use std::time::Duration;
+use serialport::SerialPort;
+use serialport::posix::TTYPort;
+use crate::error::{Result, Error};
+use super::settings::{Settings, DataBits, Parity, StopBits, FlowControl};
+use super::transport::{self, Transport};
+
+/// Pseudo terminal pair acting as virtual serial port.
+///
+/// Opened with `pty://` as port name. Data is sent and received on the master side while other programs (e.g. a device
+/// simulator) open the slave device returned by `PtyPort::slave_name`. Optionally a symbolic link to the slave device can be
+/// created by giving a path, e.g. `pty:///tmp/ttyV0`, which is removed when the port is closed.
+///
+/// Only available on UNIX systems.
+///
+/// # Example
+///
+/// ```
+/// use serial_unit_testing::serial::PtyPort;
+/// use serial_unit_testing::serial::settings::Settings;
+/// use serial_unit_testing::error::Result;
+///
+/// fn main() -> Result<()> {
+///     let port = PtyPort::open(Settings::default(), None)?;
+///
+///     println!("Device can be opened at {}", port.slave_name());
+///
+///     Ok(())
+/// }
+/// ```
+pub struct PtyPort {
+    master: TTYPort,
+    // keep the slave open so the master does not fail while no other program has opened the slave
+    slave: TTYPort,
+    slave_name: String,
+    link: Option<PathBuf>,
+    settings: Settings
+}
+
+impl PtyPort {
+    /// Create a new pseudo terminal pair with given settings.
+    ///
+    /// If a link path is given a symbolic link to the slave device is created at that path.
+    pub fn open(settings: Settings, link: Option<PathBuf>) -> Result<PtyPort> {
+        let (mut master, mut slave) = TTYPort::pair()?;
+
+        // allow other programs to open the slave device
+        slave.set_exclusive(false)?;
+
+        let slave_name = match slave.name() {
+            Some(name) => name,
+            None => return Err(Error::Other)
+        };
+
+        master.set_timeout(Duration::from_millis(settings.timeout))?;
+
+        let mut port = PtyPort {
+            master,
+            slave,
+            slave_name,
+            link: None,
+            settings
+        };
+
+        port.set_baud_rate(settings.baud_rate)?;
+        port.set_data_bits(settings.data_bits)?;
+        port.set_parity(settings.parity)?;
+        port.set_stop_bits(settings.stop_bits)?;
+        port.set_flow_control(settings.flow_control)?;
+
+        if let Some(path) = link {
+            std::os::unix::fs::symlink(&port.slave_name, &path)?;
+
+            port.link = Some(path);
+        }
+
+        Ok(port)
+    }
+
+    /// Get the path of the slave device other programs can open.
+    pub fn slave_name(&self) -> &str {
+        &self.slave_name
+    }
+}
+
+impl Drop for PtyPort {
+    fn drop(&mut self) {
+        if let Some(ref path) = self.link {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
+
+impl Transport for PtyPort {
+    fn name(&self) -> Option<String> {
+        Some(self.slave_name.clone())
+    }
+
+    fn settings(&self) -> Settings {
+        self.settings
+    }
+
+    // settings are applied to the slave as not all platforms support changing them on the master
+
+    fn set_baud_rate(&mut self, baud_rate: u32) -> Result<()> {
+        self.slave.set_baud_rate(baud_rate)?;
+        self.settings.baud_rate = baud_rate;
+
+        Ok(())
+    }
+
+    fn set_data_bits(&mut self, data_bits: DataBits) -> Result<()> {
+        self.slave.set_data_bits(data_bits.into())?;
+        self.settings.data_bits = data_bits;
+
+        Ok(())
+    }
+
+    fn set_parity(&mut self, parity: Parity) -> Result<()> {
+        self.slave.set_parity(parity.into())?;
+        self.settings.parity = parity;
+
+        Ok(())
+    }
+
+    fn set_stop_bits(&mut self, stop_bits: StopBits) -> Result<()> {
+        self.slave.set_stop_bits(stop_bits.into())?;
+        self.settings.stop_bits = stop_bits;
+
+        Ok(())
+    }
+
+    fn set_flow_control(&mut self, flow_control: FlowControl) -> Result<()> {
+        self.slave.set_flow_control(flow_control.into())?;
+        self.settings.flow_control = flow_control;
+
+        Ok(())
+    }
+
+    fn set_timeout(&mut self, timeout: Duration) -> Result<()> {
+        self.master.set_timeout(timeout)?;
+        self.settings.timeout = timeout.as_millis() as u64;
+
+        Ok(())
+    }
+
+    fn clear_input(&mut self) -> Result<()> {
+        self.master.clear(serialport::ClearBuffer::Input)?;
+
+        Ok(())
+    }
+
+    fn clear_output(&mut self) -> Result<()> {
+        self.master.clear(serialport::ClearBuffer::Output)?;
+
+        Ok(())
+    }
+
+    fn set_break(&mut self, level: bool) -> Result<()> {
+        transport::set_break(self.master.as_raw_fd(), level)
+    }
+}
+
+impl io::Write for PtyPort {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.master.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.master.flush()
+    }
+}
+
+impl io::Read for PtyPort {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.master.read(buf)
+    }
+}
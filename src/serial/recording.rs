@@ -0,0 +1,534 @@
+/*
+ * File: src/serial/recording.rs
+ * Date: 17.10.2026
+ * Author: MarkAtk
+ *
+ * MIT License
+ *
+ * Copyright (c) 2026 MarkAtk
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies
+ * of the Software, and to permit persons to whom the Software is furnished to do
+ * so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+
+use std::fs;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::path::Path;
+use std::time::{Duration, Instant};
+use crate::error::{Result, Error};
+use crate::utils;
+use super::settings::{Settings, DataBits, Parity, StopBits, FlowControl};
+use super::transport::Transport;
+use super::loopback::VirtualDevice;
+
+/// Event of a recorded session.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RecordEvent {
+    /// Settings in use, given as short string (see `Settings::to_short_string`).
+    Settings(String),
+    /// Data written to the device.
+    Write(Vec<u8>),
+    /// Data read from the device.
+    Read(Vec<u8>)
+}
+
+/// Event of a recorded session with the time since the recording started.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordEntry {
+    /// Time since the recording started.
+    pub time: Duration,
+    /// Recorded event.
+    pub event: RecordEvent
+}
+
+impl RecordEntry {
+    /// Format the entry as line of a recording file.
+    ///
+    /// Lines start with the time in seconds followed by `settings`, `>` for written or `<` for read data. Data is given as
+    /// hexadecimal bytes.
+    pub fn to_line(&self) -> String {
+        let time = format!("{}.{:06}", self.time.as_secs(), self.time.subsec_micros());
+
+        match self.event {
+            RecordEvent::Settings(ref settings) => format!("{} settings {}", time, settings),
+            RecordEvent::Write(ref data) => format!("{} > {}", time, hex_string(data)),
+            RecordEvent::Read(ref data) => format!("{} < {}", time, hex_string(data))
+        }
+    }
+
+    /// Parse a line of a recording file.
+    pub fn from_line(line: &str) -> Option<RecordEntry> {
+        let (time, event) = line.trim().split_once(' ')?;
+        let (seconds, micros) = time.split_once('.')?;
+
+        let time = Duration::from_secs(seconds.parse().ok()?) + Duration::from_micros(micros.parse().ok()?);
+
+        let event = if let Some(settings) = event.strip_prefix("settings ") {
+            RecordEvent::Settings(settings.trim().to_string())
+        } else if let Some(data) = event.strip_prefix('>') {
+            RecordEvent::Write(utils::bytes_from_hex_string(&data.replace(' ', "")).ok()?)
+        } else if let Some(data) = event.strip_prefix('<') {
+            RecordEvent::Read(utils::bytes_from_hex_string(&data.replace(' ', "")).ok()?)
+        } else {
+            return None;
+        };
+
+        Some(RecordEntry {
+            time,
+            event
+        })
+    }
+}
+
+fn hex_string(data: &[u8]) -> String {
+    data.iter().map(|byte| format!("{:02X}", byte)).collect::<Vec<String>>().join(" ")
+}
+
+/// Recorded session.
+#[derive(Debug, Clone, Default)]
+pub struct Recording {
+    /// Recorded events in order.
+    pub entries: Vec<RecordEntry>
+}
+
+impl Recording {
+    /// Load a recording from the given file.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Recording> {
+        let file = fs::File::open(path)?;
+
+        Recording::read_from(file)
+    }
+
+    /// Read a recording from the given reader.
+    ///
+    /// Empty lines and lines starting with `#` are ignored.
+    pub fn read_from<R: Read>(reader: R) -> Result<Recording> {
+        let mut entries = Vec::new();
+
+        for (index, line) in BufReader::new(reader).lines().enumerate() {
+            let line = line?;
+
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            match RecordEntry::from_line(&line) {
+                Some(entry) => entries.push(entry),
+                None => return Err(Error::from(io::Error::new(io::ErrorKind::InvalidData, format!("Invalid recording entry in line {}", index + 1))))
+            }
+        }
+
+        Ok(Recording {
+            entries
+        })
+    }
+
+    /// Get the settings the recording was started with.
+    pub fn settings(&self) -> Option<&str> {
+        self.entries.iter().find_map(|entry| match entry.event {
+            RecordEvent::Settings(ref settings) => Some(settings.as_str()),
+            _ => None
+        })
+    }
+}
+
+/// Transport wrapper recording all data written to and read from another transport.
+///
+/// Every event is written as line to the output immediately (see `RecordEntry::to_line`), so the recording is complete even if
+/// the program is interrupted. Recordings can be replayed with a `ReplayDevice` or by opening the port `replay://<file>`.
+pub struct Recorder {
+    transport: Box<dyn Transport>,
+    output: Box<dyn Write + Send>,
+    start: Instant
+}
+
+impl Recorder {
+    /// Create a new recorder wrapping the given transport and writing to the given output.
+    pub fn new(transport: Box<dyn Transport>, output: Box<dyn Write + Send>) -> Result<Recorder> {
+        let mut recorder = Recorder {
+            transport,
+            output,
+            start: Instant::now()
+        };
+
+        recorder.output.write_all(b"# Session recorded by serial-unit-testing\n")?;
+        recorder.record_settings()?;
+
+        Ok(recorder)
+    }
+
+    /// Create a new recorder wrapping the given transport and writing to the file at the given path.
+    pub fn create<P: AsRef<Path>>(transport: Box<dyn Transport>, path: P) -> Result<Recorder> {
+        let file = fs::File::create(path)?;
+
+        Recorder::new(transport, Box::new(io::BufWriter::new(file)))
+    }
+
+    /// Unwrap the recorder and return the wrapped transport.
+    pub fn into_inner(self) -> Box<dyn Transport> {
+        self.transport
+    }
+
+    fn record(&mut self, event: RecordEvent) -> io::Result<()> {
+        let entry = RecordEntry {
+            time: self.start.elapsed(),
+            event
+        };
+
+        writeln!(self.output, "{}", entry.to_line())?;
+
+        self.output.flush()
+    }
+
+    fn record_settings(&mut self) -> Result<()> {
+        let settings = self.transport.settings().to_short_string();
+
+        Ok(self.record(RecordEvent::Settings(settings))?)
+    }
+}
+
+impl Transport for Recorder {
+    fn name(&self) -> Option<String> {
+        self.transport.name()
+    }
+
+    fn settings(&self) -> Settings {
+        self.transport.settings()
+    }
+
+    fn set_baud_rate(&mut self, baud_rate: u32) -> Result<()> {
+        self.transport.set_baud_rate(baud_rate)?;
+
+        self.record_settings()
+    }
+
+    fn set_data_bits(&mut self, data_bits: DataBits) -> Result<()> {
+        self.transport.set_data_bits(data_bits)?;
+
+        self.record_settings()
+    }
+
+    fn set_parity(&mut self, parity: Parity) -> Result<()> {
+        self.transport.set_parity(parity)?;
+
+        self.record_settings()
+    }
+
+    fn set_stop_bits(&mut self, stop_bits: StopBits) -> Result<()> {
+        self.transport.set_stop_bits(stop_bits)?;
+
+        self.record_settings()
+    }
+
+    fn set_flow_control(&mut self, flow_control: FlowControl) -> Result<()> {
+        self.transport.set_flow_control(flow_control)
+    }
+
+    fn set_timeout(&mut self, timeout: Duration) -> Result<()> {
+        self.transport.set_timeout(timeout)
+    }
+
+    fn timeout(&self) -> Duration {
+        self.transport.timeout()
+    }
+
+    fn baud_rate(&self) -> Result<u32> {
+        self.transport.baud_rate()
+    }
+
+    fn data_bits(&self) -> Result<DataBits> {
+        self.transport.data_bits()
+    }
+
+    fn parity(&self) -> Result<Parity> {
+        self.transport.parity()
+    }
+
+    fn stop_bits(&self) -> Result<StopBits> {
+        self.transport.stop_bits()
+    }
+
+    fn flow_control(&self) -> Result<FlowControl> {
+        self.transport.flow_control()
+    }
+
+    fn clear_input(&mut self) -> Result<()> {
+        self.transport.clear_input()
+    }
+
+    fn clear_output(&mut self) -> Result<()> {
+        self.transport.clear_output()
+    }
+
+    fn write_request_to_send(&mut self, level: bool) -> Result<()> {
+        self.transport.write_request_to_send(level)
+    }
+
+    fn write_data_terminal_ready(&mut self, level: bool) -> Result<()> {
+        self.transport.write_data_terminal_ready(level)
+    }
+
+    fn set_break(&mut self, level: bool) -> Result<()> {
+        self.transport.set_break(level)
+    }
+
+    fn read_clear_to_send(&mut self) -> Result<bool> {
+        self.transport.read_clear_to_send()
+    }
+
+    fn read_data_set_ready(&mut self) -> Result<bool> {
+        self.transport.read_data_set_ready()
+    }
+
+    fn read_ring_indicator(&mut self) -> Result<bool> {
+        self.transport.read_ring_indicator()
+    }
+
+    fn read_carrier_detect(&mut self) -> Result<bool> {
+        self.transport.read_carrier_detect()
+    }
+}
+
+impl Write for Recorder {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let length = self.transport.write(buf)?;
+
+        self.record(RecordEvent::Write(buf[..length].to_vec()))?;
+
+        Ok(length)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.transport.flush()
+    }
+}
+
+impl Read for Recorder {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let length = self.transport.read(buf)?;
+
+        if length > 0 {
+            self.record(RecordEvent::Read(buf[..length].to_vec()))?;
+        }
+
+        Ok(length)
+    }
+}
+
+/// Virtual device replaying a recorded session.
+///
+/// Data written to the device is compared with the recorded writes. Once a recorded write is complete the following recorded reads
+/// are sent with their recorded timing. Data read before the first write is sent when the device is opened. If the written data
+/// does not match, the device skips ahead to the next matching write in the recording, otherwise the data is ignored.
+///
+/// # Example
+///
+/// ```
+/// use serial_unit_testing::serial::{Serial, Loopback, Recording, ReplayDevice};
+/// use serial_unit_testing::serial::settings::Settings;
+/// use serial_unit_testing::error::Result;
+///
+/// fn main() -> Result<()> {
+///     let recording = Recording::read_from("0.000000 > 68 0A\n0.010000 < 48 65 6C 70".as_bytes())?;
+///     let loopback = Loopback::with_device(Settings::default(), Box::new(ReplayDevice::new(recording)));
+///
+///     let mut serial = Serial::from_transport(Box::new(loopback));
+///     let (result, _) = serial.check("h\n", "Help")?;
+///
+///     assert!(result);
+///
+///     Ok(())
+/// }
+/// ```
+pub struct ReplayDevice {
+    entries: Vec<RecordEntry>,
+    position: usize,
+    input: Vec<u8>
+}
+
+impl ReplayDevice {
+    /// Create a new device replaying the given recording.
+    pub fn new(recording: Recording) -> ReplayDevice {
+        ReplayDevice {
+            entries: recording.entries,
+            position: 0,
+            input: Vec::new()
+        }
+    }
+
+    // get the written data of the block starting at given position and the position after the block
+    fn write_block(&self, position: usize) -> (Vec<u8>, usize) {
+        let mut data = Vec::new();
+        let mut end = position;
+
+        while end < self.entries.len() {
+            match self.entries[end].event {
+                RecordEvent::Write(ref bytes) => data.extend_from_slice(bytes),
+                RecordEvent::Settings(_) => (),
+                RecordEvent::Read(_) => break
+            };
+
+            end += 1;
+        }
+
+        (data, end)
+    }
+
+    // get the read data following the given position with delays relative to the given time and move to the next write
+    fn read_block(&mut self, mut time: Duration) -> Vec<(Duration, Vec<u8>)> {
+        let mut responses = Vec::new();
+
+        while self.position < self.entries.len() {
+            let entry = &self.entries[self.position];
+
+            match entry.event {
+                RecordEvent::Read(ref bytes) => {
+                    responses.push((entry.time.saturating_sub(time), bytes.clone()));
+
+                    time = entry.time;
+                },
+                RecordEvent::Settings(_) => (),
+                RecordEvent::Write(_) => break
+            };
+
+            self.position += 1;
+        }
+
+        responses
+    }
+
+    // find the next write block matching the written data
+    fn find_block(&self) -> Option<usize> {
+        let mut position = self.position;
+
+        while position < self.entries.len() {
+            if let RecordEvent::Write(_) = self.entries[position].event {
+                let (data, end) = self.write_block(position);
+
+                if data.starts_with(&self.input) || self.input.starts_with(&data) {
+                    return Some(position);
+                }
+
+                position = end;
+            } else {
+                position += 1;
+            }
+        }
+
+        None
+    }
+}
+
+impl VirtualDevice for ReplayDevice {
+    fn write(&mut self, data: &[u8]) -> Vec<(Duration, Vec<u8>)> {
+        let mut responses = Vec::new();
+
+        self.input.extend_from_slice(data);
+
+        while !self.input.is_empty() {
+            let position = match self.find_block() {
+                Some(position) => position,
+                None => {
+                    self.input.clear();
+
+                    break;
+                }
+            };
+
+            let (expected, end) = self.write_block(position);
+
+            // wait for the rest of the block
+            if expected.len() > self.input.len() {
+                break;
+            }
+
+            self.input.drain(..expected.len());
+            self.position = end;
+
+            let time = self.entries[end - 1].time;
+            responses.extend(self.read_block(time));
+        }
+
+        responses
+    }
+
+    fn open(&mut self) -> Vec<(Duration, Vec<u8>)> {
+        self.read_block(Duration::from_millis(0))
+    }
+
+    fn clear(&mut self) {
+        self.input.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::serial::{Serial, Loopback};
+    use super::*;
+
+    fn settings() -> Settings {
+        Settings {
+            timeout: 100,
+            ..Settings::default()
+        }
+    }
+
+    #[test]
+    fn recorded_checks_are_replayed() {
+        let path = std::env::temp_dir().join(format!("sut-recording-{}.rec", std::process::id()));
+
+        let recorder = Recorder::create(Box::new(Loopback::new(settings())), &path).unwrap();
+        let mut serial = Serial::from_transport(Box::new(recorder));
+
+        assert!(serial.check("a\n", "a\n").unwrap().0);
+        assert!(serial.check("b\n", "b\n").unwrap().0);
+        drop(serial);
+
+        let recording = Recording::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(recording.settings(), Some(settings().to_short_string().as_str()));
+
+        let events: Vec<RecordEvent> = recording.entries.iter().skip(1).map(|entry| entry.event.clone()).collect();
+
+        assert_eq!(events, vec![
+            RecordEvent::Write(b"a\n".to_vec()),
+            RecordEvent::Read(b"a\n".to_vec()),
+            RecordEvent::Write(b"b\n".to_vec()),
+            RecordEvent::Read(b"b\n".to_vec())
+        ]);
+
+        // written data not matching the next block skips ahead to the matching block
+        let mut device = ReplayDevice::new(recording.clone());
+        device.input = b"b".to_vec();
+
+        assert_eq!(device.find_block(), Some(3));
+
+        device.input = b"c".to_vec();
+
+        assert_eq!(device.find_block(), None);
+
+        let loopback = Loopback::with_device(settings(), Box::new(ReplayDevice::new(recording)));
+        let mut serial = Serial::from_transport(Box::new(loopback));
+
+        assert!(serial.check("a\n", "a\n").unwrap().0);
+        assert!(serial.check("b\n", "b\n").unwrap().0);
+        assert!(serial.check("a\n", "a\n").unwrap_err().is_timeout());
+    }
+}
@@ -0,0 +1,704 @@
+/*
+ * File: src/serial/rfc2217.rs
+ * Date: 17.10.2026
+ * Author: MarkAtk
+ *
+ * MIT License
+ *
+ * Copyright (c) 2026 MarkAtk
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies
+ * of the Software, and to permit persons to whom the Software is furnished to do
+ * so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+use std::collections::HashSet;
+use std::io::{self, Write};
+use std::net::TcpStream;
+use std::time::{Duration, Instant};
+use crate::error::{Result, Error};
+use super::settings::{Settings, DataBits, Parity, StopBits, FlowControl};
+use super::transport::Transport;
+use super::tcp;
+
+// telnet commands
+const IAC: u8 = 255;
+const DONT: u8 = 254;
+const DO: u8 = 253;
+const WONT: u8 = 252;
+const WILL: u8 = 251;
+const SB: u8 = 250;
+const SE: u8 = 240;
+
+// telnet options
+const BINARY: u8 = 0;
+const SUPPRESS_GO_AHEAD: u8 = 3;
+const COM_PORT_OPTION: u8 = 44;
+
+// com port option commands (client to server, server responses add 100)
+const SET_BAUDRATE: u8 = 1;
+const SET_DATASIZE: u8 = 2;
+const SET_PARITY: u8 = 3;
+const SET_STOPSIZE: u8 = 4;
+const SET_CONTROL: u8 = 5;
+const NOTIFY_MODEMSTATE: u8 = 7;
+const PURGE_DATA: u8 = 12;
+const SERVER_OFFSET: u8 = 100;
+
+// set control values
+const CONTROL_NO_FLOW_CONTROL: u8 = 1;
+const CONTROL_XON_XOFF: u8 = 2;
+const CONTROL_HARDWARE: u8 = 3;
+const CONTROL_BREAK_ON: u8 = 5;
+const CONTROL_BREAK_OFF: u8 = 6;
+const CONTROL_DTR_ON: u8 = 8;
+const CONTROL_DTR_OFF: u8 = 9;
+const CONTROL_RTS_ON: u8 = 11;
+const CONTROL_RTS_OFF: u8 = 12;
+
+// purge data values
+const PURGE_RECEIVE: u8 = 1;
+const PURGE_TRANSMIT: u8 = 2;
+
+// modem state bits
+const MODEM_CTS: u8 = 0x10;
+const MODEM_DSR: u8 = 0x20;
+const MODEM_RI: u8 = 0x40;
+const MODEM_CD: u8 = 0x80;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum DecoderState {
+    Data,
+    Command,
+    Option(u8),
+    Subnegotiation,
+    SubnegotiationCommand
+}
+
+/// Telnet COM port control (RFC 2217) client connection to a serial device.
+///
+/// Opened with `rfc2217://host:port` as port name. In contrast to a raw TCP connection serial settings and control lines are
+/// negotiated with the remote serial port. Telnet escaping is handled transparently, so data can contain any byte.
+///
+/// # Example
+///
+/// ```no_run
+/// use serial_unit_testing::serial::Serial;
+/// use serial_unit_testing::serial::settings::Parity;
+/// use serial_unit_testing::error::Result;
+///
+/// fn main() -> Result<()> {
+///     let mut serial = Serial::open("rfc2217://192.168.0.10:4001")?;
+///     serial.set_baud_rate(115200)?;
+///     serial.set_parity(Parity::Even)?;
+///
+///     serial.write("Hello World!")?;
+///
+///     Ok(())
+/// }
+/// ```
+pub struct Rfc2217Port {
+    stream: TcpStream,
+    address: String,
+    settings: Settings,
+    state: DecoderState,
+    subnegotiation: Vec<u8>,
+    data: Vec<u8>,
+    local_options: HashSet<u8>,
+    remote_options: HashSet<u8>,
+    com_port_accepted: Option<bool>,
+    modem_state: u8
+}
+
+impl Rfc2217Port {
+    /// Connect to the RFC 2217 server at the given address in the form `host:port`.
+    ///
+    /// After the telnet negotiation all given settings are sent to the server. The settings timeout is used as connection
+    /// timeout as well.
+    pub fn connect(address: &str, settings: Settings) -> Result<Rfc2217Port> {
+        let stream = tcp::connect_stream(address, settings.timeout)?;
+
+        let mut port = Rfc2217Port {
+            stream,
+            address: address.to_string(),
+            settings,
+            state: DecoderState::Data,
+            subnegotiation: Vec::new(),
+            data: Vec::new(),
+            local_options: HashSet::new(),
+            remote_options: HashSet::new(),
+            com_port_accepted: None,
+            modem_state: 0
+        };
+
+        port.negotiate()?;
+
+        port.send_baud_rate(settings.baud_rate)?;
+        port.send_data_bits(settings.data_bits)?;
+        port.send_parity(settings.parity)?;
+        port.send_stop_bits(settings.stop_bits)?;
+        port.send_flow_control(settings.flow_control)?;
+
+        Ok(port)
+    }
+
+    fn negotiate(&mut self) -> Result<()> {
+        for option in &[BINARY, SUPPRESS_GO_AHEAD, COM_PORT_OPTION] {
+            self.local_options.insert(*option);
+            self.stream.write_all(&[IAC, WILL, *option])?;
+        }
+
+        for option in &[BINARY, SUPPRESS_GO_AHEAD] {
+            self.remote_options.insert(*option);
+            self.stream.write_all(&[IAC, DO, *option])?;
+        }
+
+        // wait for the server to accept or refuse the com port option
+        let deadline = Instant::now() + Duration::from_millis(self.settings.timeout);
+
+        while self.com_port_accepted.is_none() && Instant::now() < deadline {
+            match self.receive() {
+                Ok(_) => (),
+                Err(ref e) if e.kind() == io::ErrorKind::TimedOut => break,
+                Err(e) => return Err(Error::from(e))
+            }
+        }
+
+        if self.com_port_accepted == Some(false) {
+            return Err(Error::from(io::Error::other("Server refused the RFC 2217 COM port option")));
+        }
+
+        Ok(())
+    }
+
+    fn send_baud_rate(&mut self, baud_rate: u32) -> io::Result<()> {
+        self.send_command(SET_BAUDRATE, &baud_rate.to_be_bytes())
+    }
+
+    fn send_data_bits(&mut self, data_bits: DataBits) -> io::Result<()> {
+        let value = match data_bits {
+            DataBits::Five => 5,
+            DataBits::Six => 6,
+            DataBits::Seven => 7,
+            DataBits::Eight => 8
+        };
+
+        self.send_command(SET_DATASIZE, &[value])
+    }
+
+    fn send_parity(&mut self, parity: Parity) -> io::Result<()> {
+        let value = match parity {
+            Parity::None => 1,
+            Parity::Odd => 2,
+            Parity::Even => 3
+        };
+
+        self.send_command(SET_PARITY, &[value])
+    }
+
+    fn send_stop_bits(&mut self, stop_bits: StopBits) -> io::Result<()> {
+        let value = match stop_bits {
+            StopBits::One => 1,
+            StopBits::Two => 2
+        };
+
+        self.send_command(SET_STOPSIZE, &[value])
+    }
+
+    fn send_flow_control(&mut self, flow_control: FlowControl) -> io::Result<()> {
+        let value = match flow_control {
+            FlowControl::None => CONTROL_NO_FLOW_CONTROL,
+            FlowControl::Software => CONTROL_XON_XOFF,
+            FlowControl::Hardware => CONTROL_HARDWARE
+        };
+
+        self.send_command(SET_CONTROL, &[value])
+    }
+
+    fn send_command(&mut self, command: u8, value: &[u8]) -> io::Result<()> {
+        let mut message = vec![IAC, SB, COM_PORT_OPTION, command];
+        message.extend(escape(value));
+        message.extend_from_slice(&[IAC, SE]);
+
+        self.stream.write_all(&message)
+    }
+
+    // read from the socket once and decode the received bytes
+    fn receive(&mut self) -> io::Result<()> {
+        let mut buffer = [0; 1024];
+        let length = tcp::read_stream(&mut self.stream, &mut buffer)?;
+
+        self.decode(&buffer[..length])
+    }
+
+    // receive already pending bytes without waiting, e.g. modem state notifications
+    fn poll(&mut self) -> io::Result<()> {
+        self.stream.set_nonblocking(true)?;
+
+        let result = loop {
+            let mut buffer = [0; 1024];
+
+            match tcp::read_stream(&mut self.stream, &mut buffer) {
+                Ok(length) => {
+                    if let Err(e) = self.decode(&buffer[..length]) {
+                        break Err(e);
+                    }
+                },
+                Err(ref e) if e.kind() == io::ErrorKind::TimedOut => break Ok(()),
+                Err(e) => break Err(e)
+            }
+        };
+
+        self.stream.set_nonblocking(false)?;
+
+        result
+    }
+
+    fn decode(&mut self, bytes: &[u8]) -> io::Result<()> {
+        for byte in bytes {
+            let byte = *byte;
+
+            self.state = match self.state {
+                DecoderState::Data if byte == IAC => DecoderState::Command,
+                DecoderState::Data => {
+                    self.data.push(byte);
+
+                    DecoderState::Data
+                },
+                DecoderState::Command => match byte {
+                    IAC => {
+                        self.data.push(IAC);
+
+                        DecoderState::Data
+                    },
+                    DO | DONT | WILL | WONT => DecoderState::Option(byte),
+                    SB => {
+                        self.subnegotiation.clear();
+
+                        DecoderState::Subnegotiation
+                    },
+                    // ignore other commands like go ahead or no operation
+                    _ => DecoderState::Data
+                },
+                DecoderState::Option(command) => {
+                    self.handle_option(command, byte)?;
+
+                    DecoderState::Data
+                },
+                DecoderState::Subnegotiation if byte == IAC => DecoderState::SubnegotiationCommand,
+                DecoderState::Subnegotiation => {
+                    self.subnegotiation.push(byte);
+
+                    DecoderState::Subnegotiation
+                },
+                DecoderState::SubnegotiationCommand => match byte {
+                    IAC => {
+                        self.subnegotiation.push(IAC);
+
+                        DecoderState::Subnegotiation
+                    },
+                    SE => {
+                        self.handle_subnegotiation();
+
+                        DecoderState::Data
+                    },
+                    _ => DecoderState::Data
+                }
+            };
+        }
+
+        Ok(())
+    }
+
+    fn handle_option(&mut self, command: u8, option: u8) -> io::Result<()> {
+        let supported = option == BINARY || option == SUPPRESS_GO_AHEAD || option == COM_PORT_OPTION;
+
+        // only answer state changes to prevent negotiation loops
+        let response = match command {
+            DO if supported => {
+                if option == COM_PORT_OPTION {
+                    self.com_port_accepted = Some(true);
+                }
+
+                if self.local_options.insert(option) { Some(WILL) } else { None }
+            },
+            DO => Some(WONT),
+            DONT => {
+                if option == COM_PORT_OPTION {
+                    self.com_port_accepted = Some(false);
+                }
+
+                if self.local_options.remove(&option) { Some(WONT) } else { None }
+            },
+            WILL if supported && option != COM_PORT_OPTION => {
+                if self.remote_options.insert(option) { Some(DO) } else { None }
+            },
+            WILL => Some(DONT),
+            WONT => {
+                if self.remote_options.remove(&option) { Some(DONT) } else { None }
+            },
+            _ => None
+        };
+
+        if let Some(response) = response {
+            self.stream.write_all(&[IAC, response, option])?;
+        }
+
+        Ok(())
+    }
+
+    fn handle_subnegotiation(&mut self) {
+        if self.subnegotiation.len() < 3 || self.subnegotiation[0] != COM_PORT_OPTION {
+            return;
+        }
+
+        let value = &self.subnegotiation[2..];
+
+        // update settings with the actual values reported by the server
+        match self.subnegotiation[1].wrapping_sub(SERVER_OFFSET) {
+            SET_BAUDRATE if value.len() == 4 => {
+                let baud_rate = u32::from_be_bytes([value[0], value[1], value[2], value[3]]);
+
+                if baud_rate > 0 {
+                    self.settings.baud_rate = baud_rate;
+                }
+            },
+            SET_DATASIZE => {
+                self.settings.data_bits = match value[0] {
+                    5 => DataBits::Five,
+                    6 => DataBits::Six,
+                    7 => DataBits::Seven,
+                    8 => DataBits::Eight,
+                    _ => self.settings.data_bits
+                };
+            },
+            SET_PARITY => {
+                self.settings.parity = match value[0] {
+                    1 => Parity::None,
+                    2 => Parity::Odd,
+                    3 => Parity::Even,
+                    _ => self.settings.parity
+                };
+            },
+            SET_STOPSIZE => {
+                self.settings.stop_bits = match value[0] {
+                    1 => StopBits::One,
+                    2 => StopBits::Two,
+                    _ => self.settings.stop_bits
+                };
+            },
+            NOTIFY_MODEMSTATE => self.modem_state = value[0],
+            _ => ()
+        };
+    }
+
+    fn read_modem_line(&mut self, mask: u8) -> Result<bool> {
+        self.poll()?;
+
+        Ok(self.modem_state & mask != 0)
+    }
+}
+
+impl Transport for Rfc2217Port {
+    fn name(&self) -> Option<String> {
+        Some(format!("rfc2217://{}", self.address))
+    }
+
+    fn settings(&self) -> Settings {
+        self.settings
+    }
+
+    fn set_baud_rate(&mut self, baud_rate: u32) -> Result<()> {
+        self.send_baud_rate(baud_rate)?;
+        self.settings.baud_rate = baud_rate;
+
+        Ok(())
+    }
+
+    fn set_data_bits(&mut self, data_bits: DataBits) -> Result<()> {
+        self.send_data_bits(data_bits)?;
+        self.settings.data_bits = data_bits;
+
+        Ok(())
+    }
+
+    fn set_parity(&mut self, parity: Parity) -> Result<()> {
+        self.send_parity(parity)?;
+        self.settings.parity = parity;
+
+        Ok(())
+    }
+
+    fn set_stop_bits(&mut self, stop_bits: StopBits) -> Result<()> {
+        self.send_stop_bits(stop_bits)?;
+        self.settings.stop_bits = stop_bits;
+
+        Ok(())
+    }
+
+    fn set_flow_control(&mut self, flow_control: FlowControl) -> Result<()> {
+        self.send_flow_control(flow_control)?;
+        self.settings.flow_control = flow_control;
+
+        Ok(())
+    }
+
+    fn set_timeout(&mut self, timeout: Duration) -> Result<()> {
+        self.settings.timeout = timeout.as_millis() as u64;
+
+        tcp::set_stream_timeout(&self.stream, self.settings.timeout)?;
+
+        Ok(())
+    }
+
+    fn clear_input(&mut self) -> Result<()> {
+        // data already received from the server is discarded as well
+        self.poll()?;
+        self.data.clear();
+
+        self.send_command(PURGE_DATA, &[PURGE_RECEIVE])?;
+
+        Ok(())
+    }
+
+    fn clear_output(&mut self) -> Result<()> {
+        self.send_command(PURGE_DATA, &[PURGE_TRANSMIT])?;
+
+        Ok(())
+    }
+
+    fn write_request_to_send(&mut self, level: bool) -> Result<()> {
+        self.send_command(SET_CONTROL, &[if level { CONTROL_RTS_ON } else { CONTROL_RTS_OFF }])?;
+
+        Ok(())
+    }
+
+    fn write_data_terminal_ready(&mut self, level: bool) -> Result<()> {
+        self.send_command(SET_CONTROL, &[if level { CONTROL_DTR_ON } else { CONTROL_DTR_OFF }])?;
+
+        Ok(())
+    }
+
+    fn set_break(&mut self, level: bool) -> Result<()> {
+        self.send_command(SET_CONTROL, &[if level { CONTROL_BREAK_ON } else { CONTROL_BREAK_OFF }])?;
+
+        Ok(())
+    }
+
+    fn read_clear_to_send(&mut self) -> Result<bool> {
+        self.read_modem_line(MODEM_CTS)
+    }
+
+    fn read_data_set_ready(&mut self) -> Result<bool> {
+        self.read_modem_line(MODEM_DSR)
+    }
+
+    fn read_ring_indicator(&mut self) -> Result<bool> {
+        self.read_modem_line(MODEM_RI)
+    }
+
+    fn read_carrier_detect(&mut self) -> Result<bool> {
+        self.read_modem_line(MODEM_CD)
+    }
+}
+
+impl io::Write for Rfc2217Port {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.stream.write_all(&escape(buf))?;
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.stream.flush()
+    }
+}
+
+impl io::Read for Rfc2217Port {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let deadline = Instant::now() + Duration::from_millis(self.settings.timeout);
+
+        // received bytes may only contain telnet commands, so keep receiving until data is available
+        while self.data.is_empty() {
+            if Instant::now() > deadline {
+                return Err(io::Error::new(io::ErrorKind::TimedOut, "Timed out"));
+            }
+
+            self.receive()?;
+        }
+
+        let length = self.data.len().min(buf.len());
+        buf[..length].copy_from_slice(&self.data[..length]);
+        self.data.drain(..length);
+
+        Ok(length)
+    }
+}
+
+// double every IAC byte so it is not interpreted as telnet command
+fn escape(bytes: &[u8]) -> Vec<u8> {
+    let mut escaped = Vec::with_capacity(bytes.len());
+
+    for byte in bytes {
+        escaped.push(*byte);
+
+        if *byte == IAC {
+            escaped.push(IAC);
+        }
+    }
+
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Read, Write};
+    use std::net::{TcpListener, TcpStream};
+    use std::thread;
+    use super::*;
+
+    // start a stand-in server accepting the COM port option, the handler is run with the accepted connection
+    fn start_server<F, T>(handler: F) -> (String, thread::JoinHandle<T>)
+        where F: FnOnce(TcpStream) -> T + Send + 'static, T: Send + 'static {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            stream.set_nodelay(true).unwrap();
+            stream.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+            stream.write_all(&[IAC, DO, COM_PORT_OPTION]).unwrap();
+
+            handler(stream)
+        });
+
+        (address, server)
+    }
+
+    fn test_settings() -> Settings {
+        Settings {
+            timeout: 1000,
+            ..Settings::default()
+        }
+    }
+
+    fn contains(bytes: &[u8], sequence: &[u8]) -> bool {
+        bytes.windows(sequence.len()).any(|window| window == sequence)
+    }
+
+    #[test]
+    fn settings_are_sent_as_subnegotiation() {
+        let (address, server) = start_server(|mut stream| {
+            let mut received = Vec::new();
+            stream.read_to_end(&mut received).unwrap();
+
+            received
+        });
+
+        let settings = Settings {
+            baud_rate: 115200,
+            data_bits: DataBits::Seven,
+            parity: Parity::Even,
+            stop_bits: StopBits::Two,
+            flow_control: FlowControl::Hardware,
+            ..test_settings()
+        };
+
+        let mut port = Rfc2217Port::connect(&address, settings).unwrap();
+        port.set_baud_rate(255).unwrap();
+        port.set_flow_control(FlowControl::Software).unwrap();
+        drop(port);
+
+        let received = server.join().unwrap();
+
+        assert!(contains(&received, &[IAC, WILL, COM_PORT_OPTION]));
+        assert!(contains(&received, &[IAC, SB, COM_PORT_OPTION, SET_BAUDRATE, 0x00, 0x01, 0xC2, 0x00, IAC, SE]));
+        assert!(contains(&received, &[IAC, SB, COM_PORT_OPTION, SET_DATASIZE, 7, IAC, SE]));
+        assert!(contains(&received, &[IAC, SB, COM_PORT_OPTION, SET_PARITY, 3, IAC, SE]));
+        assert!(contains(&received, &[IAC, SB, COM_PORT_OPTION, SET_STOPSIZE, 2, IAC, SE]));
+        assert!(contains(&received, &[IAC, SB, COM_PORT_OPTION, SET_CONTROL, CONTROL_HARDWARE, IAC, SE]));
+        assert!(contains(&received, &[IAC, SB, COM_PORT_OPTION, SET_BAUDRATE, 0x00, 0x00, 0x00, IAC, IAC, IAC, SE]));
+        assert!(contains(&received, &[IAC, SB, COM_PORT_OPTION, SET_CONTROL, CONTROL_XON_XOFF, IAC, SE]));
+    }
+
+    #[test]
+    fn data_bytes_are_escaped() {
+        let (address, server) = start_server(|mut stream| {
+            let mut received = Vec::new();
+            stream.read_to_end(&mut received).unwrap();
+
+            received
+        });
+
+        let mut port = Rfc2217Port::connect(&address, test_settings()).unwrap();
+        port.write_all(&[0x01, IAC, 0x02]).unwrap();
+        drop(port);
+
+        let received = server.join().unwrap();
+
+        assert!(received.ends_with(&[0x01, IAC, IAC, 0x02]));
+    }
+
+    #[test]
+    fn split_sequences_and_modem_state_are_decoded() {
+        let (address, server) = start_server(|mut stream| {
+            let chunks: [&[u8]; 6] = [
+                &[b'A', IAC],
+                &[IAC, b'B', IAC],
+                &[SB, COM_PORT_OPTION, NOTIFY_MODEMSTATE + SERVER_OFFSET],
+                &[MODEM_CTS | MODEM_DSR, IAC],
+                &[SE, b'C'],
+                &[IAC, SB, COM_PORT_OPTION, NOTIFY_MODEMSTATE + SERVER_OFFSET, MODEM_DSR | MODEM_RI, IAC, SE, b'D']
+            ];
+
+            for chunk in chunks.iter() {
+                stream.write_all(chunk).unwrap();
+                thread::sleep(Duration::from_millis(20));
+            }
+
+            // keep the connection open until the client is done
+            let mut buffer = Vec::new();
+            stream.read_to_end(&mut buffer).unwrap();
+        });
+
+        let mut port = Rfc2217Port::connect(&address, test_settings()).unwrap();
+
+        let mut data = Vec::new();
+        let mut buffer = [0; 16];
+
+        while data.len() < 4 {
+            let length = port.read(&mut buffer).unwrap();
+            data.extend_from_slice(&buffer[..length]);
+        }
+
+        assert_eq!(data, vec![b'A', IAC, b'B', b'C']);
+        assert!(port.read_clear_to_send().unwrap());
+        assert!(port.read_data_set_ready().unwrap());
+        assert!(!port.read_ring_indicator().unwrap());
+
+        let length = port.read(&mut buffer).unwrap();
+
+        assert_eq!(&buffer[..length], b"D");
+        assert!(!port.read_clear_to_send().unwrap());
+        assert!(port.read_data_set_ready().unwrap());
+        assert!(port.read_ring_indicator().unwrap());
+        assert!(!port.read_carrier_detect().unwrap());
+
+        drop(port);
+        server.join().unwrap();
+    }
+}
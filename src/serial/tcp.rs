@@ -0,0 +1,208 @@
+/*
+ * File: src/serial/tcp.rs
+ * Date: 17.10.2026
+ * Author: MarkAtk
+ *
+ * MIT License
+ *
+ * Copyright (c) 2026 MarkAtk
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies
+ * of the Software, and to permit persons to whom the Software is furnished to do
+ * so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+use std::io::{self, Read};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::Duration;
+use crate::error::Result;
+use super::settings::{Settings, DataBits, Parity, StopBits, FlowControl};
+use super::transport::Transport;
+
+/// Raw TCP socket connection to a serial device.
+///
+/// Used for devices behind serial to network bridges like ser2net or ESP-link, which can be opened with `tcp://host:port` as port
+/// name. Serial settings cannot be changed remotely and are only stored. Reads time out with the same semantics as a serial port.
+///
+/// # Example
+///
+/// ```
+/// use std::io::{Read, Write};
+/// use std::net::TcpListener;
+/// use std::thread;
+/// use serial_unit_testing::serial::Serial;
+/// use serial_unit_testing::error::Result;
+///
+/// fn main() -> Result<()> {
+///     let listener = TcpListener::bind("127.0.0.1:0")?;
+///     let address = listener.local_addr()?;
+///
+///     // echo device
+///     thread::spawn(move || {
+///         let (mut stream, _) = listener.accept().unwrap();
+///         let mut buffer = [0; 32];
+///
+///         let length = stream.read(&mut buffer).unwrap();
+///         stream.write_all(&buffer[..length]).unwrap();
+///     });
+///
+///     let mut serial = Serial::open(&format!("tcp://{}", address))?;
+///     let (result, _) = serial.check("ping", "ping")?;
+///
+///     assert!(result);
+///
+///     Ok(())
+/// }
+/// ```
+pub struct TcpPort {
+    stream: TcpStream,
+    address: String,
+    settings: Settings
+}
+
+impl TcpPort {
+    /// Connect to the given address in the form `host:port`.
+    ///
+    /// The settings timeout is used as connection timeout as well.
+    pub fn connect(address: &str, settings: Settings) -> Result<TcpPort> {
+        let stream = connect_stream(address, settings.timeout)?;
+
+        Ok(TcpPort {
+            stream,
+            address: address.to_string(),
+            settings
+        })
+    }
+}
+
+impl Transport for TcpPort {
+    fn name(&self) -> Option<String> {
+        Some(format!("tcp://{}", self.address))
+    }
+
+    fn settings(&self) -> Settings {
+        self.settings
+    }
+
+    fn set_baud_rate(&mut self, baud_rate: u32) -> Result<()> {
+        self.settings.baud_rate = baud_rate;
+
+        Ok(())
+    }
+
+    fn set_data_bits(&mut self, data_bits: DataBits) -> Result<()> {
+        self.settings.data_bits = data_bits;
+
+        Ok(())
+    }
+
+    fn set_parity(&mut self, parity: Parity) -> Result<()> {
+        self.settings.parity = parity;
+
+        Ok(())
+    }
+
+    fn set_stop_bits(&mut self, stop_bits: StopBits) -> Result<()> {
+        self.settings.stop_bits = stop_bits;
+
+        Ok(())
+    }
+
+    fn set_flow_control(&mut self, flow_control: FlowControl) -> Result<()> {
+        self.settings.flow_control = flow_control;
+
+        Ok(())
+    }
+
+    fn set_timeout(&mut self, timeout: Duration) -> Result<()> {
+        self.settings.timeout = timeout.as_millis() as u64;
+
+        set_stream_timeout(&self.stream, self.settings.timeout)?;
+
+        Ok(())
+    }
+}
+
+impl io::Write for TcpPort {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.stream.write(buf).map_err(map_timeout)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.stream.flush()
+    }
+}
+
+impl io::Read for TcpPort {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        read_stream(&mut self.stream, buf)
+    }
+}
+
+/// Open a TCP connection to the given address with given timeout in milliseconds.
+///
+/// The timeout is applied to connecting as well as reading and writing.
+pub(crate) fn connect_stream(address: &str, timeout: u64) -> io::Result<TcpStream> {
+    let mut last_error = io::Error::new(io::ErrorKind::InvalidInput, format!("Invalid address '{}'", address));
+
+    for socket_address in address.to_socket_addrs()? {
+        let result = if timeout > 0 {
+            TcpStream::connect_timeout(&socket_address, Duration::from_millis(timeout))
+        } else {
+            TcpStream::connect(socket_address)
+        };
+
+        match result {
+            Ok(stream) => {
+                stream.set_nodelay(true)?;
+                set_stream_timeout(&stream, timeout)?;
+
+                return Ok(stream);
+            },
+            Err(e) => last_error = e
+        }
+    }
+
+    Err(last_error)
+}
+
+/// Set read and write timeout of the stream in milliseconds.
+///
+/// Sockets do not support a zero timeout, so it will be rounded up to one millisecond.
+pub(crate) fn set_stream_timeout(stream: &TcpStream, timeout: u64) -> io::Result<()> {
+    let duration = Some(Duration::from_millis(timeout.max(1)));
+
+    stream.set_read_timeout(duration)?;
+    stream.set_write_timeout(duration)
+}
+
+/// Read from the stream and report timeouts and closed connections like a serial port.
+pub(crate) fn read_stream(stream: &mut TcpStream, buf: &mut [u8]) -> io::Result<usize> {
+    match stream.read(buf) {
+        Ok(0) if !buf.is_empty() => Err(io::Error::new(io::ErrorKind::ConnectionAborted, "Connection closed")),
+        Ok(length) => Ok(length),
+        Err(e) => Err(map_timeout(e))
+    }
+}
+
+// sockets report an elapsed timeout as would block on some platforms
+fn map_timeout(error: io::Error) -> io::Error {
+    match error.kind() {
+        io::ErrorKind::WouldBlock => io::Error::new(io::ErrorKind::TimedOut, "Timed out"),
+        _ => error
+    }
+}
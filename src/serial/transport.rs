@@ -0,0 +1,295 @@
+/*
+ * File: src/serial/transport.rs
+ * Date: 17.10.2026
+ * Author: MarkAtk
+ *
+ * MIT License
+ *
+ * Copyright (c) 2026 MarkAtk
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies
+ * of the Software, and to permit persons to whom the Software is furnished to do
+ * so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+use std::io;
+#[cfg(unix)]
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::time::Duration;
+use serialport;
+use crate::error::{Result, Error};
+use super::settings::{Settings, DataBits, Parity, StopBits, FlowControl};
+
+/// A byte stream a serial connection can be established over.
+///
+/// Everything `Serial` needs from a device is described by this trait, so custom transports (e.g. network bridges, simulators or
+/// recorded sessions) can be used with `Serial::from_transport` and in turn with tests and test suites.
+///
+/// Reads must fail with an `io::ErrorKind::TimedOut` error when no data could be read in the timeout duration, otherwise
+/// `Error::is_timeout` will not detect the timeout.
+pub trait Transport: io::Read + io::Write + Send {
+    /// Get the transport name if any exists.
+    fn name(&self) -> Option<String>;
+
+    /// Get the current settings.
+    fn settings(&self) -> Settings;
+
+    /// Set the baud rate.
+    fn set_baud_rate(&mut self, baud_rate: u32) -> Result<()>;
+
+    /// Set the number of data bits.
+    fn set_data_bits(&mut self, data_bits: DataBits) -> Result<()>;
+
+    /// Set the parity checking mode.
+    fn set_parity(&mut self, parity: Parity) -> Result<()>;
+
+    /// Set the number of stop bits.
+    fn set_stop_bits(&mut self, stop_bits: StopBits) -> Result<()>;
+
+    /// Set the flow control.
+    fn set_flow_control(&mut self, flow_control: FlowControl) -> Result<()>;
+
+    /// Set the I/O timeout.
+    fn set_timeout(&mut self, timeout: Duration) -> Result<()>;
+
+    /// Get the I/O timeout.
+    fn timeout(&self) -> Duration {
+        Duration::from_millis(self.settings().timeout)
+    }
+
+    /// Get the baud rate.
+    ///
+    /// Transports connected to real hardware should return the actual device baud rate.
+    fn baud_rate(&self) -> Result<u32> {
+        Ok(self.settings().baud_rate)
+    }
+
+    /// Get the number of data bits.
+    fn data_bits(&self) -> Result<DataBits> {
+        Ok(self.settings().data_bits)
+    }
+
+    /// Get the parity checking mode.
+    fn parity(&self) -> Result<Parity> {
+        Ok(self.settings().parity)
+    }
+
+    /// Get the number of stop bits.
+    fn stop_bits(&self) -> Result<StopBits> {
+        Ok(self.settings().stop_bits)
+    }
+
+    /// Get the flow control.
+    fn flow_control(&self) -> Result<FlowControl> {
+        Ok(self.settings().flow_control)
+    }
+
+    /// Discard all received data not read yet.
+    ///
+    /// Transports without buffers of their own ignore the call, received data is only discarded by reading it.
+    fn clear_input(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Discard all written data not transmitted yet.
+    ///
+    /// Transports without buffers of their own ignore the call.
+    fn clear_output(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Set the RTS (request to send) control line.
+    ///
+    /// Transports without control lines ignore the level.
+    fn write_request_to_send(&mut self, _level: bool) -> Result<()> {
+        Ok(())
+    }
+
+    /// Set the DTR (data terminal ready) control line.
+    ///
+    /// Transports without control lines ignore the level.
+    fn write_data_terminal_ready(&mut self, _level: bool) -> Result<()> {
+        Ok(())
+    }
+
+    /// Start or stop a break condition.
+    ///
+    /// Unlike control lines a break is part of the data stream, so transports without break support fail instead of ignoring it.
+    fn set_break(&mut self, _level: bool) -> Result<()> {
+        Err(Error::from(io::Error::new(io::ErrorKind::Unsupported, "Break conditions are not supported by this port")))
+    }
+
+    /// Read the CTS (clear to send) control line.
+    ///
+    /// Transports without control lines report the line as inactive.
+    fn read_clear_to_send(&mut self) -> Result<bool> {
+        Ok(false)
+    }
+
+    /// Read the DSR (data set ready) control line.
+    ///
+    /// Transports without control lines report the line as inactive.
+    fn read_data_set_ready(&mut self) -> Result<bool> {
+        Ok(false)
+    }
+
+    /// Read the RI (ring indicator) control line.
+    ///
+    /// Transports without control lines report the line as inactive.
+    fn read_ring_indicator(&mut self) -> Result<bool> {
+        Ok(false)
+    }
+
+    /// Read the CD (carrier detect) control line.
+    ///
+    /// Transports without control lines report the line as inactive.
+    fn read_carrier_detect(&mut self) -> Result<bool> {
+        Ok(false)
+    }
+}
+
+/// Break conditions of native serial ports.
+///
+/// serialport has no break support, so breaks are set on the file descriptor of ports where it is available.
+pub trait SerialPortBreak: serialport::SerialPort {
+    /// Start or stop a break condition.
+    fn set_break(&mut self, level: bool) -> Result<()>;
+}
+
+impl SerialPortBreak for dyn serialport::SerialPort {
+    fn set_break(&mut self, _level: bool) -> Result<()> {
+        Err(Error::from(io::Error::new(io::ErrorKind::Unsupported, "Break conditions are not supported by this port")))
+    }
+}
+
+#[cfg(unix)]
+impl SerialPortBreak for serialport::posix::TTYPort {
+    fn set_break(&mut self, level: bool) -> Result<()> {
+        set_break(self.as_raw_fd(), level)
+    }
+}
+
+/// Start or stop a break condition on the terminal with the given file descriptor.
+#[cfg(unix)]
+pub(crate) fn set_break(fd: RawFd, level: bool) -> Result<()> {
+    let request = if level {
+        libc::TIOCSBRK
+    } else {
+        libc::TIOCCBRK
+    };
+
+    // the ioctl only changes the line state of the terminal
+    if unsafe { libc::ioctl(fd, request) } == -1 {
+        return Err(Error::from(io::Error::last_os_error()));
+    }
+
+    Ok(())
+}
+
+impl<P: SerialPortBreak + ?Sized> Transport for Box<P> {
+    fn name(&self) -> Option<String> {
+        self.as_ref().name()
+    }
+
+    fn settings(&self) -> Settings {
+        self.as_ref().settings().into()
+    }
+
+    fn set_baud_rate(&mut self, baud_rate: u32) -> Result<()> {
+        self.as_mut().set_baud_rate(baud_rate).map_err(Error::from)
+    }
+
+    fn set_data_bits(&mut self, data_bits: DataBits) -> Result<()> {
+        self.as_mut().set_data_bits(data_bits.into()).map_err(Error::from)
+    }
+
+    fn set_parity(&mut self, parity: Parity) -> Result<()> {
+        self.as_mut().set_parity(parity.into()).map_err(Error::from)
+    }
+
+    fn set_stop_bits(&mut self, stop_bits: StopBits) -> Result<()> {
+        self.as_mut().set_stop_bits(stop_bits.into()).map_err(Error::from)
+    }
+
+    fn set_flow_control(&mut self, flow_control: FlowControl) -> Result<()> {
+        self.as_mut().set_flow_control(flow_control.into()).map_err(Error::from)
+    }
+
+    fn set_timeout(&mut self, timeout: Duration) -> Result<()> {
+        self.as_mut().set_timeout(timeout).map_err(Error::from)
+    }
+
+    fn timeout(&self) -> Duration {
+        self.as_ref().timeout()
+    }
+
+    fn baud_rate(&self) -> Result<u32> {
+        self.as_ref().baud_rate().map_err(Error::from)
+    }
+
+    fn data_bits(&self) -> Result<DataBits> {
+        self.as_ref().data_bits().map(DataBits::from).map_err(Error::from)
+    }
+
+    fn parity(&self) -> Result<Parity> {
+        self.as_ref().parity().map(Parity::from).map_err(Error::from)
+    }
+
+    fn stop_bits(&self) -> Result<StopBits> {
+        self.as_ref().stop_bits().map(StopBits::from).map_err(Error::from)
+    }
+
+    fn flow_control(&self) -> Result<FlowControl> {
+        self.as_ref().flow_control().map(FlowControl::from).map_err(Error::from)
+    }
+
+    fn clear_input(&mut self) -> Result<()> {
+        self.as_ref().clear(serialport::ClearBuffer::Input).map_err(Error::from)
+    }
+
+    fn clear_output(&mut self) -> Result<()> {
+        self.as_ref().clear(serialport::ClearBuffer::Output).map_err(Error::from)
+    }
+
+    fn write_request_to_send(&mut self, level: bool) -> Result<()> {
+        self.as_mut().write_request_to_send(level).map_err(Error::from)
+    }
+
+    fn write_data_terminal_ready(&mut self, level: bool) -> Result<()> {
+        self.as_mut().write_data_terminal_ready(level).map_err(Error::from)
+    }
+
+    fn set_break(&mut self, level: bool) -> Result<()> {
+        SerialPortBreak::set_break(self.as_mut(), level)
+    }
+
+    fn read_clear_to_send(&mut self) -> Result<bool> {
+        self.as_mut().read_clear_to_send().map_err(Error::from)
+    }
+
+    fn read_data_set_ready(&mut self) -> Result<bool> {
+        self.as_mut().read_data_set_ready().map_err(Error::from)
+    }
+
+    fn read_ring_indicator(&mut self) -> Result<bool> {
+        self.as_mut().read_ring_indicator().map_err(Error::from)
+    }
+
+    fn read_carrier_detect(&mut self) -> Result<bool> {
+        self.as_mut().read_carrier_detect().map_err(Error::from)
+    }
+}
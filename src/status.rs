@@ -0,0 +1,87 @@
+/*
+ * File: src/status.rs
+ * Date: 17.10.2026
+ * Author: MarkAtk
+ *
+ * MIT License
+ *
+ * Copyright (c) 2026 MarkAtk
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies
+ * of the Software, and to permit persons to whom the Software is furnished to do
+ * so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+
+use std::fmt;
+
+/// Exit code of the application on failure.
+///
+/// The application exits with 0 if the command finished successfully.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitCode {
+    /// Generic error, e.g. invalid arguments or files.
+    Error = 1,
+    /// At least one test or check failed.
+    TestFailure = 2,
+    /// Script or rules file could not be parsed.
+    ParseError = 3,
+    /// Serial port could not be opened or the given ports do not match the ports of the script.
+    PortError = 4,
+    /// Serial connection timed out.
+    Timeout = 5
+}
+
+/// Error ending a command with a specific exit code.
+#[derive(Debug)]
+pub struct CommandError {
+    pub code: ExitCode,
+    pub message: Option<String>
+}
+
+impl CommandError {
+    /// Create a new error with given exit code and message.
+    pub fn new(code: ExitCode, message: String) -> CommandError {
+        CommandError {
+            code,
+            message: Some(message)
+        }
+    }
+
+    /// Create a new error without message, e.g. if the result was printed already.
+    pub fn silent(code: ExitCode) -> CommandError {
+        CommandError {
+            code,
+            message: None
+        }
+    }
+}
+
+impl From<String> for CommandError {
+    fn from(message: String) -> CommandError {
+        CommandError::new(ExitCode::Error, message)
+    }
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.message {
+            Some(ref message) => f.write_str(message),
+            None => write!(f, "Exit code {}", self.code as i32)
+        }
+    }
+}
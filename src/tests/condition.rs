@@ -0,0 +1,457 @@
+/*
+ * File: src/tests/condition.rs
+ * Date: 17.10.2026
+ * Author: MarkAtk
+ *
+ * MIT License
+ *
+ * Copyright (c) 2026 MarkAtk
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies
+ * of the Software, and to permit persons to whom the Software is furnished to do
+ * so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+
+use std::cmp::Ordering;
+use std::fmt;
+use crate::tests::TestContext;
+
+/// Condition deciding if a test or test suite is run.
+///
+/// Conditions are expressions over variables of the test context, e.g. variables defined on the command line or captured by
+/// previous tests, and the results of previous tests:
+///
+/// - Variables are used by name (`fw`) or like in contents (`${fw}`)
+/// - Literals are numbers or versions (`2.1`) and strings in quotation marks (`'abc'`)
+/// - Values are compared with `==`, `!=`, `<`, `<=`, `>` and `>=`, numbers and versions are compared by their numeric parts
+/// - Expressions are combined with `&&`, `||`, `!` and parentheses
+/// - `defined(name)` checks if a variable is set, `passed('Test')` and `failed('Test')` check the result of a previous test
+///
+/// Values are true if they are neither empty, `0` nor `false`.
+///
+/// # Example
+///
+/// ```
+/// use serial_unit_testing::tests::{Condition, TestContext};
+///
+/// let condition = Condition::new_if("fw >= 2.1 && !defined(legacy)").unwrap();
+/// let mut context = TestContext::new();
+///
+/// context.set_variable("fw", "2.10");
+/// assert!(condition.is_met(&context).unwrap());
+///
+/// context.set_variable("fw", "2.0.5");
+/// assert!(!condition.is_met(&context).unwrap());
+/// ```
+#[derive(Debug, Clone)]
+pub struct Condition {
+    text: String,
+    expression: Expression,
+    skip_if: bool
+}
+
+impl Condition {
+    /// Create a condition to run only if the expression is true.
+    pub fn new_if(text: &str) -> Result<Condition, String> {
+        Condition::new(text, false)
+    }
+
+    /// Create a condition to skip if the expression is true.
+    pub fn new_skip_if(text: &str) -> Result<Condition, String> {
+        Condition::new(text, true)
+    }
+
+    fn new(text: &str, skip_if: bool) -> Result<Condition, String> {
+        let tokens = tokenize(text)?;
+        let mut parser = ExpressionParser { tokens, position: 0 };
+
+        let expression = parser.parse_or()?;
+
+        if parser.position < parser.tokens.len() {
+            return Err(format!("Unexpected '{}' in condition '{}'", parser.tokens[parser.position], text));
+        }
+
+        Ok(Condition {
+            text: text.to_string(),
+            expression,
+            skip_if
+        })
+    }
+
+    /// Get the expression of the condition.
+    pub fn expression(&self) -> &str {
+        &self.text
+    }
+
+    /// Check if the condition skips when the expression is true.
+    pub fn is_skip_if(&self) -> bool {
+        self.skip_if
+    }
+
+    /// Check if the test or test suite should be run.
+    ///
+    /// Fails if a variable used in the expression is not set.
+    pub fn is_met(&self, context: &TestContext) -> Result<bool, String> {
+        let value = is_true(&self.expression.evaluate(context)?);
+
+        Ok(value != self.skip_if)
+    }
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = if self.skip_if { "skip-if" } else { "if" };
+
+        write!(f, "{} = {}", name, self.text)
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Expression {
+    Literal(String),
+    Variable(String),
+    Defined(String),
+    Passed(String),
+    Failed(String),
+    Not(Box<Expression>),
+    And(Box<Expression>, Box<Expression>),
+    Or(Box<Expression>, Box<Expression>),
+    Compare(Box<Expression>, String, Box<Expression>)
+}
+
+impl Expression {
+    fn evaluate(&self, context: &TestContext) -> Result<String, String> {
+        let value = match self {
+            Expression::Literal(value) => return Ok(value.clone()),
+            Expression::Variable(name) => match context.variable(name) {
+                Some(value) => return Ok(value.to_string()),
+                None => return Err(format!("Undefined variable '{}'", name))
+            },
+            Expression::Defined(name) => context.variable(name).is_some(),
+            Expression::Passed(name) => context.result(name) == Some(true),
+            Expression::Failed(name) => context.result(name) == Some(false),
+            Expression::Not(expression) => !is_true(&expression.evaluate(context)?),
+            Expression::And(left, right) => is_true(&left.evaluate(context)?) && is_true(&right.evaluate(context)?),
+            Expression::Or(left, right) => is_true(&left.evaluate(context)?) || is_true(&right.evaluate(context)?),
+            Expression::Compare(left, operator, right) => {
+                let ordering = compare(&left.evaluate(context)?, &right.evaluate(context)?);
+
+                match operator.as_str() {
+                    "==" => ordering == Ordering::Equal,
+                    "!=" => ordering != Ordering::Equal,
+                    "<" => ordering == Ordering::Less,
+                    "<=" => ordering != Ordering::Greater,
+                    ">" => ordering == Ordering::Greater,
+                    _ => ordering != Ordering::Less
+                }
+            }
+        };
+
+        Ok(value.to_string())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Text(String),
+    Variable(String),
+    Symbol(&'static str)
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Token::Word(word) => f.write_str(word),
+            Token::Text(text) => write!(f, "'{}'", text),
+            Token::Variable(name) => write!(f, "${{{}}}", name),
+            Token::Symbol(symbol) => f.write_str(symbol)
+        }
+    }
+}
+
+const SYMBOLS: [&str; 11] = ["&&", "||", "==", "!=", "<=", ">=", "<", ">", "!", "(", ")"];
+
+fn tokenize(text: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut rest = text.trim_start();
+
+    while let Some(ch) = rest.chars().next() {
+        if let Some(symbol) = SYMBOLS.iter().find(|symbol| rest.starts_with(*symbol)) {
+            tokens.push(Token::Symbol(symbol));
+            rest = &rest[symbol.len()..];
+        } else if ch == '\'' || ch == '"' {
+            let end = match rest[1..].find(ch) {
+                Some(end) => end + 1,
+                None => return Err(format!("Missing closing quotation mark in condition '{}'", text))
+            };
+
+            tokens.push(Token::Text(rest[1..end].to_string()));
+            rest = &rest[end + 1..];
+        } else if rest.starts_with("${") {
+            let end = match rest.find('}') {
+                Some(end) => end,
+                None => return Err(format!("Missing closing brace in condition '{}'", text))
+            };
+
+            tokens.push(Token::Variable(rest[2..end].to_string()));
+            rest = &rest[end + 1..];
+        } else if is_word_char(ch) {
+            let end = rest.find(|ch: char| !is_word_char(ch)).unwrap_or(rest.len());
+
+            tokens.push(Token::Word(rest[..end].to_string()));
+            rest = &rest[end..];
+        } else {
+            return Err(format!("Unexpected '{}' in condition '{}'", ch, text));
+        }
+
+        rest = rest.trim_start();
+    }
+
+    Ok(tokens)
+}
+
+fn is_word_char(ch: char) -> bool {
+    ch.is_ascii_alphanumeric() || ch == '_' || ch == '.'
+}
+
+struct ExpressionParser {
+    tokens: Vec<Token>,
+    position: usize
+}
+
+impl ExpressionParser {
+    fn parse_or(&mut self) -> Result<Expression, String> {
+        let mut expression = self.parse_and()?;
+
+        while self.accept("||") {
+            expression = Expression::Or(Box::new(expression), Box::new(self.parse_and()?));
+        }
+
+        Ok(expression)
+    }
+
+    fn parse_and(&mut self) -> Result<Expression, String> {
+        let mut expression = self.parse_not()?;
+
+        while self.accept("&&") {
+            expression = Expression::And(Box::new(expression), Box::new(self.parse_not()?));
+        }
+
+        Ok(expression)
+    }
+
+    fn parse_not(&mut self) -> Result<Expression, String> {
+        if self.accept("!") {
+            return Ok(Expression::Not(Box::new(self.parse_not()?)));
+        }
+
+        self.parse_comparison()
+    }
+
+    fn parse_comparison(&mut self) -> Result<Expression, String> {
+        let left = self.parse_operand()?;
+
+        for operator in ["==", "!=", "<=", ">=", "<", ">"].iter() {
+            if self.accept(operator) {
+                return Ok(Expression::Compare(Box::new(left), operator.to_string(), Box::new(self.parse_operand()?)));
+            }
+        }
+
+        Ok(left)
+    }
+
+    fn parse_operand(&mut self) -> Result<Expression, String> {
+        let token = match self.tokens.get(self.position) {
+            Some(token) => token.clone(),
+            None => return Err("Unexpected end of condition".to_string())
+        };
+
+        self.position += 1;
+
+        match token {
+            Token::Symbol("(") => {
+                let expression = self.parse_or()?;
+
+                self.expect(")")?;
+
+                Ok(expression)
+            },
+            Token::Text(text) => Ok(Expression::Literal(text)),
+            Token::Variable(name) => Ok(Expression::Variable(name)),
+            Token::Word(word) if self.accept("(") => {
+                let argument = match self.tokens.get(self.position) {
+                    Some(Token::Word(argument)) | Some(Token::Text(argument)) => argument.clone(),
+                    _ => return Err(format!("Missing argument of '{}'", word))
+                };
+
+                self.position += 1;
+                self.expect(")")?;
+
+                match word.as_str() {
+                    "defined" => Ok(Expression::Defined(argument)),
+                    "passed" => Ok(Expression::Passed(argument)),
+                    "failed" => Ok(Expression::Failed(argument)),
+                    _ => Err(format!("Unknown function '{}'", word))
+                }
+            },
+            // numbers, versions and booleans are literals, everything else is a variable
+            Token::Word(word) if word.starts_with(|ch: char| ch.is_ascii_digit()) || word == "true" || word == "false" => Ok(Expression::Literal(word)),
+            Token::Word(word) => Ok(Expression::Variable(word)),
+            Token::Symbol(symbol) => Err(format!("Unexpected '{}' in condition", symbol))
+        }
+    }
+
+    fn accept(&mut self, symbol: &str) -> bool {
+        if let Some(Token::Symbol(current)) = self.tokens.get(self.position) {
+            if *current == symbol {
+                self.position += 1;
+
+                return true;
+            }
+        }
+
+        false
+    }
+
+    fn expect(&mut self, symbol: &str) -> Result<(), String> {
+        if self.accept(symbol) {
+            Ok(())
+        } else {
+            Err(format!("Missing '{}' in condition", symbol))
+        }
+    }
+}
+
+fn is_true(value: &str) -> bool {
+    !value.is_empty() && value != "0" && value != "false"
+}
+
+// compare numbers and versions by their numeric parts, everything else as text
+fn compare(left: &str, right: &str) -> Ordering {
+    match (numeric_parts(left), numeric_parts(right)) {
+        (Some(left), Some(right)) => {
+            let length = left.len().max(right.len());
+
+            (0..length)
+                .map(|index| left.get(index).unwrap_or(&0).cmp(right.get(index).unwrap_or(&0)))
+                .find(|ordering| *ordering != Ordering::Equal)
+                .unwrap_or(Ordering::Equal)
+        },
+        _ => left.cmp(right)
+    }
+}
+
+fn numeric_parts(value: &str) -> Option<Vec<u64>> {
+    let value = value.trim().trim_start_matches('v');
+
+    if !value.contains('.') {
+        return crate::tests::parse_number(value).map(|number| vec!(number));
+    }
+
+    value.split('.').map(|part| part.parse::<u64>().ok()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_met(text: &str, variables: &[(&str, &str)]) -> Result<bool, String> {
+        let mut context = TestContext::new();
+
+        for (name, value) in variables {
+            context.set_variable(name, value);
+        }
+
+        Condition::new_if(text)?.is_met(&context)
+    }
+
+    #[test]
+    fn precedence() {
+        // evaluated as (!a && b) || c
+        assert_eq!(is_met("!a && b || c", &[("a", "1"), ("b", "1"), ("c", "1")]), Ok(true));
+        assert_eq!(is_met("!a && b || c", &[("a", "1"), ("b", "1"), ("c", "0")]), Ok(false));
+        assert_eq!(is_met("!a && b || c", &[("a", "0"), ("b", "1"), ("c", "0")]), Ok(true));
+        assert_eq!(is_met("!(a && b) || c", &[("a", "1"), ("b", "0"), ("c", "0")]), Ok(true));
+        assert_eq!(is_met("a || b && c", &[("a", "1"), ("b", "0"), ("c", "0")]), Ok(true));
+        assert_eq!(is_met("!!a", &[("a", "true")]), Ok(true));
+
+        // negation applies to comparisons
+        assert_eq!(is_met("!a == 1", &[("a", "1")]), Ok(false));
+        assert_eq!(is_met("!a == 1", &[("a", "2")]), Ok(true));
+    }
+
+    #[test]
+    fn quoted_text() {
+        assert_eq!(is_met("mode == 'fast'", &[("mode", "fast")]), Ok(true));
+        assert_eq!(is_met("mode == \"fast mode\"", &[("mode", "fast mode")]), Ok(true));
+        assert_eq!(is_met("mode != 'a && b'", &[("mode", "a && b")]), Ok(false));
+        assert_eq!(is_met("${mode} == 'fast'", &[("mode", "slow")]), Ok(false));
+        assert_eq!(is_met("''", &[]), Ok(false));
+        assert!(Condition::new_if("mode == 'fast").is_err());
+    }
+
+    #[test]
+    fn functions() {
+        let mut context = TestContext::new();
+        context.set_variable("fw", "2.1");
+        context.set_result("Reset", true);
+        context.set_result("Unlock", false);
+
+        let is_met = |text: &str| Condition::new_if(text).unwrap().is_met(&context).unwrap();
+
+        assert!(is_met("defined(fw)"));
+        assert!(!is_met("defined(legacy)"));
+        assert!(is_met("passed('Reset')"));
+        assert!(!is_met("passed(Unlock)"));
+        assert!(is_met("failed(Unlock)"));
+        assert!(!is_met("failed('Reset')"));
+        assert!(!is_met("passed('Not Run') || failed('Not Run')"));
+
+        assert!(Condition::new_if("unknown(fw)").is_err());
+        assert!(Condition::new_if("defined()").is_err());
+    }
+
+    #[test]
+    fn version_ordering() {
+        assert_eq!(is_met("fw > 2.9", &[("fw", "2.10")]), Ok(true));
+        assert_eq!(is_met("fw < 2.1", &[("fw", "2.0.5")]), Ok(true));
+        assert_eq!(is_met("fw == 2.1.0", &[("fw", "2.1")]), Ok(true));
+        assert_eq!(is_met("fw >= min", &[("fw", "v1.10"), ("min", "v1.2")]), Ok(true));
+        assert_eq!(is_met("count <= 0x10", &[("count", "16")]), Ok(true));
+        assert_eq!(is_met("name < 'b'", &[("name", "a.x")]), Ok(true));
+    }
+
+    #[test]
+    fn undefined_variables() {
+        assert_eq!(is_met("fw > 2", &[]), Err("Undefined variable 'fw'".to_string()));
+        assert_eq!(is_met("${fw}", &[]), Err("Undefined variable 'fw'".to_string()));
+        assert_eq!(is_met("defined(fw) && fw > 2", &[]), Ok(false));
+    }
+
+    #[test]
+    fn skip_if_inverts_result() {
+        let mut context = TestContext::new();
+        context.set_variable("legacy", "1");
+
+        assert!(!Condition::new_skip_if("defined(legacy)").unwrap().is_met(&context).unwrap());
+        assert!(Condition::new_skip_if("!defined(legacy)").unwrap().is_met(&context).unwrap());
+        assert!(Condition::new_skip_if("defined(other)").unwrap().is_met(&context).unwrap());
+        assert_eq!(Condition::new_skip_if("defined(legacy)").unwrap().to_string(), "skip-if = defined(legacy)");
+    }
+}
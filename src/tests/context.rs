@@ -0,0 +1,283 @@
+/*
+ * File: src/tests/context.rs
+ * Date: 17.10.2026
+ * Author: MarkAtk
+ *
+ * MIT License
+ *
+ * Copyright (c) 2026 MarkAtk
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies
+ * of the Software, and to permit persons to whom the Software is furnished to do
+ * so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+
+use std::collections::HashMap;
+use std::fmt;
+use crate::serial::Serial;
+
+// escaped variable reference, replaced by a literal `${`
+const ESCAPED_VARIABLE: &str = "$${";
+
+/// Context shared by all tests of a script while running.
+///
+/// Values captured by named groups in the desired output of a test (e.g. `(?P<id>[0-9]+)`) are stored as variables and can be
+/// used as `${id}` in the input and output of later tests. Numeric values can be formatted with a width and radix like
+/// `${id:02X}`. A literal `${` is written as `$${`.
+///
+/// Tests of scripts using multiple ports select them by name (e.g. `@sniffer`). The port tests are run with is known by the
+/// default port name, all other ports are added to the context.
+#[derive(Default)]
+pub struct TestContext {
+    variables: HashMap<String, String>,
+    default_port: Option<String>,
+    ports: HashMap<String, Serial>,
+    results: HashMap<String, bool>
+}
+
+impl TestContext {
+    /// Create a new context without variables.
+    pub fn new() -> TestContext {
+        Default::default()
+    }
+
+    /// Set the value of a variable.
+    pub fn set_variable(&mut self, name: &str, value: &str) {
+        self.variables.insert(name.to_string(), value.to_string());
+    }
+
+    /// Get the value of a variable.
+    pub fn variable(&self, name: &str) -> Option<&str> {
+        self.variables.get(name).map(String::as_str)
+    }
+
+    /// Set the result of the test with the given name.
+    pub fn set_result(&mut self, name: &str, successful: bool) {
+        self.results.insert(name.to_string(), successful);
+    }
+
+    /// Get the result of the last run of the test with the given name.
+    ///
+    /// If no test with the name was run None will be returned.
+    pub fn result(&self, name: &str) -> Option<bool> {
+        self.results.get(name).copied()
+    }
+
+    /// Set the name of the port tests are run with.
+    pub fn set_default_port(&mut self, name: &str) {
+        self.default_port = Some(name.to_string());
+    }
+
+    /// Add a named port used by tests selecting it.
+    pub fn add_port(&mut self, name: &str, serial: Serial) {
+        self.ports.insert(name.to_string(), serial);
+    }
+
+    /// Get the port with the given name or the given default port if no name is set.
+    ///
+    /// Fails if the name is neither the default port name nor the name of an added port.
+    pub fn port<'a>(&'a mut self, name: Option<&str>, default: &'a mut Serial) -> Result<&'a mut Serial, String> {
+        let name = match name {
+            Some(name) if self.default_port.as_deref() != Some(name) => name,
+            _ => return Ok(default)
+        };
+
+        match self.ports.get_mut(name) {
+            Some(serial) => Ok(serial),
+            None => Err(format!("Unknown port '@{}'", name))
+        }
+    }
+
+    /// Replace all variable references `${name}` in given text with the variable values.
+    ///
+    /// Escaped references like `$${name}` are replaced with the literal `${name}`. Fails if a referenced variable is not set.
+    pub fn substitute(&self, text: &str) -> Result<String, String> {
+        self.substitute_with(text, |value| value.to_string())
+    }
+
+    /// Replace all variable references like `substitute` but pass the values through given function, e.g. to escape them.
+    ///
+    /// Escaped references are passed through the function as well.
+    pub fn substitute_with<F: Fn(&str) -> String>(&self, text: &str, convert: F) -> Result<String, String> {
+        let mut result = String::new();
+        let mut rest = text;
+
+        while let Some((start, name, format, end)) = find_variable(rest) {
+            let value = match self.variable(name) {
+                Some(value) => format_variable(value, format)?,
+                None => return Err(format!("Undefined variable '{}'", name))
+            };
+
+            result.push_str(&rest[..start].replace(ESCAPED_VARIABLE, &convert("${")));
+            result.push_str(&convert(&value));
+
+            rest = &rest[end..];
+        }
+
+        result.push_str(&rest.replace(ESCAPED_VARIABLE, &convert("${")));
+
+        Ok(result)
+    }
+}
+
+impl fmt::Debug for TestContext {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("TestContext")
+            .field("variables", &self.variables)
+            .field("default_port", &self.default_port)
+            .field("ports", &self.ports.keys().collect::<Vec<&String>>())
+            .field("results", &self.results)
+            .finish()
+    }
+}
+
+/// Remove all variable references from given text, e.g. to verify the text without knowing the values.
+///
+/// Escaped references are replaced with the literal `${` escaped for regular expressions, as texts are verified as outputs.
+pub(crate) fn remove_variables(text: &str) -> String {
+    let mut result = String::new();
+    let mut rest = text;
+
+    while let Some((start, _, _, end)) = find_variable(rest) {
+        result.push_str(&rest[..start].replace(ESCAPED_VARIABLE, "\\$\\{"));
+
+        rest = &rest[end..];
+    }
+
+    result.push_str(&rest.replace(ESCAPED_VARIABLE, "\\$\\{"));
+
+    result
+}
+
+/// Replace the references of given parameters in text with their values, e.g. to expand loops while parsing.
+///
+/// References of other variables are kept to be replaced when running the test.
+pub(crate) fn substitute_parameters(text: &str, parameters: &[(String, String)]) -> Result<String, String> {
+    let mut result = String::new();
+    let mut rest = text;
+
+    while let Some((start, name, format, end)) = find_variable(rest) {
+        result.push_str(&rest[..start]);
+
+        match parameters.iter().rev().find(|(parameter, _)| parameter == name) {
+            Some((_, value)) => result.push_str(&format_variable(value, format)?),
+            None => result.push_str(&rest[start..end])
+        };
+
+        rest = &rest[end..];
+    }
+
+    result.push_str(rest);
+
+    Ok(result)
+}
+
+/// Parse a number written in decimal, hexadecimal with `0x` prefix or binary with `0b` prefix.
+pub(crate) fn parse_number(text: &str) -> Option<u64> {
+    let text = text.trim();
+
+    if let Some(hex) = text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        u64::from_str_radix(hex, 16).ok()
+    } else if let Some(binary) = text.strip_prefix("0b").or_else(|| text.strip_prefix("0B")) {
+        u64::from_str_radix(binary, 2).ok()
+    } else {
+        text.parse::<u64>().ok()
+    }
+}
+
+// format a numeric value like 02X (zero padded, width, radix), values without format are used as they are
+fn format_variable(value: &str, format: Option<&str>) -> Result<String, String> {
+    let format = match format {
+        Some(format) => format,
+        None => return Ok(value.to_string())
+    };
+
+    let number = match parse_number(value) {
+        Some(number) => number,
+        None => return Err(format!("Value '{}' of variable with format '{}' is no number", value, format))
+    };
+
+    let zero_padded = format.len() > 1 && format.starts_with('0');
+    let digits = format.trim_end_matches(|ch: char| ch.is_ascii_alphabetic());
+    let radix = &format[digits.len()..];
+
+    let width = if digits.is_empty() {
+        0
+    } else {
+        match digits.parse::<usize>() {
+            Ok(width) => width,
+            Err(_) => return Err(format!("Invalid variable format '{}'", format))
+        }
+    };
+
+    let text = match radix {
+        "" | "d" => number.to_string(),
+        "x" => format!("{:x}", number),
+        "X" => format!("{:X}", number),
+        "o" => format!("{:o}", number),
+        "b" => format!("{:b}", number),
+        _ => return Err(format!("Invalid variable format '{}'", format))
+    };
+
+    let padding = if zero_padded { "0" } else { " " };
+
+    Ok(padding.repeat(width.saturating_sub(text.len())) + &text)
+}
+
+// find the next variable reference and return its start, name, format and end
+fn find_variable(text: &str) -> Option<(usize, &str, Option<&str>, usize)> {
+    let mut offset = 0;
+
+    while let Some(start) = text[offset..].find("${") {
+        let start = offset + start;
+        let name_start = start + 2;
+
+        // escaped references are no variables
+        if text[..start].ends_with('$') {
+            offset = name_start;
+
+            continue;
+        }
+
+        let name_length = text[name_start..].find(|ch: char| !is_variable_char(ch)).unwrap_or(text.len() - name_start);
+        let name_end = name_start + name_length;
+
+        if name_length > 0 && text[name_end..].starts_with('}') {
+            return Some((start, &text[name_start..name_end], None, name_end + 1));
+        }
+
+        // optional format like ${name:02X}
+        if name_length > 0 && text[name_end..].starts_with(':') {
+            let format_start = name_end + 1;
+            let format_length = text[format_start..].find(|ch: char| !ch.is_ascii_alphanumeric()).unwrap_or(text.len() - format_start);
+            let format_end = format_start + format_length;
+
+            if format_length > 0 && text[format_end..].starts_with('}') {
+                return Some((start, &text[name_start..name_end], Some(&text[format_start..format_end]), format_end + 1));
+            }
+        }
+
+        offset = name_start;
+    }
+
+    None
+}
+
+fn is_variable_char(ch: char) -> bool {
+    ch.is_ascii_alphanumeric() || ch == '_'
+}
@@ -0,0 +1,177 @@
+/*
+ * File: src/tests/control.rs
+ * Date: 17.10.2026
+ * Author: MarkAtk
+ *
+ * MIT License
+ *
+ * Copyright (c) 2026 MarkAtk
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies
+ * of the Software, and to permit persons to whom the Software is furnished to do
+ * so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+
+use std::fmt;
+use std::io;
+use std::time::Duration;
+use std::thread::sleep;
+use crate::serial::Serial;
+use crate::error::{Result, Error};
+
+/// Control line of a serial port.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ControlLine {
+    /// RTS (request to send) output.
+    RequestToSend,
+    /// DTR (data terminal ready) output.
+    DataTerminalReady,
+    /// CTS (clear to send) input.
+    ClearToSend,
+    /// DSR (data set ready) input.
+    DataSetReady,
+    /// RI (ring indicator) input.
+    RingIndicator,
+    /// CD (carrier detect) input.
+    CarrierDetect
+}
+
+impl ControlLine {
+    /// Get the control line by its short name, e.g. `rts`.
+    pub fn from_name(name: &str) -> Option<ControlLine> {
+        match name.trim().to_lowercase().as_str() {
+            "rts" => Some(ControlLine::RequestToSend),
+            "dtr" => Some(ControlLine::DataTerminalReady),
+            "cts" => Some(ControlLine::ClearToSend),
+            "dsr" => Some(ControlLine::DataSetReady),
+            "ri" => Some(ControlLine::RingIndicator),
+            "cd" | "dcd" => Some(ControlLine::CarrierDetect),
+            _ => None
+        }
+    }
+
+    /// Get the short name of the control line.
+    pub fn name(self) -> &'static str {
+        match self {
+            ControlLine::RequestToSend => "rts",
+            ControlLine::DataTerminalReady => "dtr",
+            ControlLine::ClearToSend => "cts",
+            ControlLine::DataSetReady => "dsr",
+            ControlLine::RingIndicator => "ri",
+            ControlLine::CarrierDetect => "cd"
+        }
+    }
+
+    /// Check if the line is set by the port, otherwise the line is read.
+    pub fn is_output(self) -> bool {
+        matches!(self, ControlLine::RequestToSend | ControlLine::DataTerminalReady)
+    }
+
+    /// Set the level of an output line.
+    pub fn write(self, serial: &mut Serial, level: bool) -> Result<()> {
+        match self {
+            ControlLine::RequestToSend => serial.write_request_to_send(level),
+            ControlLine::DataTerminalReady => serial.write_data_terminal_ready(level),
+            _ => Err(Error::from(io::Error::new(io::ErrorKind::InvalidInput, format!("Unable to set input line {}", self.name()))))
+        }
+    }
+
+    /// Read the level of an input line.
+    pub fn read(self, serial: &mut Serial) -> Result<bool> {
+        match self {
+            ControlLine::ClearToSend => serial.read_clear_to_send(),
+            ControlLine::DataSetReady => serial.read_data_set_ready(),
+            ControlLine::RingIndicator => serial.read_ring_indicator(),
+            ControlLine::CarrierDetect => serial.read_carrier_detect(),
+            _ => Err(Error::from(io::Error::new(io::ErrorKind::InvalidInput, format!("Unable to read output line {}", self.name()))))
+        }
+    }
+}
+
+/// Step changing or checking the control lines of a serial port.
+///
+/// Steps are written in scripts after an exclamation mark, e.g. `!dtr = low`, `!cts = high`, `!pulse rts 100ms` or `!break 250ms`.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use serial_unit_testing::serial::{Serial, Loopback};
+/// use serial_unit_testing::serial::settings::Settings;
+/// use serial_unit_testing::tests::{ControlStep, ControlLine};
+///
+/// let mut serial = Serial::from_transport(Box::new(Loopback::new(Settings::default())));
+///
+/// ControlStep::Set(ControlLine::RequestToSend, false).run(&mut serial).unwrap();
+///
+/// // the loopback connects RTS to CTS
+/// assert!(ControlStep::Check(ControlLine::ClearToSend, false).run(&mut serial).unwrap());
+/// assert_eq!(ControlStep::Pulse(ControlLine::RequestToSend, true, Duration::from_millis(100)).to_string(), "pulse rts 100ms");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ControlStep {
+    /// Set an output line to the level.
+    Set(ControlLine, bool),
+    /// Check an input line has the level.
+    Check(ControlLine, bool),
+    /// Set an output line to the level for the duration and to the opposite level afterwards.
+    Pulse(ControlLine, bool, Duration),
+    /// Send a break condition for the duration.
+    Break(Duration)
+}
+
+impl ControlStep {
+    /// Run the step on the serial port.
+    ///
+    /// Returns false if a checked line does not have the expected level.
+    pub fn run(&self, serial: &mut Serial) -> Result<bool> {
+        match *self {
+            ControlStep::Set(line, level) => line.write(serial, level)?,
+            ControlStep::Check(line, level) => return Ok(line.read(serial)? == level),
+            ControlStep::Pulse(line, level, duration) => {
+                line.write(serial, level)?;
+
+                sleep(duration);
+
+                line.write(serial, !level)?;
+            },
+            ControlStep::Break(duration) => serial.send_break(duration)?
+        };
+
+        Ok(true)
+    }
+}
+
+impl fmt::Display for ControlStep {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ControlStep::Set(line, level) | ControlStep::Check(line, level) => write!(f, "{} = {}", line.name(), level_name(level)),
+            ControlStep::Pulse(line, true, duration) => write!(f, "pulse {} {}ms", line.name(), duration.as_millis()),
+            ControlStep::Pulse(line, false, duration) => write!(f, "pulse {} low {}ms", line.name(), duration.as_millis()),
+            ControlStep::Break(duration) => write!(f, "break {}ms", duration.as_millis())
+        }
+    }
+}
+
+fn level_name(level: bool) -> &'static str {
+    if level {
+        "high"
+    } else {
+        "low"
+    }
+}
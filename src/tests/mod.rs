@@ -28,6 +28,20 @@
 
 mod test_case;
 mod test_suite;
+mod report;
+mod context;
+mod port_settings;
+mod condition;
+mod timing;
+mod control;
 
-pub use self::test_case::{TestCase, TestCaseSettings};
-pub use self::test_suite::{TestSuite, TestSuiteSettings};
+pub use self::test_case::{TestCase, TestCaseSettings, TestCaseKind};
+pub(crate) use self::test_case::checksum_pattern;
+pub use self::test_suite::{TestSuite, TestSuiteSettings, TestSuiteKind};
+pub use self::context::TestContext;
+pub use self::port_settings::PortSettings;
+pub use self::condition::Condition;
+pub use self::timing::{ResponseTiming, TimingStatistics};
+pub use self::control::{ControlStep, ControlLine};
+pub(crate) use self::context::{remove_variables, substitute_parameters, parse_number};
+pub use self::report::{ReportFormat, TestState, write_report};
@@ -0,0 +1,282 @@
+/*
+ * File: src/tests/port_settings.rs
+ * Date: 17.10.2026
+ * Author: MarkAtk
+ *
+ * MIT License
+ *
+ * Copyright (c) 2026 MarkAtk
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies
+ * of the Software, and to permit persons to whom the Software is furnished to do
+ * so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+
+use crate::serial::Serial;
+use crate::serial::settings::{Settings, DataBits, Parity, StopBits, FlowControl};
+
+/// Serial settings overriding the port settings while running a test or test suite.
+///
+/// Only set properties are changed and restored afterwards.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PortSettings {
+    /// Baud rate in bits per second.
+    pub baud_rate: Option<u32>,
+    /// Number of data bits.
+    pub data_bits: Option<DataBits>,
+    /// Parity bit mode.
+    pub parity: Option<Parity>,
+    /// Number of stop bits.
+    pub stop_bits: Option<StopBits>,
+    /// Flow control mode.
+    pub flow_control: Option<FlowControl>
+}
+
+impl PortSettings {
+    /// Check if no property is set.
+    pub fn is_empty(&self) -> bool {
+        *self == PortSettings::default()
+    }
+
+    /// Merge port settings with other port settings.
+    ///
+    /// Properties will be set if own property is not set but other's is.
+    /// Own properties will not be overwritten.
+    pub fn merge_weak(&mut self, other: &PortSettings) {
+        if self.baud_rate.is_none() {
+            self.baud_rate = other.baud_rate;
+        }
+
+        if self.data_bits.is_none() {
+            self.data_bits = other.data_bits;
+        }
+
+        if self.parity.is_none() {
+            self.parity = other.parity;
+        }
+
+        if self.stop_bits.is_none() {
+            self.stop_bits = other.stop_bits;
+        }
+
+        if self.flow_control.is_none() {
+            self.flow_control = other.flow_control;
+        }
+    }
+
+    /// Apply all set properties to the given serial port.
+    ///
+    /// The previous settings are returned to restore them later.
+    pub fn apply(&self, serial: &mut Serial) -> Result<Settings, String> {
+        let previous = serial.settings();
+
+        if let Err(e) = self.set(serial) {
+            // do not leave the port half configured
+            let _ = self.restore(serial, previous);
+
+            return Err(e);
+        }
+
+        Ok(previous)
+    }
+
+    /// Restore all set properties from the given previous settings.
+    pub fn restore(&self, serial: &mut Serial, previous: Settings) -> Result<(), String> {
+        let restore_settings = PortSettings {
+            baud_rate: self.baud_rate.map(|_| previous.baud_rate),
+            data_bits: self.data_bits.map(|_| previous.data_bits),
+            parity: self.parity.map(|_| previous.parity),
+            stop_bits: self.stop_bits.map(|_| previous.stop_bits),
+            flow_control: self.flow_control.map(|_| previous.flow_control)
+        };
+
+        restore_settings.set(serial)
+    }
+
+    fn set(&self, serial: &mut Serial) -> Result<(), String> {
+        if let Some(baud_rate) = self.baud_rate {
+            if let Err(e) = serial.set_baud_rate(baud_rate) {
+                return Err(format!("Unable to set baud rate: {}", e));
+            }
+        }
+
+        if let Some(data_bits) = self.data_bits {
+            if let Err(e) = serial.set_data_bits(data_bits) {
+                return Err(format!("Unable to set data bits: {}", e));
+            }
+        }
+
+        if let Some(parity) = self.parity {
+            if let Err(e) = serial.set_parity(parity) {
+                return Err(format!("Unable to set parity: {}", e));
+            }
+        }
+
+        if let Some(stop_bits) = self.stop_bits {
+            if let Err(e) = serial.set_stop_bits(stop_bits) {
+                return Err(format!("Unable to set stop bits: {}", e));
+            }
+        }
+
+        if let Some(flow_control) = self.flow_control {
+            if let Err(e) = serial.set_flow_control(flow_control) {
+                return Err(format!("Unable to set flow control: {}", e));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{self, Write};
+    use std::sync::{Arc, Mutex};
+    use crate::serial::{Loopback, Recorder, Recording, RecordEvent};
+    use crate::tests::{TestCase, TestSuite, TestCaseSettings, TestContext};
+    use super::*;
+
+    // output shared with the recorder to check the settings changes
+    #[derive(Clone, Default)]
+    struct SharedOutput(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedOutput {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl SharedOutput {
+        fn settings_changes(&self) -> Vec<String> {
+            let recording = Recording::read_from(self.0.lock().unwrap().as_slice()).unwrap();
+
+            recording.entries.into_iter().filter_map(|entry| match entry.event {
+                RecordEvent::Settings(settings) => Some(settings),
+                _ => None
+            }).collect()
+        }
+    }
+
+    fn recorded_loopback() -> (Serial, SharedOutput) {
+        let output = SharedOutput::default();
+        let loopback = Loopback::new(Settings {
+            timeout: 100,
+            ..Settings::default()
+        });
+
+        let recorder = Recorder::new(Box::new(loopback), Box::new(output.clone())).unwrap();
+
+        (Serial::from_transport(Box::new(recorder)), output)
+    }
+
+    fn baud_rate(baud_rate: u32) -> PortSettings {
+        PortSettings {
+            baud_rate: Some(baud_rate),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn merge_keeps_own_properties() {
+        let mut settings = baud_rate(57600);
+        settings.merge_weak(&PortSettings {
+            baud_rate: Some(19200),
+            parity: Some(Parity::Even),
+            ..Default::default()
+        });
+
+        assert_eq!(settings, PortSettings {
+            baud_rate: Some(57600),
+            parity: Some(Parity::Even),
+            ..Default::default()
+        });
+        assert!(!settings.is_empty());
+        assert!(PortSettings::default().is_empty());
+    }
+
+    #[test]
+    fn group_and_test_overrides_are_restored() {
+        let (mut serial, output) = recorded_loopback();
+
+        let mut test_suite = TestSuite::new_with_settings("G".to_string(), Default::default(), TestCaseSettings {
+            port: baud_rate(19200),
+            ..Default::default()
+        });
+
+        let mut test = TestCase::new("A".to_string(), "a".to_string(), "a".to_string());
+        test.settings.port = PortSettings {
+            baud_rate: Some(57600),
+            parity: Some(Parity::Even),
+            ..Default::default()
+        };
+
+        test_suite.push(test);
+
+        assert_eq!(test_suite.run(&mut serial), Ok(true));
+        assert_eq!(output.settings_changes(), vec!["9600 8N1", "19200 8N1", "57600 8N1", "57600 8E1", "19200 8E1", "19200 8N1", "9600 8N1"]);
+        assert_eq!(serial.settings().baud_rate, 9600);
+    }
+
+    #[test]
+    fn failing_tests_restore_settings() {
+        let (mut serial, output) = recorded_loopback();
+
+        let mut test = TestCase::new("A".to_string(), "a".to_string(), "b".to_string());
+        test.settings.port = baud_rate(57600);
+
+        assert_eq!(test.run(&mut serial), Ok(false));
+
+        let mut test = TestCase::new_expect("B".to_string(), "b".to_string());
+        test.settings.port = baud_rate(115200);
+
+        assert!(test.run(&mut serial).is_err());
+        assert_eq!(output.settings_changes(), vec!["9600 8N1", "57600 8N1", "9600 8N1", "115200 8N1", "9600 8N1"]);
+    }
+
+    #[test]
+    fn overrides_only_change_used_ports() {
+        let (mut serial, output) = recorded_loopback();
+        let (sniffer, sniffer_output) = recorded_loopback();
+
+        let mut context = TestContext::new();
+        context.set_default_port("dut");
+        context.add_port("sniffer", sniffer);
+
+        let mut test = TestCase::new("A".to_string(), "a".to_string(), "a".to_string());
+        test.input_port = Some("sniffer".to_string());
+        test.output_port = Some("sniffer".to_string());
+        test.settings.port = baud_rate(57600);
+
+        assert_eq!(test.run_with_context(&mut serial, &mut context), Ok(true));
+        assert_eq!(output.settings_changes(), vec!["9600 8N1"]);
+        assert_eq!(sniffer_output.settings_changes(), vec!["9600 8N1", "57600 8N1", "9600 8N1"]);
+
+        // tests reading from another port change both ports
+        let mut test = TestCase::new("B".to_string(), "b".to_string(), "b".to_string());
+        test.output_port = Some("sniffer".to_string());
+        test.settings.port = baud_rate(19200);
+
+        assert!(test.run_with_context(&mut serial, &mut context).is_err());
+        assert_eq!(output.settings_changes(), vec!["9600 8N1", "19200 8N1", "9600 8N1"]);
+        assert_eq!(sniffer_output.settings_changes(), vec!["9600 8N1", "57600 8N1", "9600 8N1", "19200 8N1", "9600 8N1"]);
+    }
+}
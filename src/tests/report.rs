@@ -0,0 +1,638 @@
+/*
+ * File: src/tests/report.rs
+ * Date: 17.10.2026
+ * Author: MarkAtk
+ *
+ * MIT License
+ *
+ * Copyright (c) 2026 MarkAtk
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies
+ * of the Software, and to permit persons to whom the Software is furnished to do
+ * so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+
+use std::io::{self, Write};
+use std::time::Duration;
+use crate::tests::{TestCase, TestCaseKind, TestSuite, TestSuiteKind, Condition};
+
+/// Format of a machine readable test report.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    /// JUnit XML format, e.g. for Jenkins or GitLab.
+    JUnit,
+    /// Test anything protocol (TAP) version 13.
+    Tap,
+    /// JSON format.
+    Json
+}
+
+/// State of a test in a report.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TestState {
+    /// The test was not run or skipped because a condition was not met.
+    Skipped,
+    /// The response matched the desired output.
+    Passed,
+    /// The response did not match the desired output.
+    Failed,
+    /// The response did not match the desired output but the test is allowed to fail.
+    AllowedFailure,
+    /// An error occurred while running the test.
+    Error
+}
+
+impl TestState {
+    /// Get the state of the given test.
+    pub fn of(test: &TestCase) -> TestState {
+        if test.error().is_some() {
+            return TestState::Error;
+        }
+
+        match test.is_successful() {
+            Some(true) => TestState::Passed,
+            Some(false) if test.settings.allow_failure.unwrap_or(false) => TestState::AllowedFailure,
+            Some(false) => TestState::Failed,
+            None => TestState::Skipped
+        }
+    }
+
+    /// Get the state name used in reports.
+    pub fn name(self) -> &'static str {
+        match self {
+            TestState::Skipped => "skipped",
+            TestState::Passed => "passed",
+            TestState::Failed => "failed",
+            TestState::AllowedFailure => "allowed-failure",
+            TestState::Error => "error"
+        }
+    }
+}
+
+/// Write a report of the given test suites in the given format.
+///
+/// The report contains one entry per test grouped by test suite. Tests not run or skipped by a condition are reported as skipped.
+///
+/// # Example
+///
+/// ```
+/// use serial_unit_testing::tests::{TestSuite, TestCase, ReportFormat, write_report};
+///
+/// let mut test_suite = TestSuite::new("Group".to_string());
+/// test_suite.push(TestCase::new("Test".to_string(), "a".to_string(), "b".to_string()));
+///
+/// let mut report = Vec::new();
+/// write_report(&mut report, ReportFormat::Tap, &[test_suite]).unwrap();
+///
+/// assert!(String::from_utf8(report).unwrap().starts_with("TAP version 13\n1..1\nok 1 - Group: Test # SKIP"));
+/// ```
+pub fn write_report<W: Write>(writer: &mut W, format: ReportFormat, test_suites: &[TestSuite]) -> io::Result<()> {
+    match format {
+        ReportFormat::JUnit => write_junit(writer, test_suites),
+        ReportFormat::Tap => write_tap(writer, test_suites),
+        ReportFormat::Json => write_json(writer, test_suites)
+    }
+}
+
+fn write_junit<W: Write>(writer: &mut W, test_suites: &[TestSuite]) -> io::Result<()> {
+    let sections = report_sections(test_suites);
+    let tests: Vec<&TestCase> = sections.iter().flat_map(|section| section.tests.iter().cloned()).collect();
+
+    writeln!(writer, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>")?;
+    writeln!(writer, "<testsuites tests=\"{}\" failures=\"{}\" errors=\"{}\" skipped=\"{}\" time=\"{}\">", tests.len(),
+        count(&tests, TestState::Failed), count(&tests, TestState::Error), count(&tests, TestState::Skipped),
+        seconds(total_duration(&tests)))?;
+
+    for section in sections {
+        // unnamed test suites still need a name
+        let mut name = if section.name.is_empty() {
+            format!("Tests {}", section.index + 1)
+        } else {
+            section.name.to_string()
+        };
+
+        if section.is_group_steps() {
+            name = format!("{} ({})", name, section.kind);
+        }
+
+        let tests = section.tests;
+
+        writeln!(writer, "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"{}\" skipped=\"{}\" time=\"{}\">", xml_escape(&name),
+            tests.len(), count(&tests, TestState::Failed), count(&tests, TestState::Error),
+            count(&tests, TestState::Skipped), seconds(total_duration(&tests)))?;
+
+        for test in tests {
+            writeln!(writer, "    <testcase name=\"{}\" classname=\"{}\" time=\"{}\">", xml_escape(&test_name(test)), xml_escape(&name),
+                seconds(test.duration().unwrap_or_default()))?;
+
+            match TestState::of(test) {
+                TestState::Skipped if test.is_skipped() => writeln!(writer, "      <skipped message=\"Condition not met\"/>")?,
+                TestState::Skipped => writeln!(writer, "      <skipped/>")?,
+                TestState::Failed => writeln!(writer, "      <failure message=\"{}\"/>", xml_escape(&failure_message(test)))?,
+                TestState::Error => writeln!(writer, "      <error message=\"{}\"/>", xml_escape(&test.error().unwrap_or_default()))?,
+                // allowed failures do not fail the run, so they are reported as passed with a note
+                TestState::Passed | TestState::AllowedFailure => ()
+            };
+
+            let mut details: Vec<String> = details(test).iter().map(|(key, value)| format!("{}: {}", key, value)).collect();
+
+            if TestState::of(test) == TestState::AllowedFailure {
+                details.insert(0, format!("allowed failure: {}", failure_message(test)));
+            }
+
+            writeln!(writer, "      <system-out>{}</system-out>", xml_escape(&details.join("\n")))?;
+            writeln!(writer, "    </testcase>")?;
+        }
+
+        writeln!(writer, "  </testsuite>")?;
+    }
+
+    writeln!(writer, "</testsuites>")
+}
+
+fn write_tap<W: Write>(writer: &mut W, test_suites: &[TestSuite]) -> io::Result<()> {
+    let sections = report_sections(test_suites);
+
+    writeln!(writer, "TAP version 13")?;
+    writeln!(writer, "1..{}", sections.iter().map(|section| section.tests.len()).sum::<usize>())?;
+
+    let mut number = 0;
+
+    for section in sections {
+        for test in section.tests.iter().cloned() {
+            number += 1;
+
+            let mut name = test_name(test);
+
+            if section.is_group_steps() {
+                name = format!("{}: {}", section.kind, name);
+            }
+
+            if !section.name.is_empty() {
+                name = format!("{}: {}", section.name, name);
+            }
+
+            let name = tap_escape(&name);
+
+            match TestState::of(test) {
+                TestState::Passed => writeln!(writer, "ok {} - {}", number, name)?,
+                TestState::Skipped if test.is_skipped() => writeln!(writer, "ok {} - {} # SKIP condition not met", number, name)?,
+                TestState::Skipped => writeln!(writer, "ok {} - {} # SKIP not run", number, name)?,
+                TestState::AllowedFailure => writeln!(writer, "not ok {} - {} # TODO allowed failure", number, name)?,
+                TestState::Failed | TestState::Error => writeln!(writer, "not ok {} - {}", number, name)?
+            };
+
+            // json strings are valid yaml strings
+            writeln!(writer, "  ---")?;
+
+            match TestState::of(test) {
+                TestState::Failed | TestState::AllowedFailure => writeln!(writer, "  message: {}", json_string(&failure_message(test)))?,
+                TestState::Error => writeln!(writer, "  message: {}", json_option(test.error().as_deref()))?,
+                TestState::Passed | TestState::Skipped => ()
+            };
+
+            for (key, value) in details(test) {
+                writeln!(writer, "  {}: {}", key, value)?;
+            }
+
+            writeln!(writer, "  ...")?;
+        }
+    }
+
+    Ok(())
+}
+
+fn write_json<W: Write>(writer: &mut W, test_suites: &[TestSuite]) -> io::Result<()> {
+    let tests: Vec<&TestCase> = test_suites.iter().flat_map(|test_suite| test_suite.tests()).collect();
+    let steps: Vec<&TestCase> = test_suites.iter().flat_map(|test_suite| test_suite.setup().iter().chain(test_suite.teardown())).collect();
+
+    writeln!(writer, "{{")?;
+    writeln!(writer, "  \"tests\": {},", tests.len())?;
+    writeln!(writer, "  \"passed\": {},", count(&tests, TestState::Passed))?;
+    writeln!(writer, "  \"failed\": {},", count(&tests, TestState::Failed))?;
+    writeln!(writer, "  \"errors\": {},", count(&tests, TestState::Error))?;
+    writeln!(writer, "  \"skipped\": {},", count(&tests, TestState::Skipped))?;
+    writeln!(writer, "  \"allowed_failures\": {},", count(&tests, TestState::AllowedFailure))?;
+    writeln!(writer, "  \"failed_steps\": {},", count(&steps, TestState::Failed) + count(&steps, TestState::Error))?;
+    writeln!(writer, "  \"duration\": {},", seconds(total_duration(&tests) + total_duration(&steps)))?;
+    writeln!(writer, "  \"suites\": [")?;
+
+    for (suite_index, test_suite) in test_suites.iter().enumerate() {
+        let kind = match test_suite.kind {
+            TestSuiteKind::Tests => "tests",
+            TestSuiteKind::Setup => "setup",
+            TestSuiteKind::Teardown => "teardown"
+        };
+
+        writeln!(writer, "    {{")?;
+        writeln!(writer, "      \"name\": {},", json_string(&test_suite.name))?;
+        writeln!(writer, "      \"kind\": {},", json_string(kind))?;
+        write_json_tests(writer, "setup", test_suite.setup(), ",")?;
+        write_json_tests(writer, "tests", test_suite.tests(), ",")?;
+        write_json_tests(writer, "teardown", test_suite.teardown(), "")?;
+        writeln!(writer, "    }}{}", separator(suite_index, test_suites.len()))?;
+    }
+
+    writeln!(writer, "  ]")?;
+    writeln!(writer, "}}")
+}
+
+fn write_json_tests<W: Write>(writer: &mut W, key: &str, tests: &[TestCase], end: &str) -> io::Result<()> {
+    writeln!(writer, "      \"{}\": [", key)?;
+
+    for (index, test) in tests.iter().enumerate() {
+        writeln!(writer, "        {{")?;
+        writeln!(writer, "          \"name\": {},", json_string(test.name()))?;
+        writeln!(writer, "          \"state\": {},", json_string(TestState::of(test).name()))?;
+
+        for (key, value) in details(test) {
+            writeln!(writer, "          \"{}\": {},", key, value)?;
+        }
+
+        writeln!(writer, "          \"error\": {}", json_option(test.error().as_deref()))?;
+        writeln!(writer, "        }}{}", separator(index, tests.len()))?;
+    }
+
+    writeln!(writer, "      ]{}", end)
+}
+
+// tests, setup and teardown steps of a test suite are reported separately
+struct ReportSection<'a> {
+    index: usize,
+    name: &'a str,
+    kind: &'static str,
+    group: bool,
+    tests: Vec<&'a TestCase>
+}
+
+impl<'a> ReportSection<'a> {
+    fn is_group_steps(&self) -> bool {
+        self.group && self.kind != "tests"
+    }
+}
+
+fn report_sections(test_suites: &[TestSuite]) -> Vec<ReportSection<'_>> {
+    let mut sections = Vec::new();
+
+    for (index, test_suite) in test_suites.iter().enumerate() {
+        let group = test_suite.kind == TestSuiteKind::Tests;
+
+        for (kind, tests) in [("setup", test_suite.setup()), ("tests", test_suite.tests()), ("teardown", test_suite.teardown())].iter() {
+            // test sections of groups are always reported
+            if tests.is_empty() && !(group && *kind == "tests") {
+                continue;
+            }
+
+            sections.push(ReportSection {
+                index,
+                name: &test_suite.name,
+                kind,
+                group,
+                tests: tests.iter().collect()
+            });
+        }
+    }
+
+    sections
+}
+
+fn count(tests: &[&TestCase], state: TestState) -> usize {
+    tests.iter().filter(|test| TestState::of(test) == state).count()
+}
+
+fn total_duration(tests: &[&TestCase]) -> Duration {
+    tests.iter().filter_map(|test| test.duration()).sum()
+}
+
+fn test_name(test: &TestCase) -> String {
+    if test.name().is_empty() && test.kind() == TestCaseKind::Expect {
+        test.output().to_string()
+    } else if test.name().is_empty() {
+        test.input().to_string()
+    } else {
+        test.name().to_string()
+    }
+}
+
+fn failure_message(test: &TestCase) -> String {
+    if let Some(failure) = test.timing_failure() {
+        return format!("Response {}", failure);
+    }
+
+    match test.response() {
+        Some(response) => format!("Expected '{}' but received '{}'", test.output(), response),
+        None => format!("Expected '{}' but received nothing", test.output())
+    }
+}
+
+// test properties as json values
+fn details(test: &TestCase) -> Vec<(&'static str, String)> {
+    let kind = match test.kind() {
+        TestCaseKind::Check => "check",
+        TestCaseKind::Send => "send",
+        TestCaseKind::Expect => "expect",
+        TestCaseKind::Control => "control"
+    };
+
+    // send only and expect only tests have no output or input, control steps only have an output if they check a line
+    let input = if test.kind() != TestCaseKind::Expect { Some(test.input()) } else { None };
+    let output = match test.kind() {
+        TestCaseKind::Send => None,
+        TestCaseKind::Control if test.output().is_empty() => None,
+        _ => Some(test.output())
+    };
+
+    vec![
+        ("kind", json_string(kind)),
+        ("input", json_option(input)),
+        ("expected", json_option(output)),
+        ("input_port", json_option(test.input_port.as_deref())),
+        ("output_port", json_option(test.output_port.as_deref())),
+        ("actual", json_option(test.response())),
+        ("duration", test.duration().map(seconds).unwrap_or_else(|| "null".to_string())),
+        ("repeat", test.settings.repeat.unwrap_or(0).to_string()),
+        ("attempts", test.attempts().to_string()),
+        ("discarded", test.discarded().len().to_string()),
+        ("allow_failure", test.settings.allow_failure.unwrap_or(false).to_string()),
+        ("latency_min", json_duration(test.latency().map(|latency| latency.min))),
+        ("latency_avg", json_duration(test.latency().map(|latency| latency.average))),
+        ("latency_max", json_duration(test.latency().map(|latency| latency.max))),
+        ("inter_byte_gap_max", json_duration(test.inter_byte_gap().map(|gap| gap.max))),
+        ("condition", json_option(test.settings.condition.as_ref().map(Condition::to_string).as_deref()))
+    ]
+}
+
+fn separator(index: usize, len: usize) -> &'static str {
+    if index + 1 < len {
+        ","
+    } else {
+        ""
+    }
+}
+
+fn seconds(duration: Duration) -> String {
+    format!("{:.3}", duration.as_secs_f64())
+}
+
+// timings need a higher precision than test durations
+fn json_duration(duration: Option<Duration>) -> String {
+    match duration {
+        Some(duration) => format!("{:.6}", duration.as_secs_f64()),
+        None => "null".to_string()
+    }
+}
+
+fn xml_escape(text: &str) -> String {
+    let mut result = String::new();
+
+    for ch in text.chars() {
+        match ch {
+            '&' => result.push_str("&amp;"),
+            '<' => result.push_str("&lt;"),
+            '>' => result.push_str("&gt;"),
+            '"' => result.push_str("&quot;"),
+            '\'' => result.push_str("&apos;"),
+            '\n' | '\r' | '\t' => result.push_str(&format!("&#{};", ch as u32)),
+            // other control characters are not allowed in xml
+            ch if ch.is_control() => result.push_str(&format!("\\u{{{:x}}}", ch as u32)),
+            ch => result.push(ch)
+        };
+    }
+
+    result
+}
+
+// test names must stay on one line and a hash sign starts a directive
+fn tap_escape(text: &str) -> String {
+    let mut result = String::new();
+
+    for ch in text.chars() {
+        match ch {
+            '#' => result.push_str("\\#"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            '\t' => result.push_str("\\t"),
+            ch if ch.is_control() => result.push_str(&format!("\\u{:04x}", ch as u32)),
+            ch => result.push(ch)
+        };
+    }
+
+    result
+}
+
+fn json_string(text: &str) -> String {
+    let mut result = String::from("\"");
+
+    for ch in text.chars() {
+        match ch {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            '\t' => result.push_str("\\t"),
+            ch if ch.is_control() => result.push_str(&format!("\\u{:04x}", ch as u32)),
+            ch => result.push(ch)
+        };
+    }
+
+    result.push('"');
+
+    result
+}
+
+fn json_option(text: Option<&str>) -> String {
+    match text {
+        Some(text) => json_string(text),
+        None => "null".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::serial::Serial;
+    use crate::serial::settings::Settings;
+    use crate::tests::{TestContext, TestSuiteSettings};
+    use super::*;
+
+    // run a group skipped by its condition, a disabled group and a group with a skipped and a successful test
+    fn run_skipped_tests() -> Vec<TestSuite> {
+        let mut skipped_group = TestSuite::new_with_settings("G".to_string(), TestSuiteSettings {
+            condition: Some(Condition::new_if("defined(x)").unwrap()),
+            ..Default::default()
+        }, Default::default());
+        skipped_group.push(TestCase::new("A".to_string(), "a".to_string(), "a".to_string()));
+        skipped_group.push(TestCase::new("B".to_string(), "b".to_string(), "b".to_string()));
+
+        let mut disabled_group = TestSuite::new_with_settings("H".to_string(), TestSuiteSettings {
+            disabled: true,
+            ..Default::default()
+        }, Default::default());
+        disabled_group.push(TestCase::new("C".to_string(), "c".to_string(), "c".to_string()));
+
+        let mut skipped_test = TestCase::new("D".to_string(), "d".to_string(), "d".to_string());
+        skipped_test.settings.condition = Some(Condition::new_skip_if("!defined(x)").unwrap());
+
+        let mut group = TestSuite::new("I".to_string());
+        group.push(skipped_test);
+        group.push(TestCase::new("E".to_string(), "e".to_string(), "e".to_string()));
+
+        let mut serial = Serial::open_with_settings("loopback", Settings {
+            timeout: 100,
+            ..Settings::default()
+        }).unwrap();
+        let mut context = TestContext::new();
+
+        let mut test_suites = vec![skipped_group, disabled_group, group];
+
+        for test_suite in test_suites.iter_mut() {
+            test_suite.run_with_context(&mut serial, &mut context).unwrap();
+        }
+
+        test_suites
+    }
+
+    // run a group with a passed test with characters that need escaping, a repeated test, an allowed failure, a failed test and a test with an error
+    fn run_detailed_tests() -> Vec<TestSuite> {
+        let mut repeated = TestCase::new("Repeated".to_string(), "r".to_string(), "r".to_string());
+        repeated.settings.repeat = Some(3);
+
+        let mut allowed = TestCase::new("Allowed".to_string(), "x".to_string(), "y".to_string());
+        allowed.settings.allow_failure = Some(true);
+
+        let mut group = TestSuite::new("<&\"\u{1}".to_string());
+        group.push(TestCase::new("Escaped <&\"".to_string(), "a<&\"\u{1}\n".to_string(), "a<&\"\u{1}\n".to_string()));
+        group.push(repeated);
+        group.push(allowed);
+        group.push(TestCase::new("Failed".to_string(), "1".to_string(), "2".to_string()));
+        group.push(TestCase::new("Error".to_string(), "${missing}".to_string(), "e".to_string()));
+
+        let mut serial = Serial::open_with_settings("loopback", Settings {
+            timeout: 100,
+            ..Settings::default()
+        }).unwrap();
+
+        let mut test_suites = vec![group];
+
+        assert!(test_suites[0].run(&mut serial).is_err());
+
+        test_suites
+    }
+
+    fn report(format: ReportFormat, test_suites: &[TestSuite]) -> String {
+        let mut report = Vec::new();
+        write_report(&mut report, format, test_suites).unwrap();
+
+        String::from_utf8(report).unwrap()
+    }
+
+    #[test]
+    fn skipped_tests_are_counted_separately() {
+        let test_suites = run_skipped_tests();
+
+        assert_eq!(test_suites.iter().map(TestSuite::skipped).collect::<Vec<usize>>(), vec![2, 1, 1]);
+        assert_eq!(test_suites.iter().map(TestSuite::successful).sum::<usize>(), 1);
+        assert_eq!(test_suites.iter().map(TestSuite::failed).sum::<usize>(), 0);
+        assert!(test_suites[0].is_skipped());
+        assert!(test_suites[1].is_skipped());
+        assert!(!test_suites[2].is_skipped());
+    }
+
+    #[test]
+    fn junit_counts_skipped_tests() {
+        let report = report(ReportFormat::JUnit, &run_skipped_tests());
+
+        assert!(report.contains("<testsuites tests=\"5\" failures=\"0\" errors=\"0\" skipped=\"4\""));
+        assert!(report.contains("<testsuite name=\"G\" tests=\"2\" failures=\"0\" errors=\"0\" skipped=\"2\""));
+        assert!(report.contains("<testsuite name=\"H\" tests=\"1\" failures=\"0\" errors=\"0\" skipped=\"1\""));
+        assert!(report.contains("<testsuite name=\"I\" tests=\"2\" failures=\"0\" errors=\"0\" skipped=\"1\""));
+        assert_eq!(report.matches("<skipped message=\"Condition not met\"/>").count(), 4);
+    }
+
+    #[test]
+    fn tap_counts_skipped_tests() {
+        let report = report(ReportFormat::Tap, &run_skipped_tests());
+
+        assert!(report.contains("1..5\n"));
+        assert_eq!(report.matches("# SKIP condition not met").count(), 4);
+        assert!(report.contains("ok 5 - I: E\n"));
+        assert!(!report.contains("not ok"));
+    }
+
+    #[test]
+    fn json_counts_skipped_tests() {
+        let report = report(ReportFormat::Json, &run_skipped_tests());
+
+        assert!(report.contains("\"tests\": 5,\n  \"passed\": 1,\n  \"failed\": 0,\n  \"errors\": 0,\n  \"skipped\": 4,"));
+        assert_eq!(report.matches("\"state\": \"skipped\"").count(), 4);
+        assert_eq!(report.matches("\"state\": \"passed\"").count(), 1);
+    }
+
+    #[test]
+    fn junit_reports_test_details() {
+        let report = report(ReportFormat::JUnit, &run_detailed_tests());
+
+        assert!(report.contains("<testsuites tests=\"5\" failures=\"1\" errors=\"1\" skipped=\"0\""));
+        assert!(report.contains("<testsuite name=\"&lt;&amp;&quot;\\u{1}\" tests=\"5\" failures=\"1\" errors=\"1\" skipped=\"0\""));
+        assert!(report.contains("<testcase name=\"Escaped &lt;&amp;&quot;\" classname=\"&lt;&amp;&quot;\\u{1}\""));
+        assert!(report.contains("&#10;input: &quot;a&lt;&amp;\\&quot;\\u0001\\n&quot;&#10;expected: &quot;a&lt;&amp;\\&quot;\\u0001\\n&quot;&#10;"));
+        assert!(report.contains("&#10;actual: &quot;a&lt;&amp;\\&quot;\\u0001\\n&quot;&#10;"));
+        assert!(report.contains("&#10;repeat: 3&#10;attempts: 4&#10;"));
+        assert!(report.contains("<system-out>allowed failure: Expected &apos;y&apos; but received &apos;x&apos;&#10;"));
+        assert!(report.contains("&#10;allow_failure: true&#10;"));
+        assert!(report.contains("<failure message=\"Expected &apos;2&apos; but received &apos;1&apos;\"/>"));
+        assert!(report.contains("<error message=\"Undefined variable &apos;missing&apos;\"/>"));
+        assert!(report.contains("&#10;actual: null&#10;"));
+        assert_eq!(report.matches("<failure").count(), 1);
+        assert!(!report.contains('\u{1}'));
+    }
+
+    #[test]
+    fn tap_reports_test_details() {
+        let report = report(ReportFormat::Tap, &run_detailed_tests());
+
+        assert!(report.contains("1..5\n"));
+        assert!(report.contains("ok 1 - <&\"\\u0001: Escaped <&\"\n  ---\n  kind: \"check\"\n  input: \"a<&\\\"\\u0001\\n\"\n  expected: \"a<&\\\"\\u0001\\n\"\n"));
+        assert!(report.contains("\n  actual: \"a<&\\\"\\u0001\\n\"\n"));
+        assert!(report.contains("ok 2 - <&\"\\u0001: Repeated\n"));
+        assert!(report.contains("\n  repeat: 3\n  attempts: 4\n"));
+        assert!(report.contains("not ok 3 - <&\"\\u0001: Allowed # TODO allowed failure\n  ---\n  message: \"Expected 'y' but received 'x'\"\n"));
+        assert!(report.contains("\n  allow_failure: true\n"));
+        assert!(report.contains("not ok 4 - <&\"\\u0001: Failed\n  ---\n  message: \"Expected '2' but received '1'\"\n"));
+        assert!(report.contains("not ok 5 - <&\"\\u0001: Error\n  ---\n  message: \"Undefined variable 'missing'\"\n"));
+        assert!(report.contains("\n  actual: null\n"));
+        assert!(!report.contains('\u{1}'));
+    }
+
+    #[test]
+    fn json_reports_test_details() {
+        let report = report(ReportFormat::Json, &run_detailed_tests());
+
+        assert!(report.contains("\"tests\": 5,\n  \"passed\": 2,\n  \"failed\": 1,\n  \"errors\": 1,\n  \"skipped\": 0,\n  \"allowed_failures\": 1,"));
+        assert!(report.contains("\"name\": \"<&\\\"\\u0001\",\n      \"kind\": \"tests\","));
+        assert!(report.contains("\"name\": \"Escaped <&\\\"\",\n          \"state\": \"passed\",\n          \"kind\": \"check\",\n          \"input\": \"a<&\\\"\\u0001\\n\",\n          \"expected\": \"a<&\\\"\\u0001\\n\","));
+        assert!(report.contains("\"actual\": \"a<&\\\"\\u0001\\n\","));
+        assert!(report.contains("\"repeat\": 3,\n          \"attempts\": 4,"));
+        assert!(report.contains("\"name\": \"Allowed\",\n          \"state\": \"allowed-failure\","));
+        assert!(report.contains("\"allow_failure\": true,"));
+        assert!(report.contains("\"name\": \"Failed\",\n          \"state\": \"failed\",\n          \"kind\": \"check\",\n          \"input\": \"1\",\n          \"expected\": \"2\","));
+        assert!(report.contains("\"actual\": null,"));
+        assert!(report.contains("\"error\": \"Undefined variable 'missing'\"\n"));
+        assert_eq!(report.matches("\"error\": null").count(), 4);
+        assert!(!report.contains('\u{1}'));
+    }
+}
@@ -27,13 +27,17 @@
  */
 
 use std::str;
-use std::time::Duration;
+use std::fmt;
+use std::time::{Duration, Instant};
 use std::thread::sleep;
 #[cfg(feature = "colored-tests")]
 use colored::*;
 use regex::Regex;
-use crate::serial::Serial;
-use crate::utils;
+use crate::serial::{Serial, Framing};
+use crate::serial::settings::Settings;
+use crate::tests::{TestContext, PortSettings, Condition, ResponseTiming, TimingStatistics, ControlStep};
+use crate::tests::timing::milliseconds;
+use crate::utils::{self, BytePattern, Checksum, ContentPart};
 
 /// Settings for running a test.
 ///
@@ -51,7 +55,31 @@ pub struct TestCaseSettings {
     /// Allow the test to fail.
     pub allow_failure: Option<bool>,
     /// Print additional information when executing the test.
-    pub verbose: Option<bool>
+    pub verbose: Option<bool>,
+    /// Longest time allowed until the first byte of the response is received.
+    pub max_latency: Option<Duration>,
+    /// Shortest time allowed until the first byte of the response is received.
+    pub min_latency: Option<Duration>,
+    /// Longest time allowed between two received parts of the response.
+    pub max_inter_byte_gap: Option<Duration>,
+    /// Retry the test up to given times until the response matches.
+    pub retry: Option<u32>,
+    /// Wait given duration between retries.
+    pub retry_interval: Option<Duration>,
+    /// Retry the test until the response matches or the given duration is over.
+    pub retry_for: Option<Duration>,
+    /// Framing deciding when the response is complete, otherwise reading stops as soon as the output matches.
+    pub framing: Option<Framing>,
+    /// Discard received data before writing the input.
+    pub flush: Option<bool>,
+    /// Serial settings of the default port while executing the test.
+    ///
+    /// Port settings are not merged as the test suite applies its own port settings for all tests.
+    pub port: PortSettings,
+    /// Condition to run the test, the test is skipped if the condition is not met.
+    ///
+    /// Conditions are not merged as the conditions of test suites decide if the whole test suite is run.
+    pub condition: Option<Condition>
 }
 
 impl TestCaseSettings {
@@ -83,9 +111,54 @@ impl TestCaseSettings {
         if self.verbose.is_none() && other.verbose.is_some() {
             self.verbose = other.verbose;
         }
+
+        if self.max_latency.is_none() && other.max_latency.is_some() {
+            self.max_latency = other.max_latency;
+        }
+
+        if self.min_latency.is_none() && other.min_latency.is_some() {
+            self.min_latency = other.min_latency;
+        }
+
+        if self.max_inter_byte_gap.is_none() && other.max_inter_byte_gap.is_some() {
+            self.max_inter_byte_gap = other.max_inter_byte_gap;
+        }
+
+        if self.retry.is_none() && other.retry.is_some() {
+            self.retry = other.retry;
+        }
+
+        if self.retry_interval.is_none() && other.retry_interval.is_some() {
+            self.retry_interval = other.retry_interval;
+        }
+
+        if self.retry_for.is_none() && other.retry_for.is_some() {
+            self.retry_for = other.retry_for;
+        }
+
+        if self.framing.is_none() && other.framing.is_some() {
+            self.framing = other.framing.clone();
+        }
+
+        if self.flush.is_none() && other.flush.is_some() {
+            self.flush = other.flush;
+        }
     }
 }
 
+/// Steps of a test.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TestCaseKind {
+    /// Write the input and check the response matches the output.
+    Check,
+    /// Only write the input without waiting for a response.
+    Send,
+    /// Only wait for a response matching the output without writing.
+    Expect,
+    /// Change or check the control lines of the port without writing or reading data.
+    Control
+}
+
 /// Test representing a check on the serial.
 #[derive(Debug)]
 pub struct TestCase {
@@ -95,28 +168,81 @@ pub struct TestCase {
     pub input_format: utils::TextFormat,
     /// Text format of the response received by the serial.
     pub output_format: utils::TextFormat,
+    /// Name of the port the input is written to, the default port is used if not set.
+    pub input_port: Option<String>,
+    /// Name of the port the response is read from, the default port is used if not set.
+    pub output_port: Option<String>,
 
     name: String,
+    kind: TestCaseKind,
     input: String,
     output: String,
     response: Option<String>,
     successful: Option<bool>,
-    error: Option<String>
+    skipped: bool,
+    error: Option<String>,
+    timed_out: bool,
+    duration: Option<Duration>,
+    timings: Vec<ResponseTiming>,
+    timing_failure: Option<String>,
+    attempts: u32,
+    discarded: Vec<u8>,
+    control: Option<ControlStep>
 }
 
 impl TestCase {
     /// Create a new test.
     pub fn new(name: String, input: String, output: String) -> TestCase {
+        TestCase::new_with_kind(name, TestCaseKind::Check, input, output)
+    }
+
+    /// Create a new test only writing the input.
+    pub fn new_send(name: String, input: String) -> TestCase {
+        TestCase::new_with_kind(name, TestCaseKind::Send, input, String::new())
+    }
+
+    /// Create a new test only waiting for the output.
+    pub fn new_expect(name: String, output: String) -> TestCase {
+        TestCase::new_with_kind(name, TestCaseKind::Expect, String::new(), output)
+    }
+
+    /// Create a new step changing or checking the control lines.
+    ///
+    /// Control steps use the input port, checked lines are compared like outputs.
+    pub fn new_control(name: String, step: ControlStep) -> TestCase {
+        let output = match step {
+            ControlStep::Check(_, _) => step.to_string(),
+            _ => String::new()
+        };
+
+        let mut test = TestCase::new_with_kind(name, TestCaseKind::Control, step.to_string(), output);
+        test.control = Some(step);
+
+        test
+    }
+
+    fn new_with_kind(name: String, kind: TestCaseKind, input: String, output: String) -> TestCase {
         TestCase {
             name,
+            kind,
             input,
             output,
             settings: Default::default(),
             input_format: utils::TextFormat::Text,
             output_format: utils::TextFormat::Text,
+            input_port: None,
+            output_port: None,
             response: None,
             successful: None,
-            error: None
+            skipped: false,
+            error: None,
+            timed_out: false,
+            duration: None,
+            timings: Vec::new(),
+            timing_failure: None,
+            attempts: 0,
+            discarded: Vec::new(),
+            control: None
         }
     }
 
@@ -124,6 +250,104 @@ impl TestCase {
     ///
     /// After running the test response and successful are set if no error occurred.
     pub fn run(&mut self, serial: &mut Serial) -> Result<bool, String> {
+        self.run_with_context(serial, &mut TestContext::new())
+    }
+
+    /// Execute the test on given serial port with variables of the given context.
+    ///
+    /// Variables are substituted in input and output. On success named groups of the output are stored as variables in the context.
+    /// The test is skipped if its condition is not met. The result of named tests is stored in the context.
+    pub fn run_with_context(&mut self, serial: &mut Serial, context: &mut TestContext) -> Result<bool, String> {
+        self.skipped = false;
+        self.timed_out = false;
+
+        if let Some(ref condition) = self.settings.condition {
+            match condition.is_met(context) {
+                Ok(true) => (),
+                Ok(false) => {
+                    self.skip();
+
+                    return Ok(true);
+                },
+                Err(err) => return self.exit_run_with_error(err)
+            };
+        }
+
+        let previous_settings = match self.apply_port_settings(serial, context) {
+            Ok(settings) => settings,
+            Err(err) => return self.exit_run_with_error(err)
+        };
+
+        let start = Instant::now();
+        let result = self.run_test(serial, context);
+
+        self.duration = Some(start.elapsed());
+
+        if let Err(err) = self.restore_port_settings(serial, context, previous_settings) {
+            return self.exit_run_with_error(err);
+        }
+
+        if !self.name.is_empty() {
+            context.set_result(&self.name, result == Ok(true));
+        }
+
+        result
+    }
+
+    // names of the ports written to or read from, None is the default port
+    fn used_ports(&self) -> Vec<Option<&str>> {
+        match self.kind {
+            TestCaseKind::Send | TestCaseKind::Control => vec![self.input_port.as_deref()],
+            TestCaseKind::Expect => vec![self.output_port.as_deref()],
+            TestCaseKind::Check if self.input_port == self.output_port => vec![self.input_port.as_deref()],
+            TestCaseKind::Check => vec![self.input_port.as_deref(), self.output_port.as_deref()]
+        }
+    }
+
+    // apply the port settings to all used ports and return the previous settings of each port
+    fn apply_port_settings(&self, serial: &mut Serial, context: &mut TestContext) -> Result<Vec<(Option<String>, Settings)>, String> {
+        let mut previous_settings = Vec::new();
+
+        if self.settings.port.is_empty() {
+            return Ok(previous_settings);
+        }
+
+        for name in self.used_ports() {
+            let result = context.port(name, serial).and_then(|port| self.settings.port.apply(port));
+
+            match result {
+                Ok(settings) => previous_settings.push((name.map(str::to_string), settings)),
+                Err(err) => {
+                    // do not leave already changed ports configured
+                    let _ = self.restore_port_settings(serial, context, previous_settings);
+
+                    return Err(err);
+                }
+            };
+        }
+
+        Ok(previous_settings)
+    }
+
+    fn restore_port_settings(&self, serial: &mut Serial, context: &mut TestContext, previous_settings: Vec<(Option<String>, Settings)>) -> Result<(), String> {
+        let mut result = Ok(());
+
+        for (name, settings) in previous_settings.into_iter().rev() {
+            let restored = context.port(name.as_deref(), serial).and_then(|port| self.settings.port.restore(port, settings));
+
+            if result.is_ok() {
+                result = restored;
+            }
+        }
+
+        result
+    }
+
+    fn run_test(&mut self, serial: &mut Serial, context: &mut TestContext) -> Result<bool, String> {
+        if let Some(step) = self.control.clone() {
+            return self.run_control_step(serial, context, &step);
+        }
+
         // get input and desired output in correct format
         let input = if self.input_format == utils::TextFormat::Text {
             self.descape_string(&self.input)
@@ -131,27 +355,30 @@ impl TestCase {
             self.input.clone()
         };
 
-        let mut output = if self.output_format == utils::TextFormat::Text {
-            self.descape_string(&self.output)
-        } else {
-            self.output.clone()
+        let input = match context.substitute(&input) {
+            Ok(input) => input,
+            Err(err) => return self.exit_run_with_error(err)
         };
 
-        if self.settings.ignore_case.unwrap_or(false) {
-            output = output.to_lowercase();
-        } else if self.output_format == utils::TextFormat::Hex {
-            output = output.to_uppercase();
-        }
-
-        let regex = match Regex::new(&output) {
-            Ok(regex) => regex,
-            Err(_) => return self.exit_run_with_error("Error in regex".to_string())
+        // send only tests do not check any response
+        let matcher = if self.kind != TestCaseKind::Send {
+            match self.output_matcher(context) {
+                Ok(matcher) => Some(matcher),
+                Err(err) => return self.exit_run_with_error(err)
+            }
+        } else {
+            None
         };
 
         // run test repeat + 1 times
         let mut repeat = 1;
         let mut success: bool = false;
 
+        self.timings.clear();
+        self.timing_failure = None;
+        self.attempts = 0;
+        self.discarded.clear();
+
         if let Some(count) = self.settings.repeat {
             repeat += count;
         }
@@ -162,24 +389,34 @@ impl TestCase {
                 sleep(delay);
             }
 
-            match serial.write_format(&input, self.input_format) {
-                Ok(_) => (),
-                Err(e) => return self.exit_run_with_error(format!("Unable to write to serial port: {}", e))
-            };
+            // retry until the response matches, all retries are used or the retry duration is over
+            let started = Instant::now();
+            let mut retries = 0;
 
-            let response = match self.read_response(serial, &regex) {
-                Ok(res) => res,
-                Err(err) => return self.exit_run_with_error(err)
-            };
+            success = loop {
+                self.attempts += 1;
 
-            // check if response is correct
-            if let Some(mat) = regex.find(&response) {
-                success = mat.start() == 0 && mat.end() == response.len();
-            } else {
-                success = false;
-            }
+                let result = self.run_attempt(serial, context, &input, matcher.as_ref());
+                let can_retry = self.can_retry(retries, started);
 
-            self.response = Some(response);
+                match result {
+                    Ok(Some(true)) => break true,
+                    Ok(Some(false)) | Ok(None) if can_retry => (),
+                    Ok(Some(false)) => break false,
+                    Ok(None) => {
+                        self.timed_out = true;
+
+                        return self.exit_run_with_error("Connection timed out".to_string());
+                    },
+                    Err(err) => return self.exit_run_with_error(err)
+                };
+
+                retries += 1;
+
+                if let Some(interval) = self.settings.retry_interval {
+                    sleep(interval);
+                }
+            };
 
             if !success {
                 break;
@@ -191,6 +428,150 @@ impl TestCase {
         Ok(success)
     }
 
+    fn run_control_step(&mut self, serial: &mut Serial, context: &mut TestContext, step: &ControlStep) -> Result<bool, String> {
+        if let Some(delay) = self.settings.delay {
+            sleep(delay);
+        }
+
+        let serial = match context.port(self.input_port.as_deref(), serial) {
+            Ok(serial) => serial,
+            Err(err) => return self.exit_run_with_error(err)
+        };
+
+        let success = match step.run(serial) {
+            Ok(success) => success,
+            Err(e) => return self.exit_run_with_error(format!("Unable to control serial port: {}", e))
+        };
+
+        // checked lines have the opposite level on failure
+        if let ControlStep::Check(line, level) = *step {
+            self.response = Some(ControlStep::Check(line, success == level).to_string());
+        }
+
+        self.successful = Some(success);
+
+        Ok(success)
+    }
+
+    // write the input and check the response once, None is returned if no response was received
+    fn run_attempt(&mut self, serial: &mut Serial, context: &mut TestContext, input: &str, matcher: Option<&OutputMatcher>) -> Result<Option<bool>, String> {
+        self.timing_failure = None;
+
+        if self.kind != TestCaseKind::Expect {
+            // leftover data of previous tests would corrupt the response
+            if self.settings.flush.unwrap_or(false) {
+                let output_serial = context.port(self.output_port.as_deref(), serial)?;
+
+                match output_serial.drain_for(Duration::default()) {
+                    Ok(data) => self.discarded.extend(data),
+                    Err(e) => return Err(format!("Unable to flush serial port: {}", e))
+                };
+
+                if let Err(e) = output_serial.clear_input() {
+                    return Err(format!("Unable to flush serial port: {}", e));
+                }
+            }
+
+            let serial = context.port(self.input_port.as_deref(), serial)?;
+
+            if let Err(e) = serial.write_format(input, self.input_format) {
+                return Err(format!("Unable to write to serial port: {}", e));
+            }
+        }
+
+        // latency is measured from writing the input or from starting to wait for expect only tests
+        let sent = Instant::now();
+
+        let matcher = match matcher {
+            Some(matcher) => matcher,
+            None => return Ok(Some(true))
+        };
+
+        let output_serial = context.port(self.output_port.as_deref(), serial)?;
+
+        let (response, data, timing) = match self.read_response(output_serial, matcher, sent)? {
+            Some(result) => result,
+            None => return Ok(None)
+        };
+
+        // check if response is correct
+        let mut success = match matcher {
+            OutputMatcher::Regex(regex, checksums) => match regex.find(&response) {
+                Some(mat) => mat.start() == 0 && mat.end() == response.len() && self.verify_checksums(regex, &response, checksums),
+                None => false
+            },
+            OutputMatcher::Bytes(pattern) => pattern.matches(&data)
+        };
+
+        self.timings.push(timing);
+
+        // timing is only checked for correct responses
+        if success {
+            self.timing_failure = self.check_timing(&timing);
+            success = self.timing_failure.is_none();
+        }
+
+        if let (true, OutputMatcher::Regex(regex, _)) = (success, matcher) {
+            TestCase::capture_variables(regex, &response, context);
+        }
+
+        self.response = Some(response);
+
+        Ok(Some(success))
+    }
+
+    fn can_retry(&self, retries: u32, started: Instant) -> bool {
+        if !self.has_retry_settings() {
+            return false;
+        }
+
+        self.settings.retry.is_none_or(|retry| retries < retry) && self.settings.retry_for.is_none_or(|duration| started.elapsed() < duration)
+    }
+
+    fn has_retry_settings(&self) -> bool {
+        self.settings.retry.is_some() || self.settings.retry_for.is_some()
+    }
+
+    /// Get the steps of the test.
+    pub fn kind(&self) -> TestCaseKind {
+        self.kind
+    }
+
+    /// Get the test name.
+    ///
+    /// The name is empty if the test has no name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get the input send to the serial.
+    ///
+    /// The input is empty if the test only waits for the output.
+    pub fn input(&self) -> &str {
+        &self.input
+    }
+
+    /// Get the desired output.
+    ///
+    /// The output is empty if the test only writes the input.
+    pub fn output(&self) -> &str {
+        &self.output
+    }
+
+    /// Get the last response received.
+    ///
+    /// If the test was not run before or no response was received None will be returned.
+    pub fn response(&self) -> Option<&str> {
+        self.response.as_deref()
+    }
+
+    /// Get the duration of the last run including all repeats.
+    ///
+    /// If the test was not run before None will be returned.
+    pub fn duration(&self) -> Option<Duration> {
+        self.duration
+    }
+
     /// Check if the test was successful.
     ///
     /// If the test was not run before None will be returned.
@@ -198,6 +579,63 @@ impl TestCase {
         self.successful
     }
 
+    /// Get the timing of each response of the last run.
+    ///
+    /// Timings are measured for every repeat of the test, send only tests have no timings.
+    pub fn timings(&self) -> &[ResponseTiming] {
+        &self.timings
+    }
+
+    /// Get the minimum, average and maximum latency of the responses of the last run.
+    ///
+    /// If no response was received None will be returned.
+    pub fn latency(&self) -> Option<TimingStatistics> {
+        TimingStatistics::of(&self.timings.iter().map(|timing| timing.latency).collect::<Vec<Duration>>())
+    }
+
+    /// Get the minimum, average and maximum of the longest inter-byte gap of the responses of the last run.
+    ///
+    /// If no response was received None will be returned.
+    pub fn inter_byte_gap(&self) -> Option<TimingStatistics> {
+        TimingStatistics::of(&self.timings.iter().map(|timing| timing.max_inter_byte_gap).collect::<Vec<Duration>>())
+    }
+
+    /// Get the number of attempts of the last run including all retries and repeats.
+    ///
+    /// Will return 0 if not run before.
+    pub fn attempts(&self) -> u32 {
+        self.attempts
+    }
+
+    /// Get the step of control line tests.
+    pub fn control_step(&self) -> Option<&ControlStep> {
+        self.control.as_ref()
+    }
+
+    /// Get the data discarded before writing the input of all runs.
+    ///
+    /// Data is only discarded if the flush setting is set.
+    pub fn discarded(&self) -> &[u8] {
+        &self.discarded
+    }
+
+    /// Get the reason of the test failing because of the response timing.
+    ///
+    /// If the test did not fail because of the timing None will be returned.
+    pub fn timing_failure(&self) -> Option<&str> {
+        self.timing_failure.as_deref()
+    }
+
+    /// Check if the test was skipped because its condition or the condition of its test suite was not met.
+    pub fn is_skipped(&self) -> bool {
+        self.skipped
+    }
+
+    /// Check if the test failed because no response was received in the timeout duration.
+    pub fn is_timed_out(&self) -> bool {
+        self.timed_out
+    }
+
     /// Get the error from running the test.
     ///
     /// If the test was not run before or no error occurred None will be returned.
@@ -205,58 +643,250 @@ impl TestCase {
         self.error.clone()
     }
 
-    fn read_response(&mut self, serial: &mut Serial, regex: &Regex) -> Result<String, String> {
-        let mut response = String::new();
+    fn output_matcher(&self, context: &TestContext) -> Result<OutputMatcher, String> {
+        let output = if self.output_format == utils::TextFormat::Text {
+            self.descape_string(&self.output)
+        } else {
+            self.output.clone()
+        };
 
-        loop {
-            let response_chunk = if let Some(timeout) = self.settings.timeout {
-                serial.read_with_timeout(timeout)
+        // variable values are matched literally
+        let output = context.substitute_with(&output, regex::escape)?;
+
+        // byte patterns are compared on the received bytes, everything else is a regex over the formatted response
+        if let Some(pattern) = BytePattern::parse(&output, self.output_format) {
+            return Ok(OutputMatcher::Bytes(pattern));
+        }
+
+        let (mut output, checksums) = checksum_pattern(&output, self.output_format)?;
+
+        if self.settings.ignore_case.unwrap_or(false) {
+            output = TestCase::change_case(&output, false);
+        } else if self.output_format == utils::TextFormat::Hex {
+            output = TestCase::change_case(&output, true);
+        }
+
+        match Regex::new(&output) {
+            Ok(regex) => Ok(OutputMatcher::Regex(regex, checksums)),
+            Err(_) => Err("Error in regex".to_string())
+        }
+    }
+
+    // check the received checksums against the checksums of the bytes received before
+    fn verify_checksums(&self, regex: &Regex, response: &str, checksums: &[(String, Checksum)]) -> bool {
+        if checksums.is_empty() {
+            return true;
+        }
+
+        let captures = match regex.captures(response) {
+            Some(captures) => captures,
+            None => return false
+        };
+
+        for (name, checksum) in checksums {
+            let mat = match captures.name(name) {
+                Some(mat) => mat,
+                None => return false
+            };
+
+            let data = response_bytes(&response[..mat.start()], self.output_format);
+            let received = response_bytes(mat.as_str(), self.output_format);
+
+            match (data, received) {
+                (Some(data), Some(received)) if checksum.compute(&data) == received => (),
+                _ => return false
+            };
+        }
+
+        true
+    }
+
+    // check the timing of a response against the timing settings
+    fn check_timing(&self, timing: &ResponseTiming) -> Option<String> {
+        if let Some(max_latency) = self.settings.max_latency {
+            if timing.latency > max_latency {
+                return Some(format!("latency {} exceeds max-latency {}", milliseconds(timing.latency), milliseconds(max_latency)));
+            }
+        }
+
+        if let Some(min_latency) = self.settings.min_latency {
+            if timing.latency < min_latency {
+                return Some(format!("latency {} is below min-latency {}", milliseconds(timing.latency), milliseconds(min_latency)));
+            }
+        }
+
+        if let Some(max_gap) = self.settings.max_inter_byte_gap {
+            if timing.max_inter_byte_gap > max_gap {
+                return Some(format!("inter-byte gap {} exceeds inter-byte-gap {}", milliseconds(timing.max_inter_byte_gap), milliseconds(max_gap)));
+            }
+        }
+
+        None
+    }
+
+    fn has_timing_settings(&self) -> bool {
+        self.settings.max_latency.is_some() || self.settings.min_latency.is_some() || self.settings.max_inter_byte_gap.is_some()
+    }
+
+    fn read_response(&mut self, serial: &mut Serial, matcher: &OutputMatcher, sent: Instant) -> Result<Option<(String, Vec<u8>, ResponseTiming)>, String> {
+        let mut first_received: Option<Instant> = None;
+        let mut last_received = sent;
+        let mut max_inter_byte_gap = Duration::default();
+
+        let mut record_timing = |bytes: &[u8]| {
+            if bytes.is_empty() {
+                return;
+            }
+
+            let received = Instant::now();
+
+            if first_received.is_some() {
+                max_inter_byte_gap = max_inter_byte_gap.max(received - last_received);
             } else {
-                serial.read()
+                first_received = Some(received);
+            }
+
+            last_received = received;
+        };
+
+        let (response, data) = if let Some(ref framing) = self.settings.framing {
+            // framed responses are read completely before matching
+            let data = match serial.read_frame_with(framing, self.settings.timeout, &mut record_timing) {
+                Ok(data) => data,
+                Err(e) if e.is_timeout() => return Ok(None),
+                Err(e) => return Err(format!("Error while running test {}", e))
             };
 
-            match response_chunk {
-                Ok(bytes) => {
-                    let mut new_text = match self.output_format {
-                        utils::TextFormat::Text => str::from_utf8(bytes).unwrap().to_string(),
-                        _ => match utils::radix_string(bytes, &self.output_format) {
-                            Ok(text) => text,
-                            Err(e) => return Err(format!("Error converting response {}", e))
-                        }
-                    };
+            (self.format_response(&data)?, data)
+        } else {
+            let mut data: Vec<u8> = Vec::new();
+            let mut response = String::new();
 
-                    if self.settings.ignore_case.unwrap_or(false) {
-                        new_text = new_text.to_lowercase();
-                    }
+            loop {
+                let response_chunk = if let Some(timeout) = self.settings.timeout {
+                    serial.read_with_timeout(timeout)
+                } else {
+                    serial.read()
+                };
+
+                match response_chunk {
+                    Ok(bytes) => {
+                        record_timing(bytes);
+
+                        // convert all data at once as characters may be split between reads, invalid text will not match
+                        data.extend_from_slice(bytes);
 
-                    response.push_str(new_text.as_str());
+                        response = self.format_response(&data)?;
+
+                        let complete = match matcher {
+                            OutputMatcher::Regex(regex, _) => regex.find(&response).is_some(),
+                            OutputMatcher::Bytes(pattern) => data.len() >= pattern.len()
+                        };
+
+                        if complete {
+                            break;
+                        }
+                    },
+                    Err(e) if e.is_timeout() => {
+                        if data.is_empty() {
+                            return Ok(None);
+                        }
 
-                    if regex.find(&response).is_some() {
                         break;
-                    }
-                },
-                Err(e) if e.is_timeout() => {
-                    if response.is_empty() {
-                        return Err("Connection timed out".to_string());
-                    }
+                    },
+                    Err(e) => return Err(format!("Error while running test {}", e))
+                }
+            }
 
-                    break;
-                },
-                Err(e) => return Err(format!("Error while running test {}", e))
+            (response, data)
+        };
+
+        let timing = ResponseTiming {
+            latency: first_received.unwrap_or(last_received) - sent,
+            max_inter_byte_gap
+        };
+
+        Ok(Some((response, data, timing)))
+    }
+
+    fn format_response(&self, data: &[u8]) -> Result<String, String> {
+        let response = match self.output_format {
+            utils::TextFormat::Text => String::from_utf8_lossy(data).to_string(),
+            _ => match utils::radix_string(data, &self.output_format) {
+                Ok(text) => text,
+                Err(e) => return Err(format!("Error converting response {}", e))
             }
+        };
+
+        if self.settings.ignore_case.unwrap_or(false) {
+            return Ok(response.to_lowercase());
         }
 
         Ok(response)
     }
 
     fn title(&self) -> String {
+        // expect only tests are shown with their output
+        let content = match self.kind {
+            TestCaseKind::Expect => format!(": \"{}\"", self.output),
+            TestCaseKind::Control => format!("!{}", self.input),
+            _ => format!("\"{}\"", self.input)
+        };
+
         if !self.name.is_empty() {
-            format!("{} \"{}\"", self.name, self.input)
+            format!("{} {}", self.name, content)
+        } else if self.kind == TestCaseKind::Expect || self.kind == TestCaseKind::Control {
+            content
         } else {
             self.input.clone()
         }
     }
 
+    // store the named groups of the output as variables
+    fn capture_variables(regex: &Regex, response: &str, context: &mut TestContext) {
+        let captures = match regex.captures(response) {
+            Some(captures) => captures,
+            None => return
+        };
+
+        // checksum groups are no variables
+        for name in regex.capture_names().flatten().filter(|name| !name.starts_with(CHECKSUM_GROUP)) {
+            if let Some(mat) = captures.name(name) {
+                context.set_variable(name, mat.as_str());
+            }
+        }
+    }
+
+    // change the case of a regular expression without changing the group names
+    fn change_case(pattern: &str, upper: bool) -> String {
+        let mut result = String::new();
+        let mut rest = pattern;
+
+        while let Some(start) = rest.find("(?P<") {
+            let end = match rest[start..].find('>') {
+                Some(end) => start + end + 1,
+                None => break
+            };
+
+            result.push_str(&TestCase::change_text_case(&rest[..start], upper));
+            result.push_str(&rest[start..end]);
+
+            rest = &rest[end..];
+        }
+
+        result.push_str(&TestCase::change_text_case(rest, upper));
+
+        result
+    }
+
+    fn change_text_case(text: &str, upper: bool) -> String {
+        if upper {
+            text.to_uppercase()
+        } else {
+            text.to_lowercase()
+        }
+    }
+
     fn descape_string(&self, text: &str) -> String {
         let mut response = String::new();
         let mut descape_next_char = false;
@@ -282,7 +912,16 @@ impl TestCase {
         response
     }
 
-    fn exit_run_with_error(&mut self, err: String) -> Result<bool, String> {
+    pub(crate) fn skip(&mut self) {
+        self.skipped = true;
+        self.successful = None;
+        self.response = None;
+        self.error = None;
+        self.timed_out = false;
+        self.duration = None;
+    }
+
+    pub(crate) fn exit_run_with_error(&mut self, err: String) -> Result<bool, String> {
         self.error = Some(err.clone());
 
         Err(err)
@@ -319,18 +958,111 @@ impl TestCase {
     }
 }
 
-impl ToString for TestCase {
-    fn to_string(&self) -> String {
+const CHECKSUM_GROUP: &str = "__checksum";
+
+// matcher for the desired output of a test
+enum OutputMatcher {
+    // regex over the formatted response with the checksums to verify
+    Regex(Regex, Vec<(String, Checksum)>),
+    // pattern over the received bytes
+    Bytes(BytePattern)
+}
+
+/// Replace the checksum placeholders of a desired output with regex groups matching any checksum.
+///
+/// The names of the groups and their checksums are returned to verify the checksums after matching.
+pub(crate) fn checksum_pattern(output: &str, format: utils::TextFormat) -> Result<(String, Vec<(String, Checksum)>), String> {
+    if format == utils::TextFormat::Text {
+        return Ok((output.to_string(), Vec::new()));
+    }
+
+    let parts = match utils::split_checksums(output) {
+        Ok(parts) => parts,
+        Err(e) => return Err(e.to_string())
+    };
+
+    let mut pattern = String::new();
+    let mut checksums = Vec::new();
+
+    for part in parts {
+        let checksum = match part {
+            ContentPart::Content(content) => {
+                pattern.push_str(content);
+
+                continue;
+            },
+            ContentPart::Checksum(checksum) => checksum
+        };
+
+        // received bytes are formatted with fixed width except for decimal
+        let digits = match format {
+            utils::TextFormat::Binary => "[01]{8}",
+            utils::TextFormat::Octal => "[0-7]{4}",
+            utils::TextFormat::Hex => "[0-9A-F]{2}",
+            _ => return Err("Checksums are not supported in decimal output".to_string())
+        };
+
+        let name = format!("{}{}", CHECKSUM_GROUP, checksums.len());
+        pattern.push_str(&format!("(?P<{}>(?:{}){{{}}})", name, digits, checksum.size()));
+
+        checksums.push((name, checksum));
+    }
+
+    Ok((pattern, checksums))
+}
+
+// convert a received radix string back into bytes
+fn response_bytes(text: &str, format: utils::TextFormat) -> Option<Vec<u8>> {
+    let digits = match format {
+        utils::TextFormat::Binary => 8,
+        utils::TextFormat::Octal => 4,
+        utils::TextFormat::Hex => 2,
+        _ => return None
+    };
+
+    let chars: Vec<char> = text.chars().collect();
+
+    chars.chunks(digits)
+        .map(|chunk| u8::from_str_radix(&chunk.iter().collect::<String>(), format as u32).ok())
+        .collect()
+}
+
+impl fmt::Display for TestCase {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         if let Some(err) = &self.error {
-            return format!("{}...{} {}", self.title(), TestCase::red_text("Error:"), err);
+            return write!(f, "{}...{} {}", self.title(), TestCase::red_text("Error:"), err);
+        }
+
+        if self.skipped {
+            return write!(f, "{}...{}", self.title(), TestCase::yellow_text("Skipped"));
         }
 
         if let Some(successful) = self.successful {
+            let attempts = if self.has_retry_settings() {
+                format!(" after {} attempt{}", self.attempts, if self.attempts == 1 { "" } else { "s" })
+            } else {
+                String::new()
+            };
+
+            // discarded data is shown if verbose
+            let discarded = if self.settings.verbose.unwrap_or(false) && !self.discarded.is_empty() {
+                let data = match self.output_format {
+                    utils::TextFormat::Text => String::from_utf8_lossy(&self.discarded).to_string(),
+                    _ => utils::radix_string(&self.discarded, &self.output_format).unwrap_or_default()
+                };
+
+                format!(", discarded {} byte{}: '{}'", self.discarded.len(), if self.discarded.len() == 1 { "" } else { "s" }, data)
+            } else {
+                String::new()
+            };
+
             if !successful && !self.settings.allow_failure.unwrap_or(false) {
-                return if let Some(ref response) = self.response {
-                    format!("{}...{}, expected '{}' but received '{}'", self.title(), TestCase::red_text("Failed"), self.output, response)
+                return if let Some(ref failure) = self.timing_failure {
+                    write!(f, "{}...{}{}, {}{}", self.title(), TestCase::red_text("Failed"), attempts, failure, discarded)
+                } else if let Some(ref response) = self.response {
+                    write!(f, "{}...{}{}, expected '{}' but received '{}'{}", self.title(), TestCase::red_text("Failed"), attempts, self.output, response, discarded)
                 } else {
-                    format!("{}...{}, expected '{}' but received nothing", self.title(), TestCase::red_text("Failed"), self.output)
+                    write!(f, "{}...{}{}, expected '{}' but received nothing{}", self.title(), TestCase::red_text("Failed"), attempts, self.output, discarded)
                 };
             }
 
@@ -341,7 +1073,7 @@ impl ToString for TestCase {
                 String::new()
             };
 
-            let verbose = if self.settings.verbose.unwrap_or(false) {
+            let verbose = if self.settings.verbose.unwrap_or(false) && self.kind != TestCaseKind::Send && self.kind != TestCaseKind::Control {
                 if let Some(ref response) = self.response {
                     format!(", response: '{}'", response)
                 } else {
@@ -357,9 +1089,386 @@ impl ToString for TestCase {
                 format!("{} (failed)", TestCase::yellow_text("OK"))
             };
 
-            format!("{}...{}{}{}", self.title(), result, repeat, verbose)
+            // timings are shown if checked or verbose
+            let timing = match self.latency() {
+                Some(latency) if self.has_timing_settings() || self.settings.verbose.unwrap_or(false) => {
+                    let gap = match self.inter_byte_gap() {
+                        Some(gap) if self.settings.max_inter_byte_gap.is_some() => format!(", inter-byte gap: {}", gap),
+                        _ => String::new()
+                    };
+
+                    format!(", latency: {}{}", latency, gap)
+                },
+                _ => String::new()
+            };
+
+            write!(f, "{}...{}{}{}{}{}{}", self.title(), result, attempts, repeat, verbose, timing, discarded)
         } else {
-            self.title()
+            f.write_str(&self.title())
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::serial::{Loopback, MockDevice, MockRule, MockPattern, VirtualDevice};
+    use crate::tests::{ControlLine, TestSuite, write_report, ReportFormat};
+    use super::*;
+
+    fn loopback() -> Serial {
+        Serial::open_with_settings("loopback", Settings {
+            timeout: 100,
+            ..Settings::default()
+        }).unwrap()
+    }
+
+    fn run_hex_test(input: &str, output: &str) -> Result<bool, String> {
+        let mut test = TestCase::new(String::new(), input.to_string(), output.to_string());
+        test.input_format = utils::TextFormat::Hex;
+        test.output_format = utils::TextFormat::Hex;
+
+        test.run(&mut loopback())
+    }
+
+    #[test]
+    fn hex_regex_outputs_are_matched_on_formatted_response() {
+        assert_eq!(run_hex_test("0103ABCD", "0103.{4}"), Ok(true));
+        assert_eq!(run_hex_test("0104ABCD", "0103.{4}"), Ok(false));
+    }
+
+    #[test]
+    fn plain_hex_outputs_match_complete_response() {
+        assert_eq!(run_hex_test("0103", "0103"), Ok(true));
+        assert_eq!(run_hex_test("0a0b", "0A0b"), Ok(true));
+        assert_eq!(run_hex_test("0104", "0103"), Ok(false));
+        assert_eq!(run_hex_test("010300", "0103"), Ok(false));
+    }
+
+    #[test]
+    fn flush_discards_all_stale_data() {
+        let mut serial = loopback();
+        let stale = "x".repeat(2500);
+        serial.write(&stale).unwrap();
+
+        let mut test = TestCase::new(String::new(), "OK".to_string(), "OK".to_string());
+        test.settings.flush = Some(true);
+
+        assert_eq!(test.run(&mut serial), Ok(true));
+        assert_eq!(test.discarded(), stale.as_bytes());
+    }
+
+    #[test]
+    fn stale_data_fails_test_without_flush() {
+        let mut serial = loopback();
+        serial.write(&"x".repeat(2500)).unwrap();
+
+        let mut test = TestCase::new(String::new(), "OK".to_string(), "OK".to_string());
+
+        assert_eq!(test.run(&mut serial), Ok(false));
+        assert!(test.discarded().is_empty());
+    }
+
+    #[test]
+    fn send_only_tests_do_not_read() {
+        let mut serial = loopback();
+        let mut test = TestCase::new_send("Reset".to_string(), "RESET\\r".to_string());
+
+        assert_eq!(test.run(&mut serial), Ok(true));
+        assert_eq!(test.response(), None);
+
+        // the loopback still holds the written data
+        assert_eq!(serial.read().unwrap(), b"RESET\r");
+    }
+
+    #[test]
+    fn expect_only_tests_read_unsolicited_data_without_writing() {
+        let mut serial = loopback();
+        serial.write("Ready\r\n").unwrap();
+
+        let mut test = TestCase::new_expect("Boot".to_string(), "Ready\\r\\n".to_string());
+
+        assert_eq!(test.run(&mut serial), Ok(true));
+        assert_eq!(test.response(), Some("Ready\r\n"));
+
+        // nothing was written, so nothing is echoed
+        assert!(serial.read().unwrap_err().is_timeout());
+
+        let mut test = TestCase::new_expect("Boot".to_string(), "Ready".to_string());
+
+        assert!(test.run(&mut serial).is_err());
+        assert!(test.is_timed_out());
+    }
+
+    // mock answering after 40ms with one byte every 20ms
+    fn slow_mock() -> Serial {
+        let mut rule = MockRule::new(MockPattern::Bytes(b"q".to_vec()), b"abcd".to_vec());
+        rule.delay = Duration::from_millis(40);
+        rule.chunk_size = Some(1);
+        rule.chunk_delay = Duration::from_millis(20);
+
+        Serial::from_transport(Box::new(Loopback::with_mock(Settings {
+            timeout: 500,
+            ..Settings::default()
+        }, MockDevice::new(vec![rule]))))
+    }
+
+    fn run_timed(configure: fn(&mut TestCaseSettings)) -> TestCase {
+        let mut test = TestCase::new(String::new(), "q".to_string(), "abcd".to_string());
+        configure(&mut test.settings);
+
+        test.run(&mut slow_mock()).unwrap();
+
+        test
+    }
+
+    #[test]
+    fn max_latency() {
+        let test = run_timed(|settings| settings.max_latency = Some(Duration::from_millis(300)));
+
+        assert_eq!(test.is_successful(), Some(true));
+        assert!(test.latency().unwrap().min >= Duration::from_millis(40));
+
+        let test = run_timed(|settings| settings.max_latency = Some(Duration::from_millis(10)));
+
+        assert_eq!(test.is_successful(), Some(false));
+        assert!(test.timing_failure().unwrap().contains("exceeds max-latency 10"));
+    }
+
+    #[test]
+    fn min_latency() {
+        let test = run_timed(|settings| settings.min_latency = Some(Duration::from_millis(20)));
+
+        assert_eq!(test.is_successful(), Some(true));
+
+        let test = run_timed(|settings| settings.min_latency = Some(Duration::from_millis(300)));
+
+        assert_eq!(test.is_successful(), Some(false));
+        assert!(test.timing_failure().unwrap().contains("is below min-latency 300"));
+    }
+
+    #[test]
+    fn max_inter_byte_gap() {
+        let test = run_timed(|settings| settings.max_inter_byte_gap = Some(Duration::from_millis(300)));
+
+        assert_eq!(test.is_successful(), Some(true));
+        assert!(test.inter_byte_gap().unwrap().max >= Duration::from_millis(15));
+
+        let test = run_timed(|settings| settings.max_inter_byte_gap = Some(Duration::from_millis(5)));
+
+        assert_eq!(test.is_successful(), Some(false));
+        assert!(test.timing_failure().unwrap().contains("exceeds inter-byte-gap 5"));
+    }
+
+    // device answering busy or nothing until the given request
+    struct ReadyAfter {
+        requests: u32,
+        ready_after: u32,
+        busy: Option<&'static [u8]>
+    }
+
+    impl VirtualDevice for ReadyAfter {
+        fn write(&mut self, _data: &[u8]) -> Vec<(Duration, Vec<u8>)> {
+            self.requests += 1;
+
+            let response = if self.requests >= self.ready_after {
+                Some(b"READY".as_ref())
+            } else {
+                self.busy
+            };
+
+            response.map(|response| vec![(Duration::from_millis(0), response.to_vec())]).unwrap_or_default()
+        }
+    }
+
+    fn run_retried(ready_after: u32, busy: Option<&'static [u8]>, configure: fn(&mut TestCaseSettings)) -> (Result<bool, String>, TestCase) {
+        let device = ReadyAfter {
+            requests: 0,
+            ready_after,
+            busy
+        };
+
+        let mut serial = Serial::from_transport(Box::new(Loopback::with_device(Settings {
+            timeout: 50,
+            ..Settings::default()
+        }, Box::new(device))));
+
+        let mut test = TestCase::new("Wait".to_string(), "status".to_string(), "READY".to_string());
+        configure(&mut test.settings);
+
+        (test.run(&mut serial), test)
+    }
+
+    #[test]
+    fn retry_until_matching() {
+        let (result, test) = run_retried(3, Some(b"BUSY"), |settings| settings.retry = Some(3));
+
+        assert_eq!(result, Ok(true));
+        assert_eq!(test.attempts(), 3);
+        assert!(test.to_string().contains("after 3 attempts"));
+
+        let mut test_suite = TestSuite::new("G".to_string());
+        test_suite.push(test);
+
+        let mut report = Vec::new();
+        write_report(&mut report, ReportFormat::Json, &[test_suite]).unwrap();
+
+        assert!(String::from_utf8(report).unwrap().contains("\"attempts\": 3,"));
+    }
+
+    #[test]
+    fn retries_are_limited() {
+        let (result, test) = run_retried(3, Some(b"BUSY"), |settings| settings.retry = Some(1));
+
+        assert_eq!(result, Ok(false));
+        assert_eq!(test.attempts(), 2);
+        assert_eq!(test.response(), Some("BUSY"));
+        assert!(test.to_string().contains("after 2 attempts"));
+
+        // without retry settings a test is attempted once
+        let (result, test) = run_retried(2, Some(b"BUSY"), |_| ());
+
+        assert_eq!(result, Ok(false));
+        assert_eq!(test.attempts(), 1);
+        assert!(!test.to_string().contains("attempt"));
+    }
+
+    #[test]
+    fn retry_on_timeout() {
+        let (result, test) = run_retried(2, None, |settings| settings.retry = Some(1));
+
+        assert_eq!(result, Ok(true));
+        assert_eq!(test.attempts(), 2);
+
+        let (result, test) = run_retried(3, None, |settings| settings.retry = Some(1));
+
+        assert!(result.is_err());
+        assert!(test.is_timed_out());
+        assert_eq!(test.attempts(), 2);
+    }
+
+    #[test]
+    fn retry_for_duration_with_interval() {
+        let start = Instant::now();
+        let (result, test) = run_retried(100, Some(b"BUSY"), |settings| {
+            settings.retry_for = Some(Duration::from_millis(200));
+            settings.retry_interval = Some(Duration::from_millis(40));
+        });
+
+        assert_eq!(result, Ok(false));
+        assert!(start.elapsed() >= Duration::from_millis(200));
+        assert!((2..=6).contains(&test.attempts()), "{}", test.attempts());
+
+        let (result, test) = run_retried(3, Some(b"BUSY"), |settings| {
+            settings.retry = Some(5);
+            settings.retry_interval = Some(Duration::from_millis(50));
+        });
+
+        assert_eq!(result, Ok(true));
+        assert_eq!(test.attempts(), 3);
+        assert!(test.duration().unwrap() >= Duration::from_millis(100));
+    }
+
+    fn run_with_context(input: &str, output: &str, context: &mut TestContext) -> (Result<bool, String>, TestCase) {
+        let mut test = TestCase::new(String::new(), input.to_string(), output.to_string());
+
+        (test.run_with_context(&mut loopback(), context), test)
+    }
+
+    #[test]
+    fn variables_are_captured_from_responses() {
+        let mut context = TestContext::new();
+
+        let (result, _) = run_with_context("session=42 fw=1.2\r\n", "session=(?P<session>[0-9]+) fw=(?P<fw_version>[0-9.]+)\r\n", &mut context);
+
+        assert_eq!(result, Ok(true));
+        assert_eq!(context.variable("session"), Some("42"));
+        assert_eq!(context.variable("fw_version"), Some("1.2"));
+
+        // failing tests do not capture
+        let (result, _) = run_with_context("session=43\r\n", "session=(?P<session>[0-9]+)\n", &mut context);
+
+        assert_eq!(result, Ok(false));
+        assert_eq!(context.variable("session"), Some("42"));
+    }
+
+    #[test]
+    fn variables_are_substituted() {
+        let mut context = TestContext::new();
+        context.set_variable("session", "42");
+        context.set_variable("pattern", "4.");
+
+        let (result, test) = run_with_context("echo ${session}\n", "echo ${session}\n", &mut context);
+
+        assert_eq!(result, Ok(true));
+        assert_eq!(test.response(), Some("echo 42\n"));
+
+        // values are matched literally in outputs
+        assert_eq!(run_with_context("4.", "${pattern}", &mut context).0, Ok(true));
+        assert_eq!(run_with_context("42", "${pattern}", &mut context).0, Ok(false));
+    }
+
+    #[test]
+    fn variables_are_formatted() {
+        let mut context = TestContext::new();
+        context.set_variable("reg", "10");
+        context.set_variable("mask", "0x0F");
+
+        let (result, test) = run_with_context("r${reg:02X} ${reg:x} ${mask:08b} ${mask:4}", "r0A a 00001111   15", &mut context);
+
+        assert_eq!(result, Ok(true), "{:?}", test.response());
+
+        let mut test = TestCase::new(String::new(), "${reg:02X}".to_string(), "0A".to_string());
+        test.input_format = utils::TextFormat::Hex;
+        test.output_format = utils::TextFormat::Hex;
+
+        assert_eq!(test.run_with_context(&mut loopback(), &mut context), Ok(true));
+
+        context.set_variable("name", "abc");
+
+        assert!(run_with_context("${name:02X}", "x", &mut context).0.is_err());
+    }
+
+    #[test]
+    fn escaped_variables_are_literal() {
+        let mut context = TestContext::new();
+        context.set_variable("name", "abc");
+
+        let (result, test) = run_with_context("$${name} ${name} $${undefined}", "$${name} ${name} $${undefined}", &mut context);
+
+        assert_eq!(result, Ok(true));
+        assert_eq!(test.response(), Some("${name} abc ${undefined}"));
+    }
+
+    #[test]
+    fn undefined_variables_are_errors() {
+        let mut context = TestContext::new();
+
+        let (result, test) = run_with_context("${missing}", "x", &mut context);
+
+        assert_eq!(result, Err("Undefined variable 'missing'".to_string()));
+        assert_eq!(test.error(), Some("Undefined variable 'missing'".to_string()));
+
+        let (result, _) = run_with_context("x", "${missing:02X}", &mut context);
+
+        assert_eq!(result, Err("Undefined variable 'missing'".to_string()));
+    }
+
+    #[test]
+    fn control_steps_on_loopback() {
+        let mut serial = loopback();
+
+        let mut run_step = |step: ControlStep| TestCase::new_control(String::new(), step).run(&mut serial);
+
+        // the loopback connects RTS to CTS and DTR to DSR and CD
+        assert_eq!(run_step(ControlStep::Set(ControlLine::RequestToSend, true)), Ok(true));
+        assert_eq!(run_step(ControlStep::Check(ControlLine::ClearToSend, true)), Ok(true));
+        assert_eq!(run_step(ControlStep::Set(ControlLine::DataTerminalReady, false)), Ok(true));
+        assert_eq!(run_step(ControlStep::Check(ControlLine::DataSetReady, true)), Ok(false));
+        assert_eq!(run_step(ControlStep::Check(ControlLine::CarrierDetect, false)), Ok(true));
+        assert_eq!(run_step(ControlStep::Pulse(ControlLine::RequestToSend, false, Duration::from_millis(10))), Ok(true));
+        assert_eq!(run_step(ControlStep::Check(ControlLine::ClearToSend, true)), Ok(true));
+        assert_eq!(run_step(ControlStep::Break(Duration::from_millis(10))), Ok(true));
+
+        assert_eq!(serial.read().unwrap(), [0]);
+    }
+}
@@ -26,7 +26,9 @@
  * SOFTWARE.
  */
 
+use std::fmt;
 use crate::serial::Serial;
+use crate::tests::{TestContext, Condition};
 
 pub use crate::tests::test_case::{TestCase, TestCaseSettings};
 
@@ -36,39 +38,60 @@ pub struct TestSuiteSettings {
     /// If set the test suite will stop on first test failing.
     pub stop_on_failure: bool,
     /// If set the test suites will not be run.
-    pub disabled: bool
+    pub disabled: bool,
+    /// Condition to run the test suite, all tests and steps are skipped if the condition is not met.
+    pub condition: Option<Condition>
+}
+
+/// Role of a test suite in a script.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TestSuiteKind {
+    /// Group of tests.
+    #[default]
+    Tests,
+    /// Script wide setup steps run before all other test suites.
+    Setup,
+    /// Script wide teardown steps run after all other test suites, even if they were stopped.
+    Teardown
 }
 
 /// Test suite representing a group of tests.
+///
+/// Setup steps are run before the tests of the suite. If a setup step fails the tests are not run.
+/// Teardown steps are always run after the tests, even if a test failed or an error occurred.
 #[derive(Debug)]
 pub struct TestSuite {
     /// Name of the test group.
     pub name: String,
+    /// Role of the test suite.
+    pub kind: TestSuiteKind,
     /// Settings to use for the test suite.
     pub settings: TestSuiteSettings,
     /// Test settings to use for all tests.
     pub test_settings: TestCaseSettings,
-    tests: Vec<TestCase>
+    setup: Vec<TestCase>,
+    tests: Vec<TestCase>,
+    teardown: Vec<TestCase>,
+    skipped: bool
 }
 
 impl TestSuite {
     /// Create a new test suite.
     pub fn new(name: String) -> TestSuite {
-        TestSuite {
-            name,
-            settings: Default::default(),
-            test_settings: Default::default(),
-            tests: Vec::new()
-        }
+        TestSuite::new_with_settings(name, Default::default(), Default::default())
     }
 
     /// Create a new test suite with given suite and test settings.
     pub fn new_with_settings(name: String, settings: TestSuiteSettings, test_settings: TestCaseSettings) -> TestSuite {
         TestSuite {
             name,
+            kind: TestSuiteKind::Tests,
             settings,
             test_settings,
-            tests: Vec::new()
+            setup: Vec::new(),
+            tests: Vec::new(),
+            teardown: Vec::new(),
+            skipped: false
         }
     }
 
@@ -76,62 +99,163 @@ impl TestSuite {
     ///
     /// Tests added to the suite will be run when the suite is executed.
     pub fn push(&mut self, test: TestCase) {
-        self.tests.push(test);
+        TestSuite::push_step(&mut self.tests, test, &self.test_settings);
+    }
 
-        if let Some(pushed_test) = self.tests.last_mut() {
-            pushed_test.settings.merge_weak(&self.test_settings);
-        }
+    /// Add a setup step to the suite.
+    ///
+    /// Setup steps are run in order before the tests and stop on the first step failing.
+    pub fn push_setup(&mut self, step: TestCase) {
+        TestSuite::push_step(&mut self.setup, step, &self.test_settings);
+    }
+
+    /// Add a teardown step to the suite.
+    ///
+    /// Teardown steps are always run after the tests.
+    pub fn push_teardown(&mut self, step: TestCase) {
+        TestSuite::push_step(&mut self.teardown, step, &self.test_settings);
     }
 
     /// Run all tests belonging to the test suite on given serial port.
     ///
-    /// Execution will stop early if stop_on_failure is set and a test fails.
+    /// Execution will stop early if stop_on_failure is set and a test fails or if a setup step fails.
     pub fn run(&mut self, serial: &mut Serial) -> Result<bool, String> {
-        if self.settings.disabled {
+        self.run_with_context(serial, &mut TestContext::new())
+    }
+
+    /// Run all tests belonging to the test suite on given serial port with variables of the given context.
+    ///
+    /// Execution will stop early if stop_on_failure is set and a test fails or if a setup step fails.
+    pub fn run_with_context(&mut self, serial: &mut Serial, context: &mut TestContext) -> Result<bool, String> {
+        if self.skip_if_not_met(context)? {
             return Ok(true);
         }
 
-        for test in self.tests.iter_mut() {
-            let result = test.run(serial)?;
+        let previous_settings = self.test_settings.port.apply(serial)?;
 
-            if self.settings.stop_on_failure && !result {
-                return Ok(false);
+        let mut result = TestSuite::run_setup(&mut self.setup, serial, context, |_| ());
+
+        if let Ok(true) = result {
+            for test in self.tests.iter_mut() {
+                result = test.run_with_context(serial, context);
+
+                match result {
+                    Ok(false) if self.settings.stop_on_failure => break,
+                    Err(_) => break,
+                    _ => result = Ok(true)
+                };
             }
         }
 
-        Ok(true)
+        // teardown is run in any case
+        TestSuite::run_teardown(&mut self.teardown, serial, context, |_| ());
+
+        self.test_settings.port.restore(serial, previous_settings)?;
+
+        result
     }
 
     /// Run all tests belonging to the test suite on given serial port and print the results.
     ///
-    /// Execution will stop early if stop_on_failure is set and a test fails.
+    /// Execution will stop early if stop_on_failure is set and a test fails or if a setup step fails.
     pub fn run_and_print(&mut self, serial: &mut Serial, quiet: bool) -> bool {
+        self.run_and_print_with_context(serial, &mut TestContext::new(), quiet)
+    }
+
+    /// Run all tests belonging to the test suite on given serial port with variables of the given context and print the results.
+    ///
+    /// Execution will stop early if stop_on_failure is set and a test fails or if a setup step fails.
+    pub fn run_and_print_with_context(&mut self, serial: &mut Serial, context: &mut TestContext, quiet: bool) -> bool {
         let show_title = !self.name.is_empty();
+        let indent = if show_title { "\t" } else { "" };
+
+        let skip = self.skip_if_not_met(context);
 
         if show_title && !quiet {
             println!("{}", self.title());
         }
 
-        if self.settings.disabled {
-            return true;
-        }
+        match skip {
+            Ok(false) => (),
+            Ok(true) => return true,
+            Err(err) => {
+                println!("{}Error: {}", indent, err);
 
-        for test in self.tests.iter_mut() {
-            if show_title && !quiet {
-                print!("\t");
+                return false;
             }
+        };
 
-            let result = test.run(serial).unwrap_or(false);
-            if !quiet || !result {
-                println!("{}", test.to_string());
-            }
+        let previous_settings = match self.test_settings.port.apply(serial) {
+            Ok(settings) => settings,
+            Err(err) => {
+                println!("{}", err);
 
-            if !result && self.settings.stop_on_failure {
                 return false;
             }
+        };
+        let kind = self.kind;
+
+        let print_step = |prefix: &str, step: &TestCase| {
+            let successful = step.is_successful().unwrap_or(false) || step.settings.allow_failure.unwrap_or(false);
+
+            if !quiet || !successful {
+                // steps of script wide setup and teardown are shown like tests
+                if kind == TestSuiteKind::Tests {
+                    println!("{}{}: {}", indent, prefix, step);
+                } else {
+                    println!("{}{}", indent, step);
+                }
+            }
+        };
+
+        let mut result = TestSuite::run_setup(&mut self.setup, serial, context, |step| print_step("Setup", step)).unwrap_or(false);
+
+        if result {
+            for test in self.tests.iter_mut() {
+                if !quiet {
+                    print!("{}", indent);
+                }
+
+                let test_result = test.run_with_context(serial, context).unwrap_or(false);
+                if !quiet || !test_result {
+                    println!("{}", test);
+                }
+
+                if !test_result && self.settings.stop_on_failure {
+                    result = false;
+
+                    break;
+                }
+            }
+        } else if !quiet && !self.tests.is_empty() {
+            println!("{}Skipping tests because setup failed", indent);
+        }
+
+        // teardown is run in any case
+        TestSuite::run_teardown(&mut self.teardown, serial, context, |step| print_step("Teardown", step));
+
+        if let Err(err) = self.test_settings.port.restore(serial, previous_settings) {
+            println!("{}", err);
+
+            return false;
         }
 
-        true
+        result
+    }
+
+    /// Get all tests belonging to the test suite.
+    pub fn tests(&self) -> &[TestCase] {
+        &self.tests
+    }
+
+    /// Get all setup steps belonging to the test suite.
+    pub fn setup(&self) -> &[TestCase] {
+        &self.setup
+    }
+
+    /// Get all teardown steps belonging to the test suite.
+    pub fn teardown(&self) -> &[TestCase] {
+        &self.teardown
     }
 
     /// Get the number of tests belonging to the test suite.
@@ -148,20 +272,116 @@ impl TestSuite {
     ///
     /// Will return 0 if not run before.
     pub fn failed(&self) -> usize {
-        self.count_tests(false)
+        TestSuite::count_tests(&self.tests, false)
     }
 
     /// Get the number of successful tests.
     ///
     /// Will return 0 if not run before.
     pub fn successful(&self) -> usize {
-        self.count_tests(true)
+        TestSuite::count_tests(&self.tests, true)
+    }
+
+    /// Get the number of skipped tests.
+    ///
+    /// Will return 0 if not run before.
+    pub fn skipped(&self) -> usize {
+        self.tests.iter().filter(|test| test.is_skipped()).count()
+    }
+
+    /// Check if the test suite was skipped because it is disabled or its condition was not met.
+    pub fn is_skipped(&self) -> bool {
+        self.skipped
+    }
+
+    /// Get the number of failed setup and teardown steps.
+    ///
+    /// Will return 0 if not run before.
+    pub fn failed_steps(&self) -> usize {
+        TestSuite::count_tests(&self.setup, false) + TestSuite::count_tests(&self.teardown, false)
+    }
+
+    /// Get the number of failed tests, setup and teardown steps which received no response in the timeout duration.
+    ///
+    /// Will return 0 if not run before.
+    pub fn timed_out(&self) -> usize {
+        self.setup.iter().chain(self.tests.iter()).chain(self.teardown.iter())
+            .filter(|test| test.is_timed_out() && !test.settings.allow_failure.unwrap_or(false))
+            .count()
+    }
+
+    /// Check if a setup step failed.
+    ///
+    /// Will return false if not run before.
+    pub fn is_setup_failed(&self) -> bool {
+        TestSuite::count_tests(&self.setup, false) > 0
+    }
+
+    // disabled test suites and test suites not meeting their condition skip all tests and steps
+    fn skip_if_not_met(&mut self, context: &TestContext) -> Result<bool, String> {
+        let met = match self.settings.condition {
+            Some(ref condition) => condition.is_met(context),
+            None => Ok(true)
+        };
+
+        let met = match met {
+            Ok(met) => met,
+            Err(err) => {
+                // the error is reported for all tests as none of them is run
+                for test in self.tests.iter_mut() {
+                    let _ = test.exit_run_with_error(err.clone());
+                }
+
+                return Err(err);
+            }
+        };
+
+        self.skipped = self.settings.disabled || !met;
+
+        if self.skipped {
+            for step in self.setup.iter_mut().chain(self.tests.iter_mut()).chain(self.teardown.iter_mut()) {
+                step.skip();
+            }
+        }
+
+        Ok(self.skipped)
+    }
+
+    fn push_step(steps: &mut Vec<TestCase>, step: TestCase, test_settings: &TestCaseSettings) {
+        steps.push(step);
+
+        if let Some(pushed_step) = steps.last_mut() {
+            pushed_step.settings.merge_weak(test_settings);
+        }
+    }
+
+    fn run_setup<F: FnMut(&TestCase)>(steps: &mut [TestCase], serial: &mut Serial, context: &mut TestContext, mut on_step: F) -> Result<bool, String> {
+        for step in steps.iter_mut() {
+            let result = step.run_with_context(serial, context);
+            on_step(step);
+
+            match result {
+                Ok(true) => (),
+                Ok(false) if step.settings.allow_failure.unwrap_or(false) => (),
+                _ => return result.map(|_| false)
+            };
+        }
+
+        Ok(true)
     }
 
-    fn count_tests(&self, success: bool) -> usize {
+    fn run_teardown<F: FnMut(&TestCase)>(steps: &mut [TestCase], serial: &mut Serial, context: &mut TestContext, mut on_step: F) {
+        // failing teardown steps are counted but never stop the teardown
+        for step in steps.iter_mut() {
+            let _ = step.run_with_context(serial, context);
+            on_step(step);
+        }
+    }
+
+    fn count_tests(tests: &[TestCase], success: bool) -> usize {
         let mut count = 0;
 
-        for test in &self.tests {
+        for test in tests {
             if test.is_successful().is_none() && test.error().is_none() {
                 continue;
             }
@@ -177,30 +397,31 @@ impl TestSuite {
     fn title(&self) -> String {
         if self.settings.disabled {
             format!("{}: Disabled", self.name)
+        } else if self.skipped {
+            format!("{}: Skipped", self.name)
         } else {
             format!("{}:", self.name)
         }
     }
 }
 
-impl ToString for TestSuite {
-    fn to_string(&self) -> String {
-        let mut result = String::new();
-
+impl fmt::Display for TestSuite {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let show_group = !self.name.is_empty();
+        let indent = if show_group { "\t" } else { "" };
 
         if show_group {
-            result.push_str(format!("{}\n", self.title()).as_str());
+            writeln!(f, "{}", self.title())?;
         }
 
-        for test in &self.tests {
-            if show_group {
-                result.push('\t');
-            }
+        for (prefix, steps) in [("Setup: ", &self.setup), ("", &self.tests), ("Teardown: ", &self.teardown)].iter() {
+            let prefix = if self.kind == TestSuiteKind::Tests { prefix } else { "" };
 
-            result.push_str(format!("{}\n", test.to_string()).as_str());
+            for step in steps.iter() {
+                writeln!(f, "{}{}{}", indent, prefix, step)?;
+            }
         }
 
-        result
+        Ok(())
     }
 }
@@ -0,0 +1,92 @@
+/*
+ * File: src/tests/timing.rs
+ * Date: 17.10.2026
+ * Author: MarkAtk
+ *
+ * MIT License
+ *
+ * Copyright (c) 2026 MarkAtk
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies
+ * of the Software, and to permit persons to whom the Software is furnished to do
+ * so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+
+use std::fmt;
+use std::time::Duration;
+
+/// Timing of a single response.
+///
+/// Times are measured when data is returned by the port, thus bytes received in one read have no gap between them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResponseTiming {
+    /// Time from writing the input (or starting to wait for expect only tests) until the first byte was received.
+    pub latency: Duration,
+    /// Longest time between two consecutively received parts of the response.
+    pub max_inter_byte_gap: Duration
+}
+
+/// Minimum, average and maximum of measured durations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimingStatistics {
+    /// Shortest duration.
+    pub min: Duration,
+    /// Average duration.
+    pub average: Duration,
+    /// Longest duration.
+    pub max: Duration
+}
+
+impl TimingStatistics {
+    /// Get the statistics of the given durations.
+    ///
+    /// None is returned if no durations are given.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use serial_unit_testing::tests::TimingStatistics;
+    ///
+    /// let statistics = TimingStatistics::of(&[Duration::from_millis(2), Duration::from_millis(4)]).unwrap();
+    ///
+    /// assert_eq!(statistics.average, Duration::from_millis(3));
+    /// ```
+    pub fn of(durations: &[Duration]) -> Option<TimingStatistics> {
+        let min = *durations.iter().min()?;
+        let max = *durations.iter().max()?;
+        let average = durations.iter().sum::<Duration>() / durations.len() as u32;
+
+        Some(TimingStatistics {
+            min,
+            average,
+            max
+        })
+    }
+}
+
+impl fmt::Display for TimingStatistics {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "min {}, avg {}, max {}", milliseconds(self.min), milliseconds(self.average), milliseconds(self.max))
+    }
+}
+
+/// Format a duration in milliseconds with microsecond precision.
+pub(crate) fn milliseconds(duration: Duration) -> String {
+    format!("{:.3}ms", duration.as_secs_f64() * 1000.0)
+}
@@ -0,0 +1,235 @@
+/*
+ * File: src/utils/checksum.rs
+ * Date: 17.10.2026
+ * Author: MarkAtk
+ *
+ * MIT License
+ *
+ * Copyright (c) 2026 MarkAtk
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies
+ * of the Software, and to permit persons to whom the Software is furnished to do
+ * so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+
+use crate::error::{Error, Result};
+
+/// Parameters of a cyclic redundancy check in the Rocksoft model.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Crc {
+    /// Width of the checksum in bits (8 to 32).
+    pub width: u32,
+    /// Generator polynomial without the highest bit.
+    pub polynomial: u32,
+    /// Initial register value.
+    pub init: u32,
+    /// Reflect each input byte.
+    pub reflect_in: bool,
+    /// Reflect the register before the final XOR.
+    pub reflect_out: bool,
+    /// Value XORed with the final register.
+    pub xor_out: u32
+}
+
+/// Checksum algorithm which can be computed over preceding bytes of content.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Checksum {
+    /// Cyclic redundancy check with the byte order of the checksum, true for least significant byte first.
+    Crc(Crc, bool),
+    /// XOR of all bytes.
+    Xor,
+    /// Sum of all bytes modulo 256.
+    Sum8,
+    /// Two's complement of the sum of all bytes, e.g. for Modbus ASCII.
+    Lrc
+}
+
+/// Catalogue of checksums available by name.
+///
+/// Reflected CRC algorithms are sent with the least significant byte first, all other with the most significant byte first.
+pub const CHECKSUMS: &[(&str, Checksum)] = &[
+    ("crc8", Checksum::Crc(Crc { width: 8, polynomial: 0x07, init: 0x00, reflect_in: false, reflect_out: false, xor_out: 0x00 }, false)),
+    ("crc8-maxim", Checksum::Crc(Crc { width: 8, polynomial: 0x31, init: 0x00, reflect_in: true, reflect_out: true, xor_out: 0x00 }, false)),
+    ("crc16-modbus", Checksum::Crc(Crc { width: 16, polynomial: 0x8005, init: 0xFFFF, reflect_in: true, reflect_out: true, xor_out: 0x0000 }, true)),
+    ("crc16-arc", Checksum::Crc(Crc { width: 16, polynomial: 0x8005, init: 0x0000, reflect_in: true, reflect_out: true, xor_out: 0x0000 }, true)),
+    ("crc16-kermit", Checksum::Crc(Crc { width: 16, polynomial: 0x1021, init: 0x0000, reflect_in: true, reflect_out: true, xor_out: 0x0000 }, true)),
+    ("crc16-xmodem", Checksum::Crc(Crc { width: 16, polynomial: 0x1021, init: 0x0000, reflect_in: false, reflect_out: false, xor_out: 0x0000 }, false)),
+    ("crc16-ccitt-false", Checksum::Crc(Crc { width: 16, polynomial: 0x1021, init: 0xFFFF, reflect_in: false, reflect_out: false, xor_out: 0x0000 }, false)),
+    ("crc32", Checksum::Crc(Crc { width: 32, polynomial: 0x04C1_1DB7, init: 0xFFFF_FFFF, reflect_in: true, reflect_out: true, xor_out: 0xFFFF_FFFF }, true)),
+    ("crc32-mpeg2", Checksum::Crc(Crc { width: 32, polynomial: 0x04C1_1DB7, init: 0xFFFF_FFFF, reflect_in: false, reflect_out: false, xor_out: 0x0000_0000 }, false)),
+    ("xor", Checksum::Xor),
+    ("sum8", Checksum::Sum8),
+    ("lrc", Checksum::Lrc)
+];
+
+/// Part of content containing checksum placeholders.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ContentPart<'a> {
+    /// Content without placeholders.
+    Content(&'a str),
+    /// Checksum placeholder like `{crc16-modbus}`.
+    Checksum(Checksum)
+}
+
+impl Checksum {
+    /// Get a checksum of the catalogue by name.
+    ///
+    /// The byte order of CRC checksums can be changed with the suffix `-le` or `-be`, e.g. `crc16-xmodem-le`.
+    pub fn from_name(name: &str) -> Option<Checksum> {
+        if let Some(checksum) = CHECKSUMS.iter().find(|(checksum_name, _)| *checksum_name == name).map(|(_, checksum)| *checksum) {
+            return Some(checksum);
+        }
+
+        let (base_name, little_endian) = if let Some(base_name) = name.strip_suffix("-le") {
+            (base_name, true)
+        } else if let Some(base_name) = name.strip_suffix("-be") {
+            (base_name, false)
+        } else {
+            return None;
+        };
+
+        match Checksum::from_name(base_name) {
+            Some(Checksum::Crc(crc, _)) => Some(Checksum::Crc(crc, little_endian)),
+            _ => None
+        }
+    }
+
+    /// Get the number of bytes of the checksum.
+    pub fn size(&self) -> usize {
+        match self {
+            Checksum::Crc(crc, _) => crc.width.div_ceil(8) as usize,
+            _ => 1
+        }
+    }
+
+    /// Compute the checksum of given data.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use serial_unit_testing::utils::Checksum;
+    ///
+    /// let checksum = Checksum::from_name("crc16-modbus").unwrap();
+    ///
+    /// assert_eq!(checksum.compute(b"123456789"), vec![0x37, 0x4B]);
+    /// ```
+    pub fn compute(&self, data: &[u8]) -> Vec<u8> {
+        match self {
+            Checksum::Crc(crc, little_endian) => {
+                let value = compute_crc(crc, data);
+                let mut bytes: Vec<u8> = (0..self.size()).rev().map(|index| (value >> (index * 8)) as u8).collect();
+
+                if *little_endian {
+                    bytes.reverse();
+                }
+
+                bytes
+            },
+            Checksum::Xor => vec![data.iter().fold(0, |checksum, byte| checksum ^ byte)],
+            Checksum::Sum8 => vec![data.iter().fold(0u8, |checksum, byte| checksum.wrapping_add(*byte))],
+            Checksum::Lrc => vec![data.iter().fold(0u8, |checksum, byte| checksum.wrapping_add(*byte)).wrapping_neg()]
+        }
+    }
+}
+
+/// Split content into content and checksum placeholders.
+///
+/// Placeholders are checksum names in curly braces starting with a letter, e.g. `{crc8}`. Other curly braces like regex
+/// repetitions `{2}` or variables `${name}` are kept as content. Fails if a placeholder names an unknown checksum.
+pub fn split_checksums(text: &str) -> Result<Vec<ContentPart<'_>>> {
+    let mut parts = Vec::new();
+    let mut rest = text;
+
+    while let Some((start, name, end)) = find_placeholder(rest) {
+        let checksum = match Checksum::from_name(name) {
+            Some(checksum) => checksum,
+            None => return Err(Error::UnknownChecksum(name.to_string()))
+        };
+
+        if start > 0 {
+            parts.push(ContentPart::Content(&rest[..start]));
+        }
+
+        parts.push(ContentPart::Checksum(checksum));
+
+        rest = &rest[end..];
+    }
+
+    if !rest.is_empty() {
+        parts.push(ContentPart::Content(rest));
+    }
+
+    Ok(parts)
+}
+
+// find the next placeholder and return its start, name and end
+fn find_placeholder(text: &str) -> Option<(usize, &str, usize)> {
+    let mut offset = 0;
+
+    while let Some(start) = text[offset..].find('{') {
+        let start = offset + start;
+        let name_start = start + 1;
+
+        let name_length = text[name_start..].find(|ch: char| !(ch.is_ascii_alphanumeric() || ch == '-')).unwrap_or(text.len() - name_start);
+        let name_end = name_start + name_length;
+
+        let starts_with_letter = text[name_start..].starts_with(|ch: char| ch.is_ascii_alphabetic());
+        // script variables look like placeholders after the dollar sign
+        let is_variable = text[..start].ends_with('$');
+
+        if starts_with_letter && !is_variable && text[name_end..].starts_with('}') {
+            return Some((start, &text[name_start..name_end], name_end + 1));
+        }
+
+        offset = name_start;
+    }
+
+    None
+}
+
+fn compute_crc(crc: &Crc, data: &[u8]) -> u32 {
+    let width = crc.width;
+    let mask = if width == 32 { u32::MAX as u64 } else { (1u64 << width) - 1 };
+    let top_bit = 1u64 << (width - 1);
+
+    let mut register = crc.init as u64 & mask;
+
+    for byte in data {
+        let byte = if crc.reflect_in { byte.reverse_bits() } else { *byte };
+
+        register ^= (byte as u64) << (width - 8);
+
+        for _ in 0..8 {
+            register = if register & top_bit != 0 {
+                (register << 1) ^ crc.polynomial as u64
+            } else {
+                register << 1
+            };
+
+            register &= mask;
+        }
+    }
+
+    let mut value = register as u32;
+
+    if crc.reflect_out {
+        value = value.reverse_bits() >> (32 - width);
+    }
+
+    (value ^ crc.xor_out) & mask as u32
+}
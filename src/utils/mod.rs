@@ -1,5 +1,5 @@
 /*
- * File: src/utils.rs
+ * File: src/utils/mod.rs
  * Date: 30.09.2018
  * Author: MarkAtk
  *
@@ -29,6 +29,12 @@
 use std::str;
 use super::error::{Error, Result};
 
+mod checksum;
+mod pattern;
+
+pub use self::checksum::{Checksum, Crc, ContentPart, CHECKSUMS, split_checksums};
+pub use self::pattern::{BytePattern, ByteMatcher};
+
 /// Text format type for radix string conversion.
 #[derive(PartialEq, Debug, Copy, Clone)]
 pub enum TextFormat {
@@ -140,6 +146,50 @@ pub fn bytes_from_decimal_string(original_text: &str) -> Result<Vec<u8>> {
     bytes_from_radix_string(&text, 10)
 }
 
+/// Convert content in given text format into a vector of bytes.
+///
+/// Checksum placeholders like `{crc16-modbus}` in binary, octal, decimal or hexadecimal content are replaced with the
+/// checksum of all preceding bytes. Text content is converted as is.
+///
+/// # Example
+///
+/// ```
+/// use serial_unit_testing::utils::{self, TextFormat};
+///
+/// let bytes = utils::bytes_from_format("01 03 00 00 00 0A {crc16-modbus}", TextFormat::Hex).unwrap();
+///
+/// assert_eq!(bytes, vec![0x01, 0x03, 0x00, 0x00, 0x00, 0x0A, 0xC5, 0xCD]);
+/// ```
+pub fn bytes_from_format(text: &str, text_format: TextFormat) -> Result<Vec<u8>> {
+    if text_format == TextFormat::Text {
+        return Ok(text.as_bytes().to_vec());
+    }
+
+    let mut bytes = Vec::new();
+
+    for part in split_checksums(text)? {
+        match part {
+            ContentPart::Content(content) => {
+                let content_bytes = match text_format {
+                    TextFormat::Binary => bytes_from_binary_string(content)?,
+                    TextFormat::Octal => bytes_from_octal_string(content)?,
+                    TextFormat::Decimal => bytes_from_decimal_string(content)?,
+                    _ => bytes_from_hex_string(content)?
+                };
+
+                bytes.extend_from_slice(&content_bytes);
+            },
+            ContentPart::Checksum(checksum) => {
+                let checksum_bytes = checksum.compute(&bytes);
+
+                bytes.extend_from_slice(&checksum_bytes);
+            }
+        };
+    }
+
+    Ok(bytes)
+}
+
 /// Convert a radix string into a vector of bytes.
 ///
 /// Leading and trailing whitespaces will result in an error. Conversion happens by 2 characters per byte.
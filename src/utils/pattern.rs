@@ -0,0 +1,329 @@
+/*
+ * File: src/utils/pattern.rs
+ * Date: 17.10.2026
+ * Author: MarkAtk
+ *
+ * MIT License
+ *
+ * Copyright (c) 2026 MarkAtk
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies
+ * of the Software, and to permit persons to whom the Software is furnished to do
+ * so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+
+use super::{Checksum, TextFormat};
+
+/// Matcher for a single byte of a byte pattern.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ByteMatcher {
+    /// Byte matching the value in all bits set in the mask.
+    Masked {
+        /// Expected bit values.
+        value: u8,
+        /// Bits to compare.
+        mask: u8
+    },
+    /// Byte in the inclusive range.
+    Range {
+        /// Smallest value.
+        min: u8,
+        /// Largest value.
+        max: u8
+    },
+    /// Checksum of all preceding bytes, taking as many bytes as the checksum is long.
+    Checksum(Checksum)
+}
+
+/// Pattern matching raw bytes.
+///
+/// Byte patterns are written in hexadecimal or binary content. Hexadecimal digits can be replaced with `?` to match any
+/// value, e.g. `01 ?? 0A` or `A?`. Binary digits can be replaced with `x` or `?`, e.g. `1xxx0000`. Ranges of byte values
+/// are written in brackets, e.g. `[0x10-0x1F]`, and checksum placeholders like `{crc8}` match the checksum of all
+/// preceding bytes.
+///
+/// # Example
+///
+/// ```
+/// use serial_unit_testing::utils::{BytePattern, TextFormat};
+///
+/// let pattern = BytePattern::parse("01 ?? [0x10-0x1F]", TextFormat::Hex).unwrap();
+///
+/// assert!(pattern.matches(&[0x01, 0xAB, 0x12]));
+/// assert!(!pattern.matches(&[0x01, 0xAB, 0x20]));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BytePattern {
+    matchers: Vec<ByteMatcher>
+}
+
+impl BytePattern {
+    /// Parse a byte pattern from hexadecimal or binary content.
+    ///
+    /// None is returned if the content is no valid byte pattern, e.g. because it is a regular expression.
+    pub fn parse(text: &str, format: TextFormat) -> Option<BytePattern> {
+        let (digits_per_byte, radix, wildcards) = match format {
+            TextFormat::Hex => (2, 16, "?"),
+            TextFormat::Binary => (8, 2, "?xX"),
+            _ => return None
+        };
+
+        let bits_per_digit = 8 / digits_per_byte;
+        let digit_mask = (1u8 << bits_per_digit).wrapping_sub(1);
+
+        let mut matchers = Vec::new();
+        let mut value = 0u8;
+        let mut mask = 0u8;
+        let mut digits = 0;
+        let mut rest = text;
+
+        while let Some(ch) = rest.chars().next() {
+            // ranges and checksums need complete bytes
+            if (ch == '[' || ch == '{') && digits != 0 {
+                return None;
+            }
+
+            if ch == '[' {
+                let end = rest.find(']')?;
+                matchers.push(parse_range(&rest[1..end])?);
+
+                rest = &rest[end + 1..];
+
+                continue;
+            }
+
+            if ch == '{' {
+                let end = rest.find('}')?;
+                matchers.push(ByteMatcher::Checksum(Checksum::from_name(&rest[1..end])?));
+
+                rest = &rest[end + 1..];
+
+                continue;
+            }
+
+            rest = &rest[ch.len_utf8()..];
+
+            if ch == ' ' {
+                continue;
+            }
+
+            value <<= bits_per_digit;
+            mask <<= bits_per_digit;
+
+            if !wildcards.contains(ch) {
+                value |= ch.to_digit(radix)? as u8;
+                mask |= digit_mask;
+            }
+
+            digits += 1;
+
+            if digits == digits_per_byte {
+                matchers.push(ByteMatcher::Masked { value, mask });
+
+                value = 0;
+                mask = 0;
+                digits = 0;
+            }
+        }
+
+        if digits != 0 || matchers.is_empty() {
+            return None;
+        }
+
+        Some(BytePattern {
+            matchers
+        })
+    }
+
+    /// Get the number of bytes matched by the pattern.
+    pub fn len(&self) -> usize {
+        self.matchers.iter().map(|matcher| match matcher {
+            ByteMatcher::Checksum(checksum) => checksum.size(),
+            _ => 1
+        }).sum()
+    }
+
+    /// Check if the pattern matches no bytes.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Get the matchers of the pattern.
+    pub fn matchers(&self) -> &[ByteMatcher] {
+        &self.matchers
+    }
+
+    /// Check if the given data matches the pattern completely.
+    pub fn matches(&self, data: &[u8]) -> bool {
+        if data.len() != self.len() {
+            return false;
+        }
+
+        let mut position = 0;
+
+        for matcher in &self.matchers {
+            let matched = match *matcher {
+                ByteMatcher::Masked { value, mask } => data[position] & mask == value & mask,
+                ByteMatcher::Range { min, max } => (min..=max).contains(&data[position]),
+                ByteMatcher::Checksum(checksum) => {
+                    let size = checksum.size();
+                    let matched = checksum.compute(&data[..position]) == data[position..position + size];
+
+                    // the position is advanced by one below
+                    position += size - 1;
+
+                    matched
+                }
+            };
+
+            if !matched {
+                return false;
+            }
+
+            position += 1;
+        }
+
+        true
+    }
+}
+
+// parse range like 0x10-0x1F, 0b0001-0b0100 or 16-31
+fn parse_range(text: &str) -> Option<ByteMatcher> {
+    let mut values = text.splitn(2, '-');
+
+    let min = parse_value(values.next()?)?;
+    let max = parse_value(values.next()?)?;
+
+    if min > max {
+        return None;
+    }
+
+    Some(ByteMatcher::Range { min, max })
+}
+
+fn parse_value(text: &str) -> Option<u8> {
+    let text = text.trim();
+
+    if let Some(hex) = text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        u8::from_str_radix(hex, 16).ok()
+    } else if let Some(binary) = text.strip_prefix("0b").or_else(|| text.strip_prefix("0B")) {
+        u8::from_str_radix(binary, 2).ok()
+    } else {
+        text.parse::<u8>().ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(text: &str) -> BytePattern {
+        BytePattern::parse(text, TextFormat::Hex).unwrap()
+    }
+
+    fn binary(text: &str) -> BytePattern {
+        BytePattern::parse(text, TextFormat::Binary).unwrap()
+    }
+
+    #[test]
+    fn hex_wildcards() {
+        assert!(hex("01 ?? 0A").matches(&[0x01, 0xFF, 0x0A]));
+        assert!(!hex("01 ?? 0A").matches(&[0x01, 0xFF, 0x0B]));
+        assert!(hex("A?").matches(&[0xA5]));
+        assert!(hex("?5").matches(&[0xF5]));
+        assert!(!hex("A?").matches(&[0xB5]));
+        assert_eq!(hex("A?").matchers(), &[ByteMatcher::Masked { value: 0xA0, mask: 0xF0 }]);
+    }
+
+    #[test]
+    fn binary_masks() {
+        assert!(binary("1xxx0000").matches(&[0x80]));
+        assert!(binary("1xxx0000").matches(&[0xF0]));
+        assert!(binary("1???0000").matches(&[0xA0]));
+        assert!(!binary("1xxx0000").matches(&[0x81]));
+        assert!(!binary("1XXX0000").matches(&[0x70]));
+    }
+
+    #[test]
+    fn ranges() {
+        let pattern = hex("01 [0x10-0x1F]");
+
+        assert!(pattern.matches(&[0x01, 0x10]));
+        assert!(pattern.matches(&[0x01, 0x1F]));
+        assert!(!pattern.matches(&[0x01, 0x20]));
+        assert!(!pattern.matches(&[0x01, 0x0F]));
+
+        assert_eq!(hex("[16-31]"), hex("[0x10-0x1F]"));
+        assert_eq!(binary("[0b0001-0b0100]").matchers(), &[ByteMatcher::Range { min: 1, max: 4 }]);
+
+        assert_eq!(BytePattern::parse("[0x1F-0x10]", TextFormat::Hex), None);
+        assert_eq!(BytePattern::parse("[0x10-0x100]", TextFormat::Hex), None);
+        assert_eq!(BytePattern::parse("[0x10-0x1F", TextFormat::Hex), None);
+    }
+
+    #[test]
+    fn partial_byte_before_range_or_checksum() {
+        assert_eq!(BytePattern::parse("0[0x10-0x1F]", TextFormat::Hex), None);
+        assert_eq!(BytePattern::parse("010{crc8}", TextFormat::Hex), None);
+        assert_eq!(BytePattern::parse("1010[0-1]", TextFormat::Binary), None);
+        assert_eq!(BytePattern::parse("0", TextFormat::Hex), None);
+    }
+
+    #[test]
+    fn embedded_checksums() {
+        let checksum = Checksum::from_name("crc16-modbus").unwrap();
+        let mut data = vec![0x01, 0x03, 0x00, 0x00];
+        data.extend(checksum.compute(&data));
+
+        let pattern = hex("01 03 ?? ?? {crc16-modbus}");
+
+        assert_eq!(pattern.len(), 6);
+        assert!(pattern.matches(&data));
+
+        data[5] ^= 0xFF;
+
+        assert!(!pattern.matches(&data));
+
+        // checksums cover all preceding bytes, including earlier checksums
+        let sum = Checksum::from_name("sum8").unwrap();
+        let mut data = vec![0x01, 0x02];
+        data.extend(sum.compute(&data));
+        data.push(0x03);
+        data.extend(sum.compute(&data));
+
+        assert!(hex("01 02 {sum8} 03 {sum8}").matches(&data));
+    }
+
+    #[test]
+    fn regular_expressions_are_no_patterns() {
+        assert_eq!(BytePattern::parse("0103.{4}", TextFormat::Hex), None);
+        assert_eq!(BytePattern::parse("01(03|04)", TextFormat::Hex), None);
+        assert_eq!(BytePattern::parse("0103", TextFormat::Text), None);
+    }
+
+    #[test]
+    fn plain_content() {
+        let pattern = hex("0103");
+
+        assert_eq!(pattern.matchers(), &[ByteMatcher::Masked { value: 0x01, mask: 0xFF }, ByteMatcher::Masked { value: 0x03, mask: 0xFF }]);
+        assert!(pattern.matches(&[0x01, 0x03]));
+        assert!(!pattern.matches(&[0x01, 0x03, 0x00]));
+        assert!(!pattern.matches(&[0x01]));
+        assert!(hex("0a0B").matches(&[0x0A, 0x0B]));
+    }
+}
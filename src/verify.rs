@@ -31,20 +31,22 @@ use std::fs::File;
 
 use clap::{ArgMatches, SubCommand, Arg, App};
 
+use crate::status::{CommandError, ExitCode};
+
 use serial_unit_testing::parser;
 
-pub fn run(matches: &ArgMatches) -> Result<(), String> {
+pub fn run(matches: &ArgMatches) -> Result<(), CommandError> {
     let filename = matches.value_of("file").unwrap();
 
-    let mut file = match File::open(filename) {
-        Ok(file) => file,
-        Err(ref e) if e.kind() == io::ErrorKind::NotFound => return Err("File not found".to_string()), 
-        Err(e) => return Err(format!("{}", e))
+    match File::open(filename) {
+        Ok(_) => (),
+        Err(ref e) if e.kind() == io::ErrorKind::NotFound => return Err(CommandError::from("File not found".to_string())),
+        Err(e) => return Err(CommandError::from(format!("{}", e)))
     };
 
-    let test_suites = match parser::parse_file(&mut file) {
+    let test_suites = match parser::parse_path(filename) {
         Ok(test_suites) => test_suites,
-        Err(e) => return Err(format!("Unable to parse file: {}", e))
+        Err(e) => return Err(CommandError::new(ExitCode::ParseError, format!("Unable to parse file: {}", e)))
     };
 
     match matches.occurrences_of("verbose") {
@@ -56,7 +58,7 @@ pub fn run(matches: &ArgMatches) -> Result<(), String> {
         0 => println!("OK"),
         _ => {
             for test_suite in test_suites {
-                println!("{}", test_suite.to_string());
+                println!("{}", test_suite);
             }
         }
     };
@@ -28,7 +28,9 @@
 
 use clap::{ArgMatches, SubCommand, App};
 
-pub fn run(_: &ArgMatches) -> Result<(), String> {
+use crate::status::CommandError;
+
+pub fn run(_: &ArgMatches) -> Result<(), CommandError> {
     println!("serial-unit-testing {}", crate_version!());
 
     Ok(())
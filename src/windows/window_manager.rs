@@ -51,12 +51,12 @@ impl WindowManager {
         let (tx, rx) = mpsc::channel();
 
         if let Err(err) = enable_raw_mode() {
-            return Err(io::Error::new(io::ErrorKind::Other, err));
+            return Err(io::Error::other(err));
         }
 
         let mut stdout = stdout();
         if let Err(err) = execute!(stdout, EnterAlternateScreen) {
-            return Err(io::Error::new(io::ErrorKind::Other, err));
+            return Err(io::Error::other(err));
         }
 
         let backend = CrosstermBackend::new(stdout);
@@ -135,7 +135,7 @@ impl WindowManager {
                 let result = match self.rx.recv() {
                     Ok(Event::Input(event)) => {
                         match event {
-                            KeyEvent { code: KeyCode::Char(c), modifiers: KeyModifiers::CONTROL } if c == 'c' => return Ok(()),
+                            KeyEvent { code: KeyCode::Char('c'), modifiers: KeyModifiers::CONTROL } => return Ok(()),
                             _ => Some(window.handle_key_event(event))
                         }
                     },
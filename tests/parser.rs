@@ -0,0 +1,375 @@
+/*
+ * File: tests/parser.rs
+ * Date: 17.10.2026
+ * Author: MarkAtk
+ *
+ * MIT License
+ *
+ * Copyright (c) 2026 MarkAtk
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies
+ * of the Software, and to permit persons to whom the Software is furnished to do
+ * so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use serial_unit_testing::parser::{self, Error};
+use serial_unit_testing::serial::Serial;
+use serial_unit_testing::tests::{TestSuite, TestSuiteKind, TestCase, TestCaseKind, ControlStep, ControlLine};
+
+// create an empty directory for the scripts of a test
+fn script_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("sut-parser-{}-{}", std::process::id(), name));
+
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+
+    dir
+}
+
+fn write_script(dir: &Path, name: &str, content: &str) -> PathBuf {
+    let path = dir.join(name);
+
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(&path, content).unwrap();
+
+    path
+}
+
+fn parse(name: &str, content: &str) -> Result<Vec<TestSuite>, Error> {
+    let dir = script_dir(name);
+    let path = write_script(&dir, "script.sut", content);
+
+    let result = parser::parse_path(path);
+    fs::remove_dir_all(&dir).unwrap();
+
+    result
+}
+
+#[test]
+fn test_without_name_and_format_specifier() {
+    let mut test_suites = parse("plain-test", "\"ping\" : \"ping\"\n").unwrap();
+
+    assert_eq!(test_suites.len(), 1);
+    assert_eq!(test_suites[0].len(), 1);
+
+    let mut serial = Serial::open("loopback").unwrap();
+
+    assert_eq!(test_suites[0].run(&mut serial), Ok(true));
+}
+
+#[test]
+fn includes_are_resolved_relative_to_including_file() {
+    let dir = script_dir("relative-include");
+    let path = write_script(&dir, "main.sut", "\"a\" : \"a\"\ninclude \"steps/steps.sut\"\n");
+    write_script(&dir, "steps/steps.sut", "include \"common.sut\"\n");
+    write_script(&dir, "steps/common.sut", "[Common]\n\"b\" : \"b\"\n");
+
+    let test_suites = parser::parse_path(path).unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(test_suites.len(), 2);
+    assert_eq!(test_suites[0].tests()[0].input(), "a");
+    assert_eq!(test_suites[1].name, "Common");
+    assert_eq!(test_suites[1].tests()[0].input(), "b");
+}
+
+#[test]
+fn include_cycle_is_reported_at_first_cycle() {
+    let dir = script_dir("include-cycle");
+    let path = write_script(&dir, "a.sut", "include \"b.sut\"\n");
+    write_script(&dir, "b.sut", "\"b\" : \"b\"\ninclude \"a.sut\"\n");
+
+    let result = parser::parse_path(path);
+    fs::remove_dir_all(&dir).unwrap();
+
+    match result {
+        Err(Error::File(file, error)) => {
+            assert!(file.ends_with("b.sut"));
+
+            match *error {
+                Error::IncludeCycle(ref path, 2, _) => assert!(path.ends_with("a.sut")),
+                ref error => panic!("Unexpected error {}", error)
+            };
+        },
+        _ => panic!("Include cycle not detected")
+    };
+}
+
+#[test]
+fn errors_carry_file_and_position() {
+    let dir = script_dir("error-position");
+    let path = write_script(&dir, "main.sut", "\"a\" : \"a\"\ninclude \"broken.sut\"\n");
+    write_script(&dir, "broken.sut", "\"b\" : \"b\"\n\"c\" \"c\"\n");
+
+    let result = parser::parse_path(&path);
+    let root_result = parser::parse_path(write_script(&dir, "root.sut", "\"a\" : \"a\"\n(b\n"));
+    fs::remove_dir_all(&dir).unwrap();
+
+    match result {
+        Err(Error::File(file, error)) => {
+            assert!(file.ends_with("broken.sut"));
+            assert_eq!(*error, Error::MissingDirectionSeparator(2, 5));
+        },
+        _ => panic!("Error in included file not reported")
+    };
+
+    match root_result {
+        Err(Error::File(file, _)) => assert!(file.ends_with("root.sut")),
+        _ => panic!("Error in root file not reported with file name")
+    };
+}
+
+#[test]
+fn sections_belong_to_script_or_preceding_group() {
+    let script = "[setup]\n\"s1\" : \"s1\"\n[teardown]\n\"t1\" : \"t1\"\n\
+        [G]\n\"a\" : \"a\"\n[setup, timeout = 2s]\n\"s2\" : \"s2\"\n[teardown]\n\"t2\" : \"t2\"\n[H]\n\"b\" : \"b\"\n";
+
+    let test_suites = parse("sections", script).unwrap();
+
+    assert_eq!(test_suites.len(), 4);
+
+    assert_eq!(test_suites[0].kind, TestSuiteKind::Setup);
+    assert_eq!(test_suites[0].setup()[0].input(), "s1");
+
+    assert_eq!(test_suites[1].name, "G");
+    assert_eq!(test_suites[1].setup()[0].input(), "s2");
+    assert_eq!(test_suites[1].setup()[0].settings.timeout, Some(std::time::Duration::from_secs(2)));
+    assert_eq!(test_suites[1].tests()[0].input(), "a");
+    assert_eq!(test_suites[1].teardown()[0].input(), "t2");
+
+    assert_eq!(test_suites[2].name, "H");
+    assert!(test_suites[2].setup().is_empty());
+    assert!(test_suites[2].teardown().is_empty());
+
+    assert_eq!(test_suites[3].kind, TestSuiteKind::Teardown);
+    assert_eq!(test_suites[3].teardown()[0].input(), "t1");
+}
+
+#[test]
+fn group_options_are_rejected_for_sections() {
+    let scripts = [
+        ("disabled", "[setup, disabled = true]\n\"s\" : \"s\"\n"),
+        ("stop-on-failure", "[G]\n[setup, stop-on-failure = true]\n\"s\" : \"s\"\n"),
+        ("if", "[teardown, if = \"defined(x)\"]\n\"t\" : \"t\"\n"),
+        ("skip-if", "[G]\n[teardown, skip-if = \"defined(x)\"]\n\"t\" : \"t\"\n")
+    ];
+
+    for (option, script) in scripts.iter() {
+        match parse("section-options", script) {
+            Err(Error::File(_, error)) => assert!(matches!(*error, Error::InvalidSectionOption(ref name, _, _) if name == option), "{}", error),
+            _ => panic!("Option '{}' not rejected", option)
+        };
+    }
+}
+
+#[test]
+fn section_end_closes_sections_and_groups() {
+    let script = "[G]\n[teardown]\n\"t1\" : \"t1\"\n[end]\n\"a\" : \"a\"\n[end]\n\"b\" : \"b\"\n[teardown]\n\"t2\" : \"t2\"\n[END]\n\"c\" : \"c\"\n";
+
+    let test_suites = parse("section-end", script).unwrap();
+
+    assert_eq!(test_suites.len(), 3);
+
+    assert_eq!(test_suites[0].name, "G");
+    assert_eq!(test_suites[0].teardown().iter().map(TestCase::input).collect::<Vec<&str>>(), vec!["t1"]);
+    assert_eq!(test_suites[0].tests().iter().map(TestCase::input).collect::<Vec<&str>>(), vec!["a"]);
+
+    assert_eq!(test_suites[1].name, "");
+    assert_eq!(test_suites[1].tests().iter().map(TestCase::input).collect::<Vec<&str>>(), vec!["b", "c"]);
+
+    assert_eq!(test_suites[2].kind, TestSuiteKind::Teardown);
+    assert_eq!(test_suites[2].teardown().iter().map(TestCase::input).collect::<Vec<&str>>(), vec!["t2"]);
+}
+
+#[test]
+fn invalid_section_ends_are_rejected() {
+    match parse("unexpected-section-end", "\"a\" : \"a\"\n[end]\n") {
+        Err(Error::File(_, error)) => assert!(matches!(*error, Error::UnexpectedSectionEnd(2, _)), "{}", error),
+        _ => panic!("Section end without section not rejected")
+    };
+
+    match parse("unexpected-group-end", "[G]\n\"a\" : \"a\"\n[end]\n[end]\n") {
+        Err(Error::File(_, error)) => assert!(matches!(*error, Error::UnexpectedSectionEnd(4, _)), "{}", error),
+        _ => panic!("Section end after group end not rejected")
+    };
+
+    match parse("section-end-options", "[G]\n[teardown]\n[end, timeout = 1s]\n") {
+        Err(Error::File(_, error)) => assert!(matches!(*error, Error::InvalidSectionEnd(3, _)), "{}", error),
+        _ => panic!("Section end with options not rejected")
+    };
+}
+
+#[test]
+fn teardown_runs_after_setup_failure() {
+    let mut test_suites = parse("setup-failure", "[G]\n\"a\" : \"a\"\n[setup]\n\"s\" : \"x\"\n[teardown]\n\"t\" : \"t\"\n").unwrap();
+
+    let mut serial = Serial::open_with_settings("loopback", serial_unit_testing::serial::settings::Settings {
+        timeout: 100,
+        ..Default::default()
+    }).unwrap();
+
+    assert_eq!(test_suites[0].run(&mut serial), Ok(false));
+    assert!(test_suites[0].is_setup_failed());
+    assert_eq!(test_suites[0].tests()[0].is_successful(), None);
+    assert_eq!(test_suites[0].teardown()[0].is_successful(), Some(true));
+}
+
+fn test_names(test_suites: &[TestSuite]) -> Vec<String> {
+    test_suites.iter().flat_map(|test_suite| test_suite.tests().iter().map(|test| test.name().to_string())).collect()
+}
+
+fn test_inputs(test_suites: &[TestSuite]) -> Vec<String> {
+    test_suites.iter().flat_map(|test_suite| test_suite.tests().iter().map(|test| test.input().to_string())).collect()
+}
+
+#[test]
+fn range_loops() {
+    let test_suites = parse("range-loop", "for ch in 0..3\n(Read) \"r${ch}\" : \"${ch}\"\nend\n").unwrap();
+
+    assert_eq!(test_names(&test_suites), vec!["Read [ch = 0]", "Read [ch = 1]", "Read [ch = 2]"]);
+    assert_eq!(test_inputs(&test_suites), vec!["r0", "r1", "r2"]);
+
+    let test_suites = parse("hex-range-loop", "for ch in 0x0E..0x10\n\"${ch:02X}\" : \"x\"\nend\n").unwrap();
+
+    assert_eq!(test_inputs(&test_suites), vec!["0E", "0F"]);
+}
+
+#[test]
+fn range_loops_are_limited() {
+    assert!(parse("empty-range-loop", "for ch in 5..5\n\"a\" : \"a\"\nend\n").unwrap().iter().all(TestSuite::is_empty));
+    assert!(matches!(parse("reverse-range-loop", "for ch in 5..4\n\"a\" : \"a\"\nend\n"), Err(Error::File(_, error)) if *error == Error::InvalidLoop(1, 1)));
+
+    match parse("long-range-loop", "for ch in 0..18446744073709551615\n\"a\" : \"a\"\nend\n") {
+        Err(Error::File(_, error)) => assert!(matches!(*error, Error::TooManyLoopIterations(_, 1, 1))),
+        _ => panic!("Range not limited")
+    };
+}
+
+#[test]
+fn table_loops() {
+    let script = "for reg, value in (0x10, \"00FF\"), (0x11, \"0100\")\n(Write) \"${reg}=${value}\" : \"OK\"\nend\n";
+    let test_suites = parse("table-loop", script).unwrap();
+
+    assert_eq!(test_names(&test_suites), vec!["Write [reg = 0x10, value = 00FF]", "Write [reg = 0x11, value = 0100]"]);
+    assert_eq!(test_inputs(&test_suites), vec!["0x10=00FF", "0x11=0100"]);
+
+    let test_suites = parse("list-loop", "for mode in fast, \"slow, safe\"\n(Mode) \"${mode}\" : \"OK\"\nend\n").unwrap();
+
+    assert_eq!(test_inputs(&test_suites), vec!["fast", "slow, safe"]);
+}
+
+#[test]
+fn mismatched_table_loops() {
+    let scripts = [
+        "for reg, value in (0x10, \"00FF\"), (0x11)\n\"a\" : \"a\"\nend\n",
+        "for reg, value in (0x10, \"00FF\", 1)\n\"a\" : \"a\"\nend\n",
+        "for reg, value in 0x10, 0x11\n\"a\" : \"a\"\nend\n",
+        "for reg, reg in (1, 2)\n\"a\" : \"a\"\nend\n"
+    ];
+
+    for script in scripts.iter() {
+        match parse("mismatched-loop", script) {
+            Err(Error::File(_, error)) => assert_eq!(*error, Error::InvalidLoop(1, 1), "{}", script),
+            _ => panic!("Invalid loop accepted: {}", script)
+        };
+    }
+}
+
+#[test]
+fn nested_loops() {
+    let script = "for a in 0..2\nfor b in x, y\n(T) \"${a}${b}\" : \"OK\"\nend\n(U) \"${a}\" : \"OK\"\nend\n";
+    let test_suites = parse("nested-loop", script).unwrap();
+
+    assert_eq!(test_names(&test_suites), vec!["T [a = 0, b = x]", "T [a = 0, b = y]", "U [a = 0]", "T [a = 1, b = x]", "T [a = 1, b = y]", "U [a = 1]"]);
+    assert_eq!(test_inputs(&test_suites), vec!["0x", "0y", "0", "1x", "1y", "1"]);
+
+    assert!(matches!(parse("unclosed-loop", "for a in 0..2\nfor b in x, y\n\"a\" : \"a\"\nend\n"), Err(Error::File(_, error)) if *error == Error::MissingLoopEnd(1, 1)));
+    assert!(matches!(parse("unopened-loop", "\"a\" : \"a\"\nend\n"), Err(Error::File(_, error)) if *error == Error::UnexpectedLoopEnd(2, 1)));
+}
+
+#[test]
+fn control_steps() {
+    let script = "!dtr = low\n!cts = high\n!pulse rts 100ms\n!pulse rts low 100ms\n!break 250ms\n";
+    let test_suites = parse("control-steps", script).unwrap();
+    let steps: Vec<ControlStep> = test_suites[0].tests().iter().map(|test| test.control_step().unwrap().clone()).collect();
+
+    assert_eq!(steps, vec![
+        ControlStep::Set(ControlLine::DataTerminalReady, false),
+        ControlStep::Check(ControlLine::ClearToSend, true),
+        ControlStep::Pulse(ControlLine::RequestToSend, true, Duration::from_millis(100)),
+        ControlStep::Pulse(ControlLine::RequestToSend, false, Duration::from_millis(100)),
+        ControlStep::Break(Duration::from_millis(250))
+    ]);
+}
+
+#[test]
+fn named_control_steps_on_ports() {
+    let test_suites = parse("control-port", "@dut\n(Reset) !@dut pulse dtr low 10ms\n").unwrap();
+    let test = &test_suites[0].tests()[0];
+
+    assert_eq!(test.name(), "Reset");
+    assert_eq!(test.input_port.as_deref(), Some("dut"));
+    assert_eq!(test.control_step(), Some(&ControlStep::Pulse(ControlLine::DataTerminalReady, false, Duration::from_millis(10))));
+
+    assert!(matches!(parse("control-unknown-port", "@dut\n(Reset) !@other dtr = low\n"), Err(Error::File(_, _))));
+}
+
+#[test]
+fn invalid_control_steps() {
+    let scripts = ["!cts = maybe\n", "!pulse cts 100ms\n", "!pulse rts\n", "!break\n", "!toggle rts\n"];
+
+    for script in scripts.iter() {
+        match parse("invalid-control", script) {
+            Err(Error::File(_, error)) => assert!(matches!(*error, Error::InvalidControlStep(1, _)), "{}", script),
+            _ => panic!("Invalid control step accepted: {}", script)
+        };
+    }
+}
+
+#[test]
+fn send_and_expect_only_tests() {
+    let script = "(Boot) : \"Ready\\r\\n\"\n(Reset) \"RESET\\r\"\n: h\"00 FF\"\nh\"01\"\n(Wait, timeout = 10ms) : \"x\"\n(Check) \"a\" : \"a\"\n";
+    let test_suites = parse("send-expect", script).unwrap();
+    let tests = test_suites[0].tests();
+
+    let kinds: Vec<TestCaseKind> = tests.iter().map(|test| test.kind()).collect();
+
+    assert_eq!(kinds, vec![TestCaseKind::Expect, TestCaseKind::Send, TestCaseKind::Expect, TestCaseKind::Send, TestCaseKind::Expect, TestCaseKind::Check]);
+    assert_eq!((tests[0].name(), tests[0].input(), tests[0].output()), ("Boot", "", "Ready\\r\\n"));
+    assert_eq!((tests[1].name(), tests[1].input(), tests[1].output()), ("Reset", "RESET\\r", ""));
+    assert_eq!(tests[2].output(), "00 FF");
+    assert_eq!(tests[3].input(), "01");
+    assert_eq!(tests[4].settings.timeout, Some(Duration::from_millis(10)));
+
+    let routed = parse("send-expect-ports", "@a\n@b\n(Send) @a \"x\"\n(Expect) : @b \"y\"\n").unwrap();
+    let tests = routed[0].tests();
+
+    assert_eq!((tests[0].kind(), tests[0].input_port.as_deref()), (TestCaseKind::Send, Some("a")));
+    assert_eq!((tests[1].kind(), tests[1].output_port.as_deref()), (TestCaseKind::Expect, Some("b")));
+}
+
+#[test]
+fn incomplete_send_and_expect_only_tests() {
+    let scripts = ["(Boot) :\n", ":\n", "(Reset) \"a\" :\n", ": : \"a\"\n"];
+
+    for script in scripts.iter() {
+        assert!(matches!(parse("incomplete-test", script), Err(Error::File(_, _))), "{}", script);
+    }
+}
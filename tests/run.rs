@@ -0,0 +1,143 @@
+/*
+ * File: tests/run.rs
+ * Date: 17.10.2026
+ * Author: MarkAtk
+ *
+ * MIT License
+ *
+ * Copyright (c) 2026 MarkAtk
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies
+ * of the Software, and to permit persons to whom the Software is furnished to do
+ * so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+#![cfg(feature = "sut")]
+
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("sut-run-{}-{}", std::process::id(), name))
+}
+
+// run the script on the loopback port and return the exit code and the output
+fn run_script(name: &str, script: &str, args: &[&str]) -> (i32, String) {
+    run_script_on_ports(name, script, &["loopback"], args)
+}
+
+// run the script on the given ports and return the exit code and the standard and error output
+fn run_script_on_ports(name: &str, script: &str, ports: &[&str], args: &[&str]) -> (i32, String) {
+    let path = temp_path(&format!("{}.sut", name));
+    fs::write(&path, script).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_sut"))
+        .arg("run")
+        .arg(&path)
+        .args(ports)
+        .args(["--timeout", "100"])
+        .args(args)
+        .env("NO_COLOR", "1")
+        .output()
+        .unwrap();
+
+    fs::remove_file(&path).unwrap();
+
+    let mut text = String::from_utf8_lossy(&output.stdout).to_string();
+    text.push_str(&String::from_utf8_lossy(&output.stderr));
+
+    (output.status.code().unwrap(), text)
+}
+
+#[test]
+fn script_teardown_runs_after_setup_failure() {
+    let script = "[setup]\n(Init) \"s\" : \"x\"\n[teardown]\n(Reset) \"t\" : \"t\"\n[G]\n(A) \"a\" : \"a\"\n";
+
+    let (code, output) = run_script("setup-failure", script, &[]);
+
+    assert_eq!(code, 2);
+    assert!(output.contains("Stopping because setup failed"));
+    assert!(output.contains("Reset \"t\"...OK"));
+    assert!(!output.contains("A \"a\""));
+}
+
+#[test]
+fn script_teardown_runs_after_stop_on_failure() {
+    let script = "[teardown]\n(Reset) \"t\" : \"t\"\n[G]\n(A) \"a\" : \"b\"\n[H]\n(B) \"b\" : \"b\"\n";
+
+    let (code, output) = run_script("stop-on-failure", script, &["--stop-on-failure"]);
+
+    assert_eq!(code, 2);
+    assert!(output.contains("Stopping because 'stop-on-failure' is set"));
+    assert!(output.contains("Reset \"t\"...OK"));
+    assert!(!output.contains("B \"b\""));
+}
+
+#[test]
+fn several_ports_need_port_declarations() {
+    let (code, output) = run_script_on_ports("undeclared-ports", "(A) \"a\" : \"a\"\n", &["loopback", "loopback"], &[]);
+
+    assert_eq!(code, 4);
+    assert!(output.contains("Script declares no ports but 2 ports are given"));
+}
+
+#[test]
+fn declared_ports_must_match_given_ports() {
+    let script = "@a\n@b\n(A) @a \"a\" : @a \"a\"\n(B) @b \"b\" : @b \"b\"\n";
+
+    let (code, output) = run_script_on_ports("too-few-ports", script, &["loopback"], &[]);
+
+    assert_eq!(code, 4);
+    assert!(output.contains("Script declares 2 ports but 1 ports are given"));
+
+    let (code, output) = run_script_on_ports("too-many-ports", script, &["loopback", "loopback", "loopback"], &[]);
+
+    assert_eq!(code, 4);
+    assert!(output.contains("Script declares 2 ports but 3 ports are given"));
+}
+
+#[test]
+fn options_apply_to_all_ports() {
+    let rules = temp_path("all-ports.rules");
+    let recording = temp_path("all-ports.rec");
+    let second_recording = temp_path("all-ports.rec.1");
+    fs::write(&rules, "\"q\" : \"r\"\n").unwrap();
+
+    let script = "@a\n@b\n(A) @a \"q\" : @a \"r\"\n(B) @b \"q\" : @b \"r\"\n";
+    let (code, output) = run_script_on_ports("all-ports", script, &["loopback", "loopback"],
+        &["--mock", rules.to_str().unwrap(), "--record", recording.to_str().unwrap()]);
+
+    let recorded = recording.exists() && second_recording.exists();
+    fs::remove_file(&rules).unwrap();
+    let _ = fs::remove_file(&recording);
+    let _ = fs::remove_file(&second_recording);
+
+    assert_eq!(code, 0, "{}", output);
+    assert!(recorded);
+}
+
+#[test]
+fn skipped_tests_are_counted_separately() {
+    let script = "[G, if = \"defined(x)\"]\n(A) \"a\" : \"a\"\n(B) \"b\" : \"b\"\n[H]\n(C, skip-if = \"!defined(x)\") \"c\" : \"c\"\n(D) \"d\" : \"d\"\n";
+
+    let (code, output) = run_script("skipped", script, &[]);
+
+    assert_eq!(code, 0, "{}", output);
+    assert!(output.contains("Ran 1 tests, 1 successful, 0 failed"));
+    assert!(output.contains("3 tests skipped"));
+}